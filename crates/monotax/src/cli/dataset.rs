@@ -0,0 +1,75 @@
+//! Export and import of the dataset.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::Context;
+use log::info;
+use monotax_core::app::dataset::{export_dataset, import_dataset};
+use monotax_core::domain::repository::coverage::CoverageRepository;
+use monotax_core::domain::repository::income_tax::IncomeTaxRepository;
+use monotax_core::domain::repository::payment::PaymentRepository;
+use monotax_core::domain::repository::sync::SyncStateRepository;
+use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::infra::io::writer;
+use monotax_core::integration::dataset::{Dataset, DatasetFormat};
+
+pub async fn export(
+    format: DatasetFormat,
+    output: Option<&Path>,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+    payment_repository: &impl PaymentRepository,
+    coverage_repository: &impl CoverageRepository,
+    sync_repository: &impl SyncStateRepository,
+) -> anyhow::Result<()> {
+    let generator = format!("monotax {}", env!("CARGO_PKG_VERSION"));
+    let dataset = export_dataset(
+        generator,
+        income_repository,
+        income_tax_repository,
+        payment_repository,
+        coverage_repository,
+        sync_repository,
+    )
+    .await?;
+    dataset.write(format, &mut writer(output)?)?;
+    if let Some(output) = output {
+        info!(
+            "{} taxes, {} incomes and {} payments are exported to {}",
+            dataset.taxes.len(),
+            dataset.incomes.len(),
+            dataset.payments.len(),
+            output.display()
+        );
+    }
+    Ok(())
+}
+
+pub async fn import(
+    input: &Path,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+    payment_repository: &impl PaymentRepository,
+    coverage_repository: &impl CoverageRepository,
+    sync_repository: &impl SyncStateRepository,
+) -> anyhow::Result<()> {
+    let file = File::open(input).context("opening input file")?;
+    let dataset = Dataset::read(BufReader::new(file))
+        .with_context(|| format!("failed to read dataset {}", input.display()))?;
+    let imported = import_dataset(
+        &dataset,
+        income_repository,
+        income_tax_repository,
+        payment_repository,
+        coverage_repository,
+        sync_repository,
+    )
+    .await?;
+    info!(
+        "{} taxes, {} incomes, {} payments and {} coverages are imported, {} incomes are already saved",
+        imported.taxes, imported.incomes, imported.payments, imported.coverages, imported.skipped_incomes
+    );
+    Ok(())
+}
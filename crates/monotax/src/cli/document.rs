@@ -0,0 +1,162 @@
+//! Documents issued to banks and other institutions.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
+use clap::{Subcommand, ValueEnum};
+use log::{info, warn};
+use monotax_core::app::declaration::generate_declaration_report;
+use monotax_core::app::income_certificate::generate_income_certificate;
+use monotax_core::domain::repository::income_tax::IncomeTaxRepository;
+use monotax_core::domain::repository::payment::PaymentRepository;
+use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Quarter;
+use monotax_core::infra::io::writer;
+use monotax_core::report::income_certificate::{CertificateFormat, Taxpayer};
+use monotax_dps::configuration::DpsConfiguration;
+use monotax_dps::payment::payment_orders;
+
+use crate::config::Configuration;
+
+#[derive(Debug, Subcommand)]
+pub enum DocumentCommands {
+    /// Write the certificate of monthly income and taxes of the period for banks and visas
+    IncomeCertificate {
+        /// First day of the period.
+        #[clap(long)]
+        from: NaiveDate,
+        /// Last day of the period. Defaults to today.
+        #[clap(long)]
+        to: Option<NaiveDate>,
+        #[clap(long, value_enum, default_value_t)]
+        format: CertificateFormat,
+        /// Output file. Required for PDF.
+        #[clap(short, long, required_if_eq("format", "pdf"))]
+        output: Option<PathBuf>,
+    },
+    /// Write payment orders of taxes declared for the quarter and not paid yet for import into the bank
+    PaymentOrders {
+        /// Year of the declaration. Defaults to the current year.
+        #[clap(short, long)]
+        year: Option<i32>,
+        /// Quarter of the declaration. Defaults to the current quarter.
+        #[clap(short, long, value_enum)]
+        quarter: Option<Quarter>,
+        /// Date of the payments. Defaults to today.
+        #[clap(long)]
+        date: Option<NaiveDate>,
+        #[clap(long, value_enum, default_value_t)]
+        format: PaymentOrderFormat,
+        /// Output file for payment orders
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Format of payment orders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum PaymentOrderFormat {
+    /// DBOsoft import CSV.
+    #[default]
+    Dbo,
+    /// ISO 20022 credit transfer initiation.
+    Pain001,
+}
+
+pub async fn handle_document(
+    command: &DocumentCommands,
+    config: &Configuration,
+    income_repo: &mut impl IncomeRepository,
+    income_tax_repo: &impl IncomeTaxRepository,
+    payment_repo: &impl PaymentRepository,
+) -> Result<()> {
+    match command {
+        DocumentCommands::IncomeCertificate {
+            from,
+            to,
+            format,
+            output,
+        } => {
+            let today = Local::now().date_naive();
+            let to = to.unwrap_or(today);
+            let certificate = generate_income_certificate(
+                taxpayer(config.dps())?,
+                *from,
+                to,
+                today,
+                income_repo,
+                income_tax_repo,
+                payment_repo,
+            )
+            .await?;
+            let mut output_writer = writer(output.as_deref())?;
+            match format {
+                CertificateFormat::Html => {
+                    certificate.write_html(&config.templates()?, &mut output_writer)?
+                }
+                _ => certificate.write(*format, &mut output_writer)?,
+            }
+            if let Some(output) = output {
+                info!(
+                    "income certificate of {} - {} is written to {}",
+                    from,
+                    to,
+                    output.display()
+                );
+            }
+            Ok(())
+        }
+        DocumentCommands::PaymentOrders {
+            year,
+            quarter,
+            date,
+            format,
+            output,
+        } => {
+            let year = year.unwrap_or_else(|| Local::now().year());
+            let quarter = quarter.unwrap_or_else(Quarter::current);
+            let date = date.unwrap_or_else(|| Local::now().date_naive());
+            let report =
+                generate_declaration_report(year, quarter, income_repo, income_tax_repo).await?;
+            let payments = payment_repo.find_all().await?;
+            let orders = payment_orders(&report, &payments, config.dps(), date)?;
+            if orders.is_empty() {
+                warn!("no taxes left to pay for {} {}", quarter, year);
+                return Ok(());
+            }
+            let mut output_writer = writer(output.as_deref())?;
+            match format {
+                PaymentOrderFormat::Dbo => {
+                    monotax_dbo::payment::write_payment_orders(&orders, &mut output_writer)?
+                }
+                PaymentOrderFormat::Pain001 => {
+                    let xml = monotax_iso20022::pain::write_credit_transfers(
+                        &orders,
+                        Local::now().naive_local(),
+                    )?;
+                    output_writer.write_all(xml.as_bytes())?;
+                }
+            }
+            output_writer.flush()?;
+            info!("{} payment orders are written", orders.len());
+            Ok(())
+        }
+    }
+}
+
+/// Entrepreneur of the DPS configuration. Certificates without the name and the number
+/// of the taxpayer are useless.
+fn taxpayer(config: &DpsConfiguration) -> Result<Taxpayer> {
+    if config.name.is_empty() || config.tin.is_empty() {
+        anyhow::bail!("the name and the tin of the taxpayer are not set in the dps configuration");
+    }
+    Ok(Taxpayer {
+        name: config.name.clone(),
+        tin: config.tin.clone(),
+        address: config.address.clone(),
+        phone: config.phone.clone(),
+        email: config.email.clone(),
+    })
+}
@@ -1,50 +1,111 @@
 //! Handlers for cli app requests.
 
-use std::{fs::File, path::Path};
+use std::path::Path;
 
-use anyhow::Context;
+use chrono::{Datelike, Local};
+use log::warn;
 use monotax_core::app::income::read_incomes;
-use monotax_dbo::dbo;
-use tokio::task::block_in_place;
+use monotax_core::app::payment::find_payments;
 
+use monotax_core::domain::repository::coverage::CoverageRepository;
+use monotax_core::domain::repository::payment::PaymentRepository;
 use monotax_core::domain::repository::IncomeRepository;
-use monotax_core::domain::Income;
+use monotax_core::domain::{Income, Quarter};
 use monotax_core::infra::io::writer;
-use monotax_core::integration::taxer;
+use monotax_core::integration::taxer::{self, TaxerPayment};
 
-use crate::config;
+use crate::config::Configuration;
 
 use super::filter::FilterArgs;
+use super::opts::{TaxerArgs, TaxerCommands};
+use super::report::warn_about_coverage_gaps;
+use super::statement::incomes_from_statement;
+
+pub async fn handle_taxer(
+    args: &TaxerArgs,
+    config: &Configuration,
+    income_repo: &mut impl IncomeRepository,
+    payment_repo: &impl PaymentRepository,
+    coverage_repo: &impl CoverageRepository,
+) -> anyhow::Result<()> {
+    match &args.command {
+        Some(command) => generate_taxer_payments(command, config, payment_repo).await,
+        None => generate_taxer_report(args, config, income_repo, coverage_repo).await,
+    }
+}
 
 pub async fn generate_taxer_report(
+    args: &TaxerArgs,
+    config: &Configuration,
     income_repo: &mut impl IncomeRepository,
-    input: Option<&Path>,
-    output: Option<&Path>,
-    filter: &FilterArgs,
+    coverage_repo: &impl CoverageRepository,
 ) -> anyhow::Result<()> {
-    let config = config::load_config()?;
-    let incomes = read_incomes_from_file_or_db(income_repo, input, filter).await?;
-    let writer = writer(output)?;
-    taxer::export_csv(incomes, config.taxer(), writer)?;
+    let input = args.input.as_deref();
+    if input.is_none() {
+        warn_about_coverage_gaps(&args.filter.criteria(), coverage_repo).await?;
+    }
+    let taxer_config = args.layout.apply(config.taxer());
+    let mut incomes =
+        read_incomes_from_file_or_db(config, income_repo, input, &args.filter).await?;
+    let refunds = incomes.iter().filter(|income| income.is_refund()).count();
+    if refunds > 0 {
+        warn!(
+            "{} refunds are not exported. record them in Taxer manually",
+            refunds
+        );
+    }
+    if let Some(account) = &args.account {
+        incomes = taxer::select_account(incomes, &taxer_config, account)?;
+    }
+    let writer = writer(args.output.as_deref())?;
+    taxer::export_csv(incomes, &taxer_config, writer)?;
     Ok(())
 }
 
+/// Exports tax payments recorded in the quarter.
+/// The tax office from the DPS configuration is the counterparty.
+pub async fn generate_taxer_payments(
+    command: &TaxerCommands,
+    config: &Configuration,
+    payment_repo: &impl PaymentRepository,
+) -> anyhow::Result<()> {
+    let TaxerCommands::Payments {
+        year,
+        quarter,
+        output,
+        template,
+    } = command;
+    let year = year.unwrap_or_else(|| Local::now().year());
+    let quarter = quarter.unwrap_or_else(Quarter::current);
+    let payments = find_payments(
+        quarter.first_day(year),
+        quarter.last_day(year),
+        payment_repo,
+    )
+    .await?
+    .iter()
+    .map(|payment| TaxerPayment::from_payment(payment, &config.dps().tax_office))
+    .collect::<Vec<_>>();
+    if payments.is_empty() {
+        warn!(
+            "no payments recorded in {} {}. record them with `payments add`",
+            quarter, year
+        );
+    }
+    let template = template.unwrap_or(config.taxer().template());
+    let taxer_config = config.taxer().clone().with_template(template);
+    taxer::export_payments_csv(&payments, &taxer_config, writer(output.as_deref())?)
+}
+
 async fn read_incomes_from_file_or_db(
+    config: &Configuration,
     income_repo: &mut impl IncomeRepository,
     input: Option<&Path>,
     filter: &FilterArgs,
 ) -> anyhow::Result<Vec<Income>> {
     let incomes = match input {
-        Some(statement) => incomes_from_dbo_file(statement, filter).await?,
+        Some(statement) => incomes_from_statement(config, statement, None, filter).await?,
         None => read_incomes(filter.criteria(), income_repo).await?,
     };
     Ok(incomes)
 }
-
-async fn incomes_from_dbo_file(input: &Path, filter: &FilterArgs) -> anyhow::Result<Vec<Income>> {
-    let incomes = block_in_place(move || {
-        let file = File::open(input).context("opening input file")?;
-        dbo::read_incomes(file, filter.criteria())
-    })?;
-    Ok(incomes)
-}
@@ -0,0 +1,89 @@
+//! Manage batches of imported incomes.
+
+use anyhow::Context;
+use clap::Subcommand;
+use log::info;
+use monotax_core::domain::model::import_batch::{BatchID, ImportBatch};
+use monotax_core::domain::repository::import_batch::ImportBatchRepository;
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommands {
+    /// List all import batches.
+    List,
+    /// Show the import batch and incomes it saved.
+    Show {
+        /// Identifier of the batch.
+        id: BatchID,
+    },
+    /// Remove incomes saved by the import batch.
+    Revert {
+        /// Identifier of the batch.
+        id: BatchID,
+    },
+}
+
+pub async fn process_imports(
+    command: &ImportCommands,
+    batch_repository: &impl ImportBatchRepository,
+) -> anyhow::Result<()> {
+    match command {
+        ImportCommands::List => {
+            for batch in batch_repository.find_all().await? {
+                println!("{}", batch_summary(&batch));
+            }
+            Ok(())
+        }
+        ImportCommands::Show { id } => {
+            let batch = find_batch(batch_repository, *id).await?;
+            println!("{}", batch_summary(&batch));
+            println!("sha256: {}", batch.source().sha256());
+            for income in batch_repository.find_incomes(*id).await? {
+                println!(
+                    "{}\t{}\t{:.2}\t{}\t{}",
+                    income.income_no(),
+                    income.datetime(),
+                    income.amount().amount(),
+                    income.currency(),
+                    income.comment().unwrap_or_default()
+                );
+            }
+            Ok(())
+        }
+        ImportCommands::Revert { id } => {
+            let batch = find_batch(batch_repository, *id).await?;
+            if batch.is_reverted() {
+                anyhow::bail!("import batch {} is already reverted", id);
+            }
+            let removed = batch_repository.revert(*id).await?;
+            info!("removed {} incomes of import batch {}", removed, id);
+            Ok(())
+        }
+    }
+}
+
+async fn find_batch(
+    batch_repository: &impl ImportBatchRepository,
+    id: BatchID,
+) -> anyhow::Result<ImportBatch> {
+    batch_repository
+        .find_by_id(id)
+        .await?
+        .with_context(|| format!("import batch {} not found", id))
+}
+
+fn batch_summary(batch: &ImportBatch) -> String {
+    let status = match batch.reverted_at() {
+        Some(reverted_at) => format!("reverted at {}", reverted_at.format("%Y-%m-%d %H:%M:%S")),
+        None => "active".to_string(),
+    };
+    format!(
+        "{}\t{}\t{}\t{}\t{}/{} imported\t{}",
+        batch.id(),
+        batch.imported_at().format("%Y-%m-%d %H:%M:%S"),
+        batch.source().name(),
+        batch.source().format(),
+        batch.imported_rows(),
+        batch.total_rows(),
+        status
+    )
+}
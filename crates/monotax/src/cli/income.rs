@@ -1,55 +1,340 @@
-use std::{
-    fs::File,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
-use anyhow::Context;
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, Utc};
 use clap::Subcommand;
+use log::info;
 use monotax_core::{
-    app::income::import_incomes,
-    domain::{repository::IncomeRepository, Income},
+    app::income::{
+        add_income, delete_income, edit_income, import_statement, read_incomes, IncomeChanges,
+    },
+    app::income_book::generate_income_book,
+    domain::model::income::{Amount, IncomeKind},
+    domain::repository::{
+        coverage::CoverageRepository, import_batch::ImportBatchRepository,
+        sync::SyncStateRepository, IncomeRepository,
+    },
+    domain::{Currency, Income},
+    infra::io::writer,
+    report::income_book::BookFormat,
+    report::income_list::{IncomeList, IncomeSort, ListFormat},
 };
-use monotax_dbo::dbo;
-use tokio::task::block_in_place;
+use monotax_csv::profile::FORMAT_PREFIX;
+use monotax_monobank::client::MonobankClient;
+use monotax_monobank::statement::MonobankStatementReader;
+use monotax_monobank::sync::sync_account;
+
+use crate::config::Configuration;
 
 use super::filter::FilterArgs;
+use super::statement::read_statement_file;
 
 #[derive(Debug, Subcommand)]
 pub enum IncomeCommands {
+    /// List incomes stored in the database.
+    List {
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[clap(long, value_enum, default_value_t)]
+        sort: IncomeSort,
+        /// Sort in descending order.
+        #[clap(long)]
+        reverse: bool,
+        #[clap(long, value_enum, default_value_t)]
+        format: ListFormat,
+    },
+    /// Write the income book of the year with numbered entries, daily and running totals.
+    Book {
+        /// Year of the book. Defaults to the current year.
+        #[clap(short, long)]
+        year: Option<i32>,
+        #[clap(long, value_enum, default_value_t)]
+        format: BookFormat,
+        /// File to write the book to. Required for XLSX and PDF.
+        #[clap(short, long, required_if_eq_any([("format", "xlsx"), ("format", "pdf")]))]
+        output: Option<PathBuf>,
+    },
+    /// Import incomes from a bank statement file.
+    Import {
+        statement_file: PathBuf,
+        /// Statement format. Detected from the file content if omitted.
+        #[clap(long)]
+        format: Option<String>,
+        /// Name of the user-defined CSV profile from the configuration.
+        #[clap(long, conflicts_with = "format")]
+        profile: Option<String>,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
     /// Import incomes from DBOSoft banking statement file.
     ImportDbo {
         statement_file: PathBuf,
         #[command(flatten)]
         filter: FilterArgs,
     },
+    /// Synchronize incomes with Monobank accounts via the personal API.
+    Sync {
+        /// Account to synchronize. All configured accounts are synchronized if omitted.
+        #[clap(long)]
+        account: Option<String>,
+        /// Start date for accounts that were never synchronized.
+        /// Defaults to the start of the current year.
+        #[clap(long)]
+        since: Option<NaiveDate>,
+    },
+    /// Add an income that isn't in any statement.
+    Add {
+        /// Date of the income as YYYY-MM-DD, optionally with HH:MM[:SS] time.
+        #[clap(long, value_parser = parse_date_time)]
+        date: NaiveDateTime,
+        #[clap(long, value_parser = parse_amount)]
+        amount: Amount,
+        #[clap(long, default_value_t)]
+        currency: Currency,
+        #[clap(long)]
+        description: Option<String>,
+        /// Kind of the income book entry.
+        #[clap(long, value_enum, default_value_t)]
+        kind: IncomeKind,
+    },
+    /// Correct the income with the payment number.
+    Edit {
+        payment_no: i64,
+        /// Date of the income as YYYY-MM-DD, optionally with HH:MM[:SS] time.
+        #[clap(long, value_parser = parse_date_time)]
+        date: Option<NaiveDateTime>,
+        #[clap(long, value_parser = parse_amount)]
+        amount: Option<Amount>,
+        #[clap(long)]
+        currency: Option<Currency>,
+        #[clap(long)]
+        description: Option<String>,
+        /// Kind of the income book entry.
+        #[clap(long, value_enum)]
+        kind: Option<IncomeKind>,
+    },
+    /// Delete the income with the payment number.
+    Delete { payment_no: i64 },
 }
 
 pub async fn process_incomes(
     command: &IncomeCommands,
+    config: &Configuration,
     income_repository: &mut impl IncomeRepository,
+    sync_repository: &impl SyncStateRepository,
+    batch_repository: &impl ImportBatchRepository,
+    coverage_repository: &impl CoverageRepository,
 ) -> anyhow::Result<()> {
     match command {
+        IncomeCommands::List {
+            filter,
+            sort,
+            reverse,
+            format,
+        } => {
+            let incomes = read_incomes(filter.criteria(), income_repository).await?;
+            IncomeList::new(incomes)
+                .sorted(*sort, *reverse)
+                .write(*format, writer(None)?)
+        }
+        IncomeCommands::Book {
+            year,
+            format,
+            output,
+        } => {
+            let year = year.unwrap_or_else(|| Local::now().year());
+            let book = generate_income_book(year, income_repository).await?;
+            let mut output_writer = writer(output.as_deref())?;
+            match format {
+                BookFormat::Html => book.write_html(&config.templates()?, &mut output_writer)?,
+                _ => book.write(*format, &mut output_writer)?,
+            }
+            if let Some(output) = output {
+                info!(
+                    "income book of {} with {} entries is written to {}",
+                    year,
+                    book.entries().len(),
+                    output.display()
+                );
+            }
+            Ok(())
+        }
+        IncomeCommands::Import {
+            statement_file,
+            format,
+            profile,
+            filter,
+        } => {
+            let format = match profile {
+                Some(profile) => Some(format!("{}{}", FORMAT_PREFIX, profile)),
+                None => format.clone(),
+            };
+            import_incomes_from_statement(
+                config,
+                batch_repository,
+                coverage_repository,
+                statement_file,
+                format.as_deref(),
+                filter,
+            )
+            .await
+        }
         IncomeCommands::ImportDbo {
             statement_file,
             filter,
-        } => import_incomes_from_dbo_csv(income_repository, statement_file, filter).await,
+        } => {
+            import_incomes_from_statement(
+                config,
+                batch_repository,
+                coverage_repository,
+                statement_file,
+                Some("dbo"),
+                filter,
+            )
+            .await
+        }
+        IncomeCommands::Sync { account, since } => {
+            sync_monobank_incomes(
+                config,
+                income_repository,
+                sync_repository,
+                coverage_repository,
+                account.as_deref(),
+                *since,
+            )
+            .await
+        }
+        IncomeCommands::Add {
+            date,
+            amount,
+            currency,
+            description,
+            kind,
+        } => {
+            let mut income = Income::new(*date, *amount)
+                .with_currency(*currency)
+                .with_kind(*kind);
+            if let Some(description) = description {
+                income = income.with_comment(description.clone());
+            }
+            let _ = add_income(income, income_repository).await?;
+            Ok(())
+        }
+        IncomeCommands::Edit {
+            payment_no,
+            date,
+            amount,
+            currency,
+            description,
+            kind,
+        } => {
+            let changes = IncomeChanges {
+                date: *date,
+                amount: *amount,
+                currency: *currency,
+                comment: description.clone(),
+                kind: *kind,
+            };
+            let _ = edit_income(*payment_no, changes, income_repository).await?;
+            Ok(())
+        }
+        IncomeCommands::Delete { payment_no } => {
+            delete_income(*payment_no, income_repository).await
+        }
     }
 }
 
-async fn import_incomes_from_dbo_csv(
-    income_repo: &mut impl IncomeRepository,
+async fn import_incomes_from_statement(
+    config: &Configuration,
+    batch_repo: &impl ImportBatchRepository,
+    coverage_repo: &impl CoverageRepository,
     statement: &Path,
+    format: Option<&str>,
     filter: &FilterArgs,
 ) -> anyhow::Result<()> {
-    let incomes = incomes_from_dbo_file(statement, filter).await?;
-    let _ = import_incomes(incomes, income_repo).await?;
+    let statement = read_statement_file(config, statement, format, filter).await?;
+    let _ = import_statement(
+        statement.source,
+        statement.incomes,
+        &statement.coverages,
+        batch_repo,
+        coverage_repo,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn sync_monobank_incomes(
+    config: &Configuration,
+    income_repo: &mut impl IncomeRepository,
+    sync_repo: &impl SyncStateRepository,
+    coverage_repo: &impl CoverageRepository,
+    account: Option<&str>,
+    since: Option<NaiveDate>,
+) -> anyhow::Result<()> {
+    let monobank = config.monobank();
+    if monobank.token.is_empty() {
+        anyhow::bail!("monobank token is not configured. set it in the [monobank] section");
+    }
+    let accounts = match account {
+        Some(account) => vec![account.to_string()],
+        None => monobank.accounts.clone(),
+    };
+    if accounts.is_empty() {
+        anyhow::bail!("no monobank accounts to synchronize");
+    }
+    let since = since
+        .unwrap_or_else(|| NaiveDate::from_ymd_opt(Local::now().year(), 1, 1).unwrap())
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    let until = Utc::now();
+    let mut client = MonobankClient::new(&monobank.base_url, &monobank.token);
+    let reader = MonobankStatementReader::with_own_accounts(monobank.own_accounts.clone());
+    for account in accounts {
+        let synced = sync_account(
+            &mut client,
+            &reader,
+            &account,
+            since,
+            until,
+            income_repo,
+            sync_repo,
+        )
+        .await?;
+        info!(
+            "imported {} incomes from account {}",
+            synced.imported, account
+        );
+        if let Some(coverage) = synced.coverage {
+            coverage_repo.save_all(&[coverage], None).await?;
+        }
+    }
     Ok(())
 }
 
-async fn incomes_from_dbo_file(input: &Path, filter: &FilterArgs) -> anyhow::Result<Vec<Income>> {
-    let incomes = block_in_place(move || {
-        let file = File::open(input).context("opening input file")?;
-        dbo::read_incomes(file, filter.criteria())
-    })?;
-    Ok(incomes)
+/// Parses the date with an optional time. Incomes without time are dated by midnight.
+fn parse_date_time(raw: &str) -> Result<NaiveDateTime, String> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ];
+    FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("invalid date {}. expected YYYY-MM-DD [HH:MM[:SS]]", raw))
+}
+
+pub(super) fn parse_amount(raw: &str) -> Result<Amount, String> {
+    let amount: f64 = raw
+        .replace(',', ".")
+        .parse()
+        .map_err(|_| format!("invalid amount {}", raw))?;
+    Amount::new(amount).map_err(|e| e.to_string())
 }
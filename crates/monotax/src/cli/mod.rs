@@ -1,7 +1,13 @@
+pub mod dataset;
+pub mod document;
 pub mod filter;
 pub mod handler;
+pub mod import;
 pub mod income;
 pub mod opts;
-pub mod router;
+pub mod payment;
 pub mod report;
+pub mod router;
+pub mod statement;
+pub mod watch;
 pub use opts::Cli;
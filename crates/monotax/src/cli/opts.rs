@@ -1,8 +1,14 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use monotax_core::domain::Quarter;
+use monotax_core::integration::dataset::DatasetFormat;
+use monotax_core::integration::taxer::{TaxerImportConfig, TaxerTemplate};
 
-use super::{filter::FilterArgs, income::IncomeCommands, report::ReportCommands};
+use super::{
+    document::DocumentCommands, filter::FilterArgs, import::ImportCommands, income::IncomeCommands,
+    payment::PaymentCommands, report::ReportCommands,
+};
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about)]
@@ -24,19 +30,112 @@ pub enum Command {
         #[clap(subcommand)]
         command: IncomeCommands,
     },
+    /// Review and revert imports of statement files
+    Imports {
+        #[clap(subcommand)]
+        command: ImportCommands,
+    },
+    /// Record tax payments
+    Payments {
+        #[clap(subcommand)]
+        command: PaymentCommands,
+    },
     /// Generate reports
     Reports {
         #[clap(subcommand)]
         command: ReportCommands,
     },
+    /// Write documents for banks and other institutions
+    Documents {
+        #[clap(subcommand)]
+        command: DocumentCommands,
+    },
     /// Export statement csv to taxer csv
-    Taxer {
-        /// Input file to export. If specified, the database is ignored.
-        input: Option<PathBuf>,
+    Taxer(TaxerArgs),
+    /// Export incomes, taxes and payments as a versioned dataset
+    Export {
+        /// Format of the dataset
+        #[clap(short, long, value_enum, default_value_t)]
+        format: DatasetFormat,
+        /// Output file. If not specified, the dataset is printed.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import incomes, taxes and payments of a dataset written by export
+    Import {
+        /// Dataset file in JSON or NDJSON
+        input: PathBuf,
+    },
+    /// Watch a directory and import statement files dropped into it
+    Watch {
+        /// Inbox directory with statement files
+        dir: PathBuf,
+        /// Seconds between checks of the directory
+        #[clap(long, default_value_t = 5)]
+        interval: u64,
+        /// Import files that are in the directory and exit
+        #[clap(long)]
+        once: bool,
+    },
+}
+
+#[derive(Debug, Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct TaxerArgs {
+    #[clap(subcommand)]
+    pub command: Option<TaxerCommands>,
+    /// Input file to export. If specified, the database is ignored.
+    pub input: Option<PathBuf>,
+    /// Output file for taxer csv
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+    #[command(flatten)]
+    pub layout: TaxerLayoutArgs,
+    /// Export only incomes of the Taxer account.
+    #[clap(long)]
+    pub account: Option<String>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TaxerCommands {
+    /// Export tax payments recorded in the quarter as Taxer expenses
+    Payments {
+        /// Year of the payments. Defaults to the current year.
+        #[clap(short, long)]
+        year: Option<i32>,
+        /// Quarter of the payments. Defaults to the current quarter.
+        #[clap(short, long, value_enum)]
+        quarter: Option<Quarter>,
         /// Output file for taxer csv
         #[clap(short, long)]
         output: Option<PathBuf>,
-        #[command(flatten)]
-        filter: FilterArgs,
+        /// Layout of the file. Defaults to the configured template.
+        /// The header row is always written.
+        #[clap(long, value_enum)]
+        template: Option<TaxerTemplate>,
     },
 }
+
+/// Layout of Taxer files overriding the configuration.
+#[derive(Debug, Args)]
+pub struct TaxerLayoutArgs {
+    /// Layout of the file. Defaults to the configured template.
+    #[clap(long, value_enum)]
+    template: Option<TaxerTemplate>,
+    /// Write the header row.
+    #[clap(long)]
+    header: bool,
+}
+
+impl TaxerLayoutArgs {
+    /// Configuration with the layout of the arguments.
+    pub fn apply(&self, config: &TaxerImportConfig) -> TaxerImportConfig {
+        let template = self.template.unwrap_or(config.template());
+        config
+            .clone()
+            .with_template(template)
+            .with_header(self.header || config.header())
+    }
+}
@@ -0,0 +1,64 @@
+//! Record taxes paid to the budget.
+
+use chrono::NaiveDate;
+use clap::Subcommand;
+use monotax_core::app::payment::{add_payment, delete_payment};
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::payment::{PaymentID, TaxPayment};
+use monotax_core::domain::repository::income_tax::IncomeTaxRepository;
+use monotax_core::domain::repository::payment::PaymentRepository;
+
+use super::income::parse_amount;
+
+#[derive(Debug, Subcommand)]
+pub enum PaymentCommands {
+    /// Record the tax payment made from a bank account.
+    Add {
+        /// Date of the payment as YYYY-MM-DD.
+        #[clap(long)]
+        date: NaiveDate,
+        /// Amount paid in UAH.
+        #[clap(long, value_parser = parse_amount)]
+        amount: Amount,
+        /// Name of the income tax, like ЄП.
+        #[clap(long)]
+        tax: Option<String>,
+    },
+    /// List all recorded payments.
+    List,
+    /// Delete the payment.
+    Delete {
+        /// Identifier of the payment.
+        id: PaymentID,
+    },
+}
+
+pub async fn process_payments(
+    command: &PaymentCommands,
+    payment_repository: &impl PaymentRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+) -> anyhow::Result<()> {
+    match command {
+        PaymentCommands::Add { date, amount, tax } => {
+            let mut payment = TaxPayment::new(*date, *amount);
+            if let Some(tax) = tax {
+                payment = payment.with_tax(tax.clone());
+            }
+            add_payment(payment, payment_repository, income_tax_repository).await?;
+            Ok(())
+        }
+        PaymentCommands::List => {
+            for payment in payment_repository.find_all().await? {
+                println!(
+                    "{}\t{}\t{:.2}\t{}",
+                    payment.id(),
+                    payment.date(),
+                    payment.amount().amount(),
+                    payment.tax().unwrap_or_default()
+                );
+            }
+            Ok(())
+        }
+        PaymentCommands::Delete { id } => delete_payment(*id, payment_repository).await,
+    }
+}
@@ -1,9 +1,33 @@
-use super::filter::FilterArgs;
-use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local};
 use clap::Subcommand;
+use log::{info, warn};
 use monotax_core::app::balance::generate_balance_report;
+use monotax_core::app::coverage::find_coverage_gaps;
+use monotax_core::app::declaration::{declaration_criteria, generate_declaration_report};
+use monotax_core::app::income::read_incomes;
+use monotax_core::app::workbook::generate_report_workbook;
+use monotax_core::domain::filter::income::IncomeCriteria;
+use monotax_core::domain::repository::coverage::CoverageRepository;
 use monotax_core::domain::repository::income_tax::IncomeTaxRepository;
+use monotax_core::domain::repository::payment::PaymentRepository;
 use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Quarter;
+use monotax_core::infra::io::writer;
+use monotax_core::report::declaration::DeclarationReport;
+use monotax_core::report::reconciliation::Reconciliation;
+use monotax_core::report::render::{ReportFormat, ReportRenderer};
+use monotax_dps::configuration::DpsConfiguration;
+use monotax_dps::declaration::{DeclarationKind, SingleTaxDeclaration};
+use monotax_ledger::journal::{Journal, JournalFormat};
+
+use crate::config::Configuration;
+
+use super::filter::FilterArgs;
+use super::statement::read_statement_file;
 
 #[derive(Debug, Subcommand)]
 pub enum ReportCommands {
@@ -11,20 +35,196 @@ pub enum ReportCommands {
     Balance {
         #[command(flatten)]
         filter: FilterArgs,
+        #[clap(long, value_enum, default_value_t)]
+        format: ReportFormat,
+        /// Output file. If not specified, the report is printed.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Summarize the quarterly declaration with income and taxes from the start of the year
+    Declaration {
+        /// Year of the declaration. Defaults to the current year.
+        #[clap(short, long)]
+        year: Option<i32>,
+        /// Quarter of the declaration. Defaults to the current quarter.
+        #[clap(short, long, value_enum)]
+        quarter: Option<Quarter>,
+        #[clap(long, value_enum, default_value_t)]
+        format: ReportFormat,
+        /// Output file. If not specified, the report is printed.
+        #[clap(short, long, conflicts_with = "xml")]
+        output: Option<PathBuf>,
+        /// Write the declaration as DPS XML to the file.
+        /// Directories get a file named as the electronic cabinet expects.
+        #[clap(long)]
+        xml: Option<PathBuf>,
+        /// Kind of the XML declaration.
+        #[clap(long, value_enum, default_value_t)]
+        kind: DeclarationKind,
+    },
+    /// Write incomes, the balance and quarterly totals to an Excel workbook
+    Workbook {
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Output xlsx file
+        #[clap(short, long)]
+        output: PathBuf,
+    },
+    /// Export incomes, taxes and tax payments as a journal of plain-text accounting tools
+    Journal {
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[clap(long, value_enum, default_value_t)]
+        format: JournalFormat,
+        /// Output file. If not specified, the journal is printed.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare incomes exported from Taxer with incomes in the database
+    Reconcile {
+        /// Taxer CSV file with incomes.
+        taxer_file: PathBuf,
+        /// Period to compare.
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[clap(long, value_enum, default_value_t)]
+        format: ReportFormat,
     },
 }
 
 pub async fn handle_report(
     command: &ReportCommands,
+    config: &Configuration,
     income_repo: &mut impl IncomeRepository,
     income_tax_repo: &impl IncomeTaxRepository,
+    payment_repo: &impl PaymentRepository,
+    coverage_repo: &impl CoverageRepository,
 ) -> Result<()> {
     match command {
-        ReportCommands::Balance { filter } => {
+        ReportCommands::Balance {
+            filter,
+            format,
+            output,
+        } => {
             let criteria = filter.criteria();
+            warn_about_coverage_gaps(&criteria, coverage_repo).await?;
             let report = generate_balance_report(criteria, income_repo, income_tax_repo).await?;
-            println!("{:?}", report);
+            render(&report, *format, config, output.as_deref())
+        }
+        ReportCommands::Declaration {
+            year,
+            quarter,
+            format,
+            output,
+            xml,
+            kind,
+        } => {
+            let year = year.unwrap_or_else(|| Local::now().year());
+            let quarter = quarter.unwrap_or_else(Quarter::current);
+            warn_about_coverage_gaps(&declaration_criteria(year, quarter), coverage_repo).await?;
+            let report =
+                generate_declaration_report(year, quarter, income_repo, income_tax_repo).await?;
+            match xml {
+                Some(path) => write_dps_declaration(&report, config.dps(), *kind, path),
+                None => render(&report, *format, config, output.as_deref()),
+            }
+        }
+        ReportCommands::Workbook { filter, output } => {
+            let criteria = filter.criteria();
+            warn_about_coverage_gaps(&criteria, coverage_repo).await?;
+            let workbook = generate_report_workbook(criteria, income_repo, income_tax_repo).await?;
+            workbook.write(&mut writer(Some(output))?)?;
+            info!(
+                "workbook with {} incomes is written to {}",
+                workbook.incomes().len(),
+                output.display()
+            );
             Ok(())
         }
+        ReportCommands::Journal {
+            filter,
+            format,
+            output,
+        } => {
+            let criteria = filter.criteria();
+            let incomes = read_incomes(criteria.clone(), income_repo).await?;
+            let mut payments = payment_repo.find_all().await?;
+            payments.retain(|payment| criteria.filter(&payment.date()));
+            let taxes = income_tax_repo.find_all().await?;
+            let journal = Journal::new(incomes, &payments, &taxes, config.ledger())?;
+            journal.write(*format, &mut writer(output.as_deref())?)?;
+            if let Some(output) = output {
+                info!(
+                    "{} journal with {} transactions is written to {}",
+                    format,
+                    journal.transactions().len(),
+                    output.display()
+                );
+            }
+            Ok(())
+        }
+        ReportCommands::Reconcile {
+            taxer_file,
+            filter,
+            format,
+        } => {
+            let taxer = read_statement_file(config, taxer_file, Some("taxer"), filter).await?;
+            let incomes = read_incomes(filter.criteria(), income_repo).await?;
+            let reconciliation = Reconciliation::new(taxer.incomes, incomes);
+            if reconciliation.is_reconciled() {
+                info!("all {} incomes match", reconciliation.matched());
+            }
+            render(&reconciliation, *format, config, None)
+        }
+    }
+}
+
+/// Writes the report to the file or prints it. HTML pages use the configured templates.
+fn render(
+    report: &impl ReportRenderer,
+    format: ReportFormat,
+    config: &Configuration,
+    output: Option<&Path>,
+) -> Result<()> {
+    let mut output_writer = writer(output)?;
+    match format {
+        ReportFormat::Html => report.render_html(&config.templates()?, &mut output_writer)?,
+        _ => report.render(format, &mut output_writer)?,
+    }
+    if let Some(output) = output {
+        info!("{} is written to {}", report.title(), output.display());
+    }
+    Ok(())
+}
+
+/// Writes the declaration in windows-1251 as DPS expects.
+fn write_dps_declaration(
+    report: &DeclarationReport,
+    config: &DpsConfiguration,
+    kind: DeclarationKind,
+    path: &Path,
+) -> Result<()> {
+    let declaration =
+        SingleTaxDeclaration::new(report, config, Local::now().date_naive())?.with_kind(kind);
+    let path = if path.is_dir() {
+        path.join(declaration.file_name())
+    } else {
+        path.to_path_buf()
+    };
+    fs::write(&path, declaration.to_bytes())
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    info!("the {} declaration is written to {}", kind, path.display());
+    warn!("the declaration is not validated against the official schema. check it in the DPS cabinet before signing");
+    Ok(())
+}
+
+/// Warns about days of the period that no imported statement covers.
+pub async fn warn_about_coverage_gaps(
+    criteria: &IncomeCriteria,
+    coverage_repo: &impl CoverageRepository,
+) -> Result<()> {
+    for gap in find_coverage_gaps(criteria, coverage_repo).await? {
+        warn!("{}. some incomes may be missing", gap);
     }
+    Ok(())
 }
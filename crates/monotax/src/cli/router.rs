@@ -1,7 +1,13 @@
 //! Invoke cli application with necessary environment and a command.
 
+use std::time::Duration;
+
+use monotax_sqlite::coverage_repository::SqlxCoverageRepository;
+use monotax_sqlite::import_batch_repository::SqlxImportBatchRepository;
 use monotax_sqlite::income_repository::income_repository;
 use monotax_sqlite::income_tax_repository::SqlxIncomeTaxRepository;
+use monotax_sqlite::payment_repository::SqlxPaymentRepository;
+use monotax_sqlite::sync_state_repository::SqlxSyncStateRepository;
 use sqlx::SqlitePool;
 
 use crate::config::Configuration;
@@ -14,35 +20,107 @@ use super::Cli;
 /// Runs a CLI command.
 pub async fn handle_command(
     cli: &Cli,
-    _config: &Configuration,
+    config: &Configuration,
     db_pool: SqlitePool,
 ) -> anyhow::Result<()> {
     let mut income_repo = income_repository(db_pool.clone());
     let income_tax_repo = SqlxIncomeTaxRepository::new(db_pool.clone());
+    let sync_state_repo = SqlxSyncStateRepository::new(db_pool.clone());
+    let import_batch_repo = SqlxImportBatchRepository::new(db_pool.clone());
+    let coverage_repo = SqlxCoverageRepository::new(db_pool.clone());
+    let payment_repo = SqlxPaymentRepository::new(db_pool.clone());
 
     match &cli.command {
         Command::Init { force } => init(&db_pool, *force).await?,
         Command::Incomes { command } => {
-            super::income::process_incomes(command, &mut income_repo).await?
+            super::income::process_incomes(
+                command,
+                config,
+                &mut income_repo,
+                &sync_state_repo,
+                &import_batch_repo,
+                &coverage_repo,
+            )
+            .await?
+        }
+        Command::Imports { command } => {
+            super::import::process_imports(command, &import_batch_repo).await?
+        }
+        Command::Payments { command } => {
+            super::payment::process_payments(command, &payment_repo, &income_tax_repo).await?
         }
 
         Command::Reports { command } => {
-            super::report::handle_report(command, &mut income_repo, &income_tax_repo).await?
+            super::report::handle_report(
+                command,
+                config,
+                &mut income_repo,
+                &income_tax_repo,
+                &payment_repo,
+                &coverage_repo,
+            )
+            .await?
         }
 
-        Command::Taxer {
-            input,
-            output,
-            filter,
-        } => {
-            generate_taxer_report(
+        Command::Documents { command } => {
+            super::document::handle_document(
+                command,
+                config,
                 &mut income_repo,
-                input.as_deref(),
-                output.as_deref(),
-                filter,
+                &income_tax_repo,
+                &payment_repo,
+            )
+            .await?
+        }
+
+        Command::Taxer(args) => {
+            handle_taxer(
+                args,
+                config,
+                &mut income_repo,
+                &payment_repo,
+                &coverage_repo,
             )
             .await?;
         }
+        Command::Export { format, output } => {
+            super::dataset::export(
+                *format,
+                output.as_deref(),
+                &mut income_repo,
+                &income_tax_repo,
+                &payment_repo,
+                &coverage_repo,
+                &sync_state_repo,
+            )
+            .await?
+        }
+        Command::Import { input } => {
+            super::dataset::import(
+                input,
+                &mut income_repo,
+                &income_tax_repo,
+                &payment_repo,
+                &coverage_repo,
+                &sync_state_repo,
+            )
+            .await?
+        }
+        Command::Watch {
+            dir,
+            interval,
+            once,
+        } => {
+            super::watch::watch_inbox(
+                dir,
+                Duration::from_secs(*interval),
+                *once,
+                config,
+                &import_batch_repo,
+                &coverage_repo,
+            )
+            .await?
+        }
     }
 
     Ok(())
@@ -0,0 +1,99 @@
+//! Read bank statements of all supported formats.
+
+use std::path::Path;
+
+use anyhow::Context;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::import_batch::ImportSource;
+use monotax_core::domain::Income;
+use monotax_core::filter::IncomePredicate;
+use monotax_core::integration::statement::StatementReaders;
+use monotax_core::integration::taxer::reader::TaxerStatementReader;
+use monotax_csv::profile::CsvProfileReader;
+use monotax_dbo::dbo::DboStatementReader;
+use monotax_iso20022::camt::CamtStatementReader;
+use monotax_monobank::statement::MonobankStatementReader;
+use monotax_ofx::{ofx::OfxStatementReader, qif::QifStatementReader};
+use monotax_privatbank::statement::{PrivatBankCsvReader, PrivatBankXlsxReader};
+use monotax_swift::mt940::Mt940StatementReader;
+use tokio::task::block_in_place;
+
+use crate::config::Configuration;
+
+use super::filter::FilterArgs;
+
+/// Lists all statement readers known to the application.
+///
+/// Register new readers here to make them available for import and format detection.
+/// User-defined CSV profiles go after bank formats so that built-in formats take precedence
+/// in detection. Taxer goes last since its files are plain CSV with Ukrainian headers.
+pub fn statement_readers(config: &Configuration) -> anyhow::Result<StatementReaders> {
+    let own_accounts = config.monobank().own_accounts.clone();
+    let mut readers = StatementReaders::new()
+        .with_reader(DboStatementReader)
+        .with_reader(MonobankStatementReader::with_own_accounts(own_accounts))
+        .with_reader(PrivatBankCsvReader)
+        .with_reader(PrivatBankXlsxReader)
+        .with_reader(CamtStatementReader)
+        .with_reader(Mt940StatementReader)
+        .with_reader(OfxStatementReader)
+        .with_reader(QifStatementReader::new(config.qif().currency));
+    let mut profiles = config.csv_profiles().iter().collect::<Vec<_>>();
+    profiles.sort_by_key(|(name, _)| name.as_str());
+    for (name, profile) in profiles {
+        readers = readers.with_reader(CsvProfileReader::new(name, profile.clone())?);
+    }
+    Ok(readers.with_reader(TaxerStatementReader))
+}
+
+/// Incomes of the statement file along with the description of the file
+/// and periods the statement covers.
+pub struct StatementFile {
+    pub source: ImportSource,
+    pub incomes: Vec<Income>,
+    pub coverages: Vec<StatementCoverage>,
+}
+
+/// Reads incomes from the statement file.
+/// Detects the statement format when it's not specified.
+pub async fn incomes_from_statement(
+    config: &Configuration,
+    input: &Path,
+    format: Option<&str>,
+    filter: &FilterArgs,
+) -> anyhow::Result<Vec<Income>> {
+    let statement = read_statement_file(config, input, format, filter).await?;
+    Ok(statement.incomes)
+}
+
+/// Reads incomes of the statement file that pass the filter.
+/// The coverage of the statement is not filtered.
+pub async fn read_statement_file(
+    config: &Configuration,
+    input: &Path,
+    format: Option<&str>,
+    filter: &FilterArgs,
+) -> anyhow::Result<StatementFile> {
+    let (content, statement) = block_in_place(move || {
+        let content = std::fs::read(input).context("opening input file")?;
+        let statement = statement_readers(config)?.read(&content, format)?;
+        anyhow::Ok((content, statement))
+    })?;
+    let name = input
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| input.display().to_string());
+    let source = ImportSource::from_content(name, statement.format().to_string(), &content);
+    let (incomes, coverages) = statement.into_parts();
+    let criteria = filter.criteria();
+    let mut incomes = incomes
+        .into_iter()
+        .filter(|income| criteria.test(income))
+        .collect::<Vec<_>>();
+    incomes.sort();
+    Ok(StatementFile {
+        source,
+        incomes,
+        coverages,
+    })
+}
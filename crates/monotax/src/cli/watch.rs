@@ -0,0 +1,355 @@
+//! Import statements dropped into an inbox directory.
+//!
+//! The inbox is usually a folder synchronized between machines,
+//! so statements downloaded anywhere end up in the database.
+//! Every new file is imported with the detected format and moved to the `processed`
+//! or `failed` subdirectory. Outcomes are appended to the `watch.log` file of the inbox.
+//! Files that could not be saved because of database errors stay in the inbox
+//! and are imported again on the next scan.
+//!
+//! Files are moved only after the import, so a file may stay in the inbox if monotax
+//! stops in between. Such files are recognized by their SHA-256 on the next start
+//! and moved without importing them again.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::Local;
+use log::{info, warn};
+use monotax_core::app::income::import_statement;
+use monotax_core::domain::repository::coverage::CoverageRepository;
+use monotax_core::domain::repository::import_batch::ImportBatchRepository;
+
+use crate::config::Configuration;
+
+use super::filter::{FilterArgs, IncludeQuarters, IncludeYears};
+use super::statement::read_statement_file;
+
+const PROCESSED_DIR: &str = "processed";
+const FAILED_DIR: &str = "failed";
+const LOG_FILE: &str = "watch.log";
+/// Extensions of files that are still being downloaded or synchronized.
+const PARTIAL_EXTENSIONS: [&str; 4] = ["part", "partial", "crdownload", "tmp"];
+
+/// Inbox directory with statement files.
+struct Inbox {
+    dir: PathBuf,
+    /// Sizes of files seen on the previous scan.
+    /// A file is ready when its size doesn't change between scans.
+    seen: HashMap<PathBuf, u64>,
+}
+
+/// What happened to a statement file.
+enum Outcome {
+    Imported {
+        batch: i64,
+        imported: usize,
+        total: usize,
+    },
+    AlreadyImported {
+        batch: i64,
+    },
+    /// The statement can't be read.
+    Failed(anyhow::Error),
+    /// The statement was read but not saved.
+    Deferred(anyhow::Error),
+}
+
+/// Watches the inbox directory importing new statement files until stopped.
+/// With `once`, imports files that are already in the inbox and exits.
+pub async fn watch_inbox(
+    dir: &Path,
+    interval: Duration,
+    once: bool,
+    config: &Configuration,
+    batch_repo: &impl ImportBatchRepository,
+    coverage_repo: &impl CoverageRepository,
+) -> anyhow::Result<()> {
+    let mut inbox = Inbox::open(dir)?;
+    info!("watching {} for statements", dir.display());
+    loop {
+        for file in inbox.ready_files(once)? {
+            let outcome = import_file(&file, config, batch_repo, coverage_repo).await;
+            if let Err(error) = inbox.settle(&file, &outcome) {
+                warn!("failed to settle {}: {:#}", file.display(), error);
+            }
+        }
+        if once {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn import_file(
+    file: &Path,
+    config: &Configuration,
+    batch_repo: &impl ImportBatchRepository,
+    coverage_repo: &impl CoverageRepository,
+) -> Outcome {
+    let statement = match read_statement_file(config, file, None, &all_incomes()).await {
+        Ok(statement) => statement,
+        Err(error) => return Outcome::Failed(error),
+    };
+    let result = async {
+        let known_batches = batch_repo.find_by_sha256(statement.source.sha256()).await?;
+        if let Some(batch) = known_batches.iter().find(|batch| !batch.is_reverted()) {
+            return Ok(Outcome::AlreadyImported { batch: batch.id() });
+        }
+        let batch = import_statement(
+            statement.source,
+            statement.incomes,
+            &statement.coverages,
+            batch_repo,
+            coverage_repo,
+        )
+        .await?;
+        anyhow::Ok(Outcome::Imported {
+            batch: batch.id(),
+            imported: batch.imported_rows(),
+            total: batch.total_rows(),
+        })
+    };
+    result.await.unwrap_or_else(Outcome::Deferred)
+}
+
+/// Statements in the inbox are imported completely regardless of their dates.
+fn all_incomes() -> FilterArgs {
+    FilterArgs {
+        quarter: None,
+        include_quarters: IncludeQuarters::Any,
+        include_years: IncludeYears::All,
+        year: None,
+    }
+}
+
+impl Inbox {
+    fn open(dir: &Path) -> anyhow::Result<Self> {
+        if !dir.is_dir() {
+            anyhow::bail!("{} is not a directory", dir.display());
+        }
+        for subdir in [PROCESSED_DIR, FAILED_DIR] {
+            fs::create_dir_all(dir.join(subdir))
+                .with_context(|| format!("failed to create {} directory", subdir))?;
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            seen: HashMap::new(),
+        })
+    }
+
+    /// Lists files that are not changing anymore.
+    /// Without waiting, all files are considered ready.
+    fn ready_files(&mut self, no_wait: bool) -> anyhow::Result<Vec<PathBuf>> {
+        let mut sizes = HashMap::new();
+        for entry in fs::read_dir(&self.dir).context("failed to read the inbox")? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_file() && is_statement_candidate(&path) {
+                sizes.insert(path, entry.metadata()?.len());
+            }
+        }
+        let mut ready = sizes
+            .iter()
+            .filter(|(path, size)| no_wait || self.seen.get(*path) == Some(size))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        ready.sort();
+        self.seen = sizes;
+        Ok(ready)
+    }
+
+    /// Moves the file out of the inbox and logs the outcome.
+    /// Deferred files stay in the inbox to be imported again.
+    fn settle(&mut self, file: &Path, outcome: &Outcome) -> anyhow::Result<()> {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        if let Outcome::Deferred(error) = outcome {
+            let message = format!("left in the inbox: {:#}", error);
+            warn!("{}: {}", name, message);
+            return self.log(&format!("{}\t{}", name, message));
+        }
+        let (subdir, message) = match outcome {
+            Outcome::Imported {
+                batch,
+                imported,
+                total,
+            } => (
+                PROCESSED_DIR,
+                format!(
+                    "imported {} of {} incomes as batch {}",
+                    imported, total, batch
+                ),
+            ),
+            Outcome::AlreadyImported { batch } => (
+                PROCESSED_DIR,
+                format!("skipped, already imported as batch {}", batch),
+            ),
+            Outcome::Failed(error) | Outcome::Deferred(error) => {
+                (FAILED_DIR, format!("failed: {:#}", error))
+            }
+        };
+        match outcome {
+            Outcome::Failed(_) => warn!("{}: {}", name, message),
+            _ => info!("{}: {}", name, message),
+        }
+        let target = free_path(&self.dir.join(subdir), &name);
+        fs::rename(file, &target)
+            .with_context(|| format!("failed to move {} to {}", name, target.display()))?;
+        self.seen.remove(file);
+        self.log(&format!("{}\t{}", name, message))
+    }
+
+    fn log(&self, line: &str) -> anyhow::Result<()> {
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(LOG_FILE))
+            .context("failed to open the watch log")?;
+        writeln!(
+            log,
+            "{}\t{}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            line
+        )?;
+        Ok(())
+    }
+}
+
+/// Skips hidden files, the log and files that are not downloaded completely.
+fn is_statement_candidate(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let partial = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| PARTIAL_EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+    !name.starts_with('.') && !name.starts_with('~') && name != LOG_FILE && !partial
+}
+
+/// Picks the name in the directory that doesn't clash with existing files.
+fn free_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let stem = Path::new(name)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let extension = Path::new(name)
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|copy| dir.join(format!("{}-{}{}", stem, copy, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use monotax_sqlite::coverage_repository::SqlxCoverageRepository;
+    use monotax_sqlite::import_batch_repository::SqlxImportBatchRepository;
+    use monotax_sqlite::init::initialize_db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::SqlitePool;
+
+    use super::*;
+
+    const STATEMENT: &str = ":20:STMT240301\n:25:UA213223130000026007233566001\n:60F:C240301UAH0,00\n:61:240305C1500,00NTRFNONREF\n:62F:C240331UAH1500,00\n";
+
+    fn inbox_dir(test: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("monotax-inbox-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn database() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_db(&pool).await.unwrap();
+        pool
+    }
+
+    async fn watch_once(dir: &Path, pool: &SqlitePool) {
+        let batch_repo = SqlxImportBatchRepository::new(pool.clone());
+        let coverage_repo = SqlxCoverageRepository::new(pool.clone());
+        watch_inbox(
+            dir,
+            Duration::ZERO,
+            true,
+            &Configuration::default(),
+            &batch_repo,
+            &coverage_repo,
+        )
+        .await
+        .unwrap();
+    }
+
+    fn watch_log(dir: &Path) -> String {
+        fs::read_to_string(dir.join(LOG_FILE)).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn move_imported_file_to_processed() {
+        let dir = inbox_dir("imported");
+        let pool = database().await;
+        fs::write(dir.join("statement.sta"), STATEMENT).unwrap();
+
+        watch_once(&dir, &pool).await;
+
+        assert!(!dir.join("statement.sta").exists());
+        assert!(dir.join(PROCESSED_DIR).join("statement.sta").exists());
+        assert!(watch_log(&dir).contains("imported 1 of 1 incomes as batch 1"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn skip_already_imported_file() {
+        let dir = inbox_dir("duplicate");
+        let pool = database().await;
+        fs::write(dir.join("statement.sta"), STATEMENT).unwrap();
+        watch_once(&dir, &pool).await;
+        fs::write(dir.join("statement.sta"), STATEMENT).unwrap();
+
+        watch_once(&dir, &pool).await;
+
+        assert!(dir.join(PROCESSED_DIR).join("statement-1.sta").exists());
+        assert!(watch_log(&dir).contains("skipped, already imported as batch 1"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn move_unreadable_file_to_failed() {
+        let dir = inbox_dir("failed");
+        let pool = database().await;
+        fs::write(dir.join("notes.txt"), "not a statement").unwrap();
+
+        watch_once(&dir, &pool).await;
+
+        assert!(dir.join(FAILED_DIR).join("notes.txt").exists());
+        assert!(watch_log(&dir).contains("notes.txt\tfailed:"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn leave_file_in_inbox_when_database_fails() {
+        let dir = inbox_dir("deferred");
+        let pool = database().await;
+        pool.close().await;
+        fs::write(dir.join("statement.sta"), STATEMENT).unwrap();
+
+        watch_once(&dir, &pool).await;
+
+        assert!(dir.join("statement.sta").exists());
+        assert!(!dir.join(FAILED_DIR).join("statement.sta").exists());
+        assert!(watch_log(&dir).contains("statement.sta\tleft in the inbox:"));
+    }
+}
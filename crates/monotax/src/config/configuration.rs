@@ -1,4 +1,12 @@
+use std::collections::HashMap;
+
 use monotax_core::integration::taxer::TaxerImportConfig;
+use monotax_core::report::template::{Templates, TemplatesConfiguration};
+use monotax_csv::profile::CsvProfile;
+use monotax_dps::configuration::DpsConfiguration;
+use monotax_ledger::configuration::LedgerConfiguration;
+use monotax_monobank::configuration::MonobankConfiguration;
+use monotax_ofx::configuration::QifConfiguration;
 use monotax_sqlite::configuration::DatabaseConfiguration;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +15,18 @@ use serde::{Deserialize, Serialize};
 pub struct Configuration {
     taxer: TaxerImportConfig,
     pub database: DatabaseConfiguration,
+    #[serde(default)]
+    monobank: MonobankConfiguration,
+    #[serde(default)]
+    csv_profiles: HashMap<String, CsvProfile>,
+    #[serde(default)]
+    qif: QifConfiguration,
+    #[serde(default)]
+    dps: DpsConfiguration,
+    #[serde(default)]
+    ledger: LedgerConfiguration,
+    #[serde(default)]
+    templates: TemplatesConfiguration,
 }
 
 impl Configuration {
@@ -17,4 +37,33 @@ impl Configuration {
     pub fn database(&self) -> &DatabaseConfiguration {
         &self.database
     }
+
+    pub fn monobank(&self) -> &MonobankConfiguration {
+        &self.monobank
+    }
+
+    /// User-defined CSV statement profiles by their names.
+    pub fn csv_profiles(&self) -> &HashMap<String, CsvProfile> {
+        &self.csv_profiles
+    }
+
+    /// Currency of QIF statements.
+    pub fn qif(&self) -> &QifConfiguration {
+        &self.qif
+    }
+
+    /// Taxpayer details for DPS reports.
+    pub fn dps(&self) -> &DpsConfiguration {
+        &self.dps
+    }
+
+    /// Accounts of plain-text accounting journals.
+    pub fn ledger(&self) -> &LedgerConfiguration {
+        &self.ledger
+    }
+
+    /// Templates of HTML reports with user templates from the configured directory.
+    pub fn templates(&self) -> anyhow::Result<Templates> {
+        Templates::new(self.templates.dir.as_deref())
+    }
 }
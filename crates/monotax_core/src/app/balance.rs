@@ -10,5 +10,5 @@ pub async fn generate_balance_report(
 ) -> anyhow::Result<BalanceReport> {
     let incomes = income_repository.find_by(criteria).await?;
     let income_taxes = income_tax_repository.find_all().await?;
-    Ok(BalanceReport::new(incomes, income_taxes))
+    Ok(BalanceReport::new(incomes, income_taxes)?)
 }
@@ -0,0 +1,22 @@
+use chrono::Local;
+
+use crate::domain::filter::income::IncomeCriteria;
+use crate::domain::model::coverage::{coverage_gaps, CoverageGap};
+use crate::domain::repository::coverage::CoverageRepository;
+
+/// Finds days of the period selected by the criteria that no imported statement covers.
+/// Days after today are not expected to be covered yet.
+pub async fn find_coverage_gaps(
+    criteria: &IncomeCriteria,
+    coverage_repo: &impl CoverageRepository,
+) -> anyhow::Result<Vec<CoverageGap>> {
+    let Some((start, end)) = criteria.period() else {
+        return Ok(vec![]);
+    };
+    let end = end.min(Local::now().date_naive());
+    if end < start {
+        return Ok(vec![]);
+    }
+    let coverages = coverage_repo.find_all().await?;
+    Ok(coverage_gaps(&coverages, start, end))
+}
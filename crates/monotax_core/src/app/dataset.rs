@@ -0,0 +1,90 @@
+use log::info;
+
+use crate::domain::repository::coverage::CoverageRepository;
+use crate::domain::repository::income_tax::IncomeTaxRepository;
+use crate::domain::repository::payment::PaymentRepository;
+use crate::domain::repository::sync::SyncStateRepository;
+use crate::domain::repository::IncomeRepository;
+use crate::integration::dataset::{Dataset, SyncCursor};
+
+/// Counts of records saved by the import of the dataset.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DatasetImport {
+    pub taxes: usize,
+    pub incomes: usize,
+    pub skipped_incomes: usize,
+    pub payments: usize,
+    pub coverages: usize,
+}
+
+/// Collects all incomes, taxes, payments, coverages and sync cursors of the database.
+pub async fn export_dataset(
+    generator: String,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+    payment_repository: &impl PaymentRepository,
+    coverage_repository: &impl CoverageRepository,
+    sync_repository: &impl SyncStateRepository,
+) -> anyhow::Result<Dataset> {
+    let taxes = income_tax_repository.find_all().await?;
+    let mut incomes = income_repository.find_all().await?;
+    incomes.sort_by_key(|income| income.income_no());
+    let sync_cursors = sync_repository
+        .find_all()
+        .await?
+        .into_iter()
+        .map(|(account, synced_until)| SyncCursor {
+            account,
+            synced_until,
+        })
+        .collect();
+    Ok(Dataset::new(generator, taxes, incomes)
+        .with_payments(payment_repository.find_all().await?)
+        .with_coverages(coverage_repository.find_all().await?)
+        .with_sync_cursors(sync_cursors))
+}
+
+/// Saves records of the dataset keeping identifiers of taxes and payments and payment numbers.
+/// Taxes replace taxes with the same id, incomes, payments and coverages that are already
+/// saved are skipped. Sync cursors never move back.
+pub async fn import_dataset(
+    dataset: &Dataset,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+    payment_repository: &impl PaymentRepository,
+    coverage_repository: &impl CoverageRepository,
+    sync_repository: &impl SyncStateRepository,
+) -> anyhow::Result<DatasetImport> {
+    info!(
+        "importing dataset of version {} written by {} at {}",
+        dataset.metadata.version, dataset.metadata.generator, dataset.metadata.exported_at
+    );
+    for tax in &dataset.taxes {
+        income_tax_repository.save(tax).await?;
+    }
+    let incomes = income_repository.restore_all(&dataset.incomes).await?;
+    let payments = payment_repository.restore_all(&dataset.payments).await?;
+    let known_coverages = coverage_repository.find_all().await?;
+    let coverages = dataset
+        .coverages
+        .iter()
+        .filter(|coverage| !known_coverages.contains(coverage))
+        .cloned()
+        .collect::<Vec<_>>();
+    coverage_repository.save_all(&coverages, None).await?;
+    for cursor in &dataset.sync_cursors {
+        let last_synced = sync_repository.last_synced(&cursor.account).await?;
+        if last_synced.is_none_or(|last_synced| last_synced < cursor.synced_until) {
+            sync_repository
+                .save_last_synced(&cursor.account, cursor.synced_until)
+                .await?;
+        }
+    }
+    Ok(DatasetImport {
+        taxes: dataset.taxes.len(),
+        incomes,
+        skipped_incomes: dataset.incomes.len() - incomes,
+        payments,
+        coverages: coverages.len(),
+    })
+}
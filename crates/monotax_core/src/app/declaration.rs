@@ -0,0 +1,30 @@
+use crate::domain::filter::income::{IncomeCriteria, IncomeCriterion, QuarterFilter, YearFilter};
+use crate::domain::model::income::ensure_uah;
+use crate::domain::repository::income_tax::IncomeTaxRepository;
+use crate::domain::repository::IncomeRepository;
+use crate::domain::Quarter;
+use crate::report::declaration::DeclarationReport;
+
+/// Criteria of incomes from the start of the year to the end of the quarter.
+pub fn declaration_criteria(year: i32, quarter: Quarter) -> IncomeCriteria {
+    IncomeCriteria::new(&[
+        IncomeCriterion::Year(YearFilter::One(year)),
+        IncomeCriterion::Quarter(QuarterFilter::Ytd(quarter)),
+    ])
+}
+
+/// Generates the declaration of incomes from the start of the year to the end of the quarter.
+/// Fails when some incomes are not in hryvnias.
+pub async fn generate_declaration_report(
+    year: i32,
+    quarter: Quarter,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+) -> anyhow::Result<DeclarationReport> {
+    let incomes = income_repository
+        .find_by(declaration_criteria(year, quarter))
+        .await?;
+    ensure_uah(&incomes)?;
+    let income_taxes = income_tax_repository.find_all().await?;
+    Ok(DeclarationReport::new(year, quarter, incomes, income_taxes))
+}
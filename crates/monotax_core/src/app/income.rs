@@ -1,6 +1,24 @@
-use log::info;
+use chrono::NaiveDateTime;
+use log::{info, warn};
 
-use crate::domain::{filter::income::IncomeCriteria, repository::IncomeRepository, Income};
+use crate::domain::model::coverage::StatementCoverage;
+use crate::domain::model::import_batch::{ImportBatch, ImportSource};
+use crate::domain::model::income::{Amount, IncomeKind};
+use crate::domain::repository::coverage::CoverageRepository;
+use crate::domain::repository::import_batch::ImportBatchRepository;
+use crate::domain::{
+    filter::income::IncomeCriteria, repository::IncomeRepository, Currency, Income,
+};
+
+/// Changes of income fields. Fields that are not set are kept.
+#[derive(Debug, Clone, Default)]
+pub struct IncomeChanges {
+    pub date: Option<NaiveDateTime>,
+    pub amount: Option<Amount>,
+    pub currency: Option<Currency>,
+    pub comment: Option<String>,
+    pub kind: Option<IncomeKind>,
+}
 
 pub async fn import_incomes(
     incomes: Vec<Income>,
@@ -11,9 +29,91 @@ pub async fn import_incomes(
     Ok(imported_count)
 }
 
+/// Imports incomes of the statement file recording the batch they came from
+/// and periods the statement covers.
+/// Warns when the same content was imported before.
+pub async fn import_statement(
+    source: ImportSource,
+    incomes: Vec<Income>,
+    coverages: &[StatementCoverage],
+    batch_repo: &impl ImportBatchRepository,
+    coverage_repo: &impl CoverageRepository,
+) -> anyhow::Result<ImportBatch> {
+    let known_batches = batch_repo.find_by_sha256(source.sha256()).await?;
+    if let Some(batch) = known_batches.iter().find(|batch| !batch.is_reverted()) {
+        warn!(
+            "{} has been already imported from {} as batch {}",
+            source.name(),
+            batch.source().name(),
+            batch.id()
+        );
+    }
+    let batch = batch_repo.import(source, &incomes).await?;
+    coverage_repo.save_all(coverages, Some(batch.id())).await?;
+    info!(
+        "imported {} of {} incomes as batch {}",
+        batch.imported_rows(),
+        batch.total_rows(),
+        batch.id()
+    );
+    Ok(batch)
+}
+
 pub async fn read_incomes(
     criteria: IncomeCriteria,
     income_repo: &mut impl IncomeRepository,
 ) -> anyhow::Result<Vec<Income>> {
     income_repo.find_by(criteria).await
 }
+
+/// Adds the income that isn't in any statement, like a cash receipt.
+pub async fn add_income(
+    income: Income,
+    income_repo: &mut impl IncomeRepository,
+) -> anyhow::Result<Income> {
+    let income = income_repo.add(&income).await?;
+    info!("added income {}", income.income_no());
+    Ok(income)
+}
+
+/// Corrects fields of the income with the payment number.
+pub async fn edit_income(
+    payment_no: i64,
+    changes: IncomeChanges,
+    income_repo: &mut impl IncomeRepository,
+) -> anyhow::Result<Income> {
+    let Some(mut income) = income_repo.find_by_payment_no(payment_no).await? else {
+        anyhow::bail!("income {} not found", payment_no);
+    };
+    if let Some(date) = changes.date {
+        income = income.with_date(date);
+    }
+    if let Some(amount) = changes.amount {
+        income = income.with_amount(amount);
+    }
+    if let Some(currency) = changes.currency {
+        income = income.with_currency(currency);
+    }
+    if let Some(comment) = changes.comment {
+        income = income.with_comment(comment);
+    }
+    if let Some(kind) = changes.kind {
+        income = income.with_kind(kind);
+    }
+    if !income_repo.update(&income).await? {
+        anyhow::bail!("income {} not found", payment_no);
+    }
+    info!("updated income {}", payment_no);
+    Ok(income)
+}
+
+pub async fn delete_income(
+    payment_no: i64,
+    income_repo: &mut impl IncomeRepository,
+) -> anyhow::Result<()> {
+    if !income_repo.delete(payment_no).await? {
+        anyhow::bail!("income {} not found", payment_no);
+    }
+    info!("deleted income {}", payment_no);
+    Ok(())
+}
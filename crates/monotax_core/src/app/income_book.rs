@@ -0,0 +1,16 @@
+use crate::domain::filter::income::{IncomeCriteria, IncomeCriterion, YearFilter};
+use crate::domain::model::income::ensure_uah;
+use crate::domain::repository::IncomeRepository;
+use crate::report::income_book::IncomeBook;
+
+/// Generates the income book of the year.
+/// Fails when some incomes are not in hryvnias.
+pub async fn generate_income_book(
+    year: i32,
+    income_repository: &mut impl IncomeRepository,
+) -> anyhow::Result<IncomeBook> {
+    let criteria = IncomeCriteria::new(&[IncomeCriterion::Year(YearFilter::One(year))]);
+    let incomes = income_repository.find_by(criteria).await?;
+    ensure_uah(&incomes)?;
+    Ok(IncomeBook::new(year, incomes))
+}
@@ -0,0 +1,39 @@
+use chrono::NaiveDate;
+use log::warn;
+
+use crate::domain::repository::income_tax::IncomeTaxRepository;
+use crate::domain::repository::payment::PaymentRepository;
+use crate::domain::repository::IncomeRepository;
+use crate::report::income_certificate::{IncomeCertificate, Taxpayer};
+
+/// Generates the certificate of incomes from `from` to `to` issued on `issued`.
+pub async fn generate_income_certificate(
+    taxpayer: Taxpayer,
+    from: NaiveDate,
+    to: NaiveDate,
+    issued: NaiveDate,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+    payment_repository: &impl PaymentRepository,
+) -> anyhow::Result<IncomeCertificate> {
+    if from > to {
+        anyhow::bail!("the period starts after its end");
+    }
+    let incomes = income_repository.find_all().await?;
+    let payments = payment_repository.find_all().await?;
+    let unassigned = payments
+        .iter()
+        .filter(|payment| (from..=to).contains(&payment.date()))
+        .filter(|payment| payment.tax().is_none())
+        .count();
+    if unassigned > 0 {
+        warn!(
+            "{} payments of the period have no tax and are not certified as paid",
+            unassigned
+        );
+    }
+    let taxes = income_tax_repository.find_all().await?;
+    Ok(IncomeCertificate::new(
+        taxpayer, from, to, issued, incomes, &payments, &taxes,
+    )?)
+}
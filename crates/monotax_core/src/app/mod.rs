@@ -2,5 +2,12 @@
 //! Functions here coordinate actions of various infrastructure and domain components
 //! to achieve business goals.
 
+pub mod coverage;
+pub mod dataset;
+pub mod declaration;
 pub mod income;
-pub mod balance;
\ No newline at end of file
+pub mod income_book;
+pub mod income_certificate;
+pub mod payment;
+pub mod balance;
+pub mod workbook;
\ No newline at end of file
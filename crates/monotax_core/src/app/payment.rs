@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use log::info;
+
+use crate::domain::model::payment::{PaymentID, TaxPayment};
+use crate::domain::repository::income_tax::IncomeTaxRepository;
+use crate::domain::repository::payment::PaymentRepository;
+
+/// Records the tax payment made from a bank account.
+/// Fails when the tax of the payment is not a known income tax.
+pub async fn add_payment(
+    payment: TaxPayment,
+    payment_repo: &impl PaymentRepository,
+    income_tax_repo: &impl IncomeTaxRepository,
+) -> anyhow::Result<TaxPayment> {
+    if let Some(tax) = payment.tax() {
+        let taxes = income_tax_repo.find_all().await?;
+        if !taxes.iter().any(|known| known.name() == tax) {
+            let known = taxes.iter().map(|tax| tax.name()).collect::<Vec<_>>();
+            anyhow::bail!("unknown tax {}. known taxes are: {}", tax, known.join(", "));
+        }
+    }
+    let payment = payment_repo.add(&payment).await?;
+    info!("added payment {}", payment.id());
+    Ok(payment)
+}
+
+/// Lists payments made from `start` to `end`, both inclusive.
+pub async fn find_payments(
+    start: NaiveDate,
+    end: NaiveDate,
+    payment_repo: &impl PaymentRepository,
+) -> anyhow::Result<Vec<TaxPayment>> {
+    let payments = payment_repo.find_all().await?;
+    Ok(payments
+        .into_iter()
+        .filter(|payment| (start..=end).contains(&payment.date()))
+        .collect())
+}
+
+pub async fn delete_payment(
+    id: PaymentID,
+    payment_repo: &impl PaymentRepository,
+) -> anyhow::Result<()> {
+    if !payment_repo.delete(id).await? {
+        anyhow::bail!("payment {} not found", id);
+    }
+    info!("deleted payment {}", id);
+    Ok(())
+}
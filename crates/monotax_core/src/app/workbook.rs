@@ -0,0 +1,16 @@
+use crate::domain::filter::income::IncomeCriteria;
+use crate::domain::repository::income_tax::IncomeTaxRepository;
+use crate::domain::repository::IncomeRepository;
+use crate::report::workbook::ReportWorkbook;
+
+/// Generates the workbook of incomes matching the criteria.
+/// Fails when some incomes are not in hryvnias.
+pub async fn generate_report_workbook(
+    criteria: IncomeCriteria,
+    income_repository: &mut impl IncomeRepository,
+    income_tax_repository: &impl IncomeTaxRepository,
+) -> anyhow::Result<ReportWorkbook> {
+    let incomes = income_repository.find_by(criteria).await?;
+    let income_taxes = income_tax_repository.find_all().await?;
+    Ok(ReportWorkbook::new(incomes, income_taxes)?)
+}
@@ -4,5 +4,6 @@ pub mod filter;
 pub mod model;
 pub mod repository;
 
+pub use model::currency::Currency;
 pub use model::income::Income;
 pub use model::quarter::Quarter;
@@ -0,0 +1,199 @@
+//! Periods covered by imported statements.
+//!
+//! Every imported statement covers a period of some bank account.
+//! Days of the reporting period that no statement covers are gaps,
+//! which usually means a statement was forgotten.
+
+use std::fmt::Display;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Days of the account covered by a statement, both ends included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementCoverage {
+    account: String,
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+/// Days that no statement covers, both ends included.
+/// The account is absent when no statement of any account covers the period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageGap {
+    account: Option<String>,
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+impl StatementCoverage {
+    /// Creates the coverage swapping the dates if they are in the wrong order.
+    pub fn new(account: String, start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            account,
+            start: start.min(end),
+            end: start.max(end),
+        }
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.end
+    }
+
+    fn overlaps(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.start <= end && self.end >= start
+    }
+}
+
+impl CoverageGap {
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.end
+    }
+}
+
+impl Display for CoverageGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.account {
+            Some(account) => write!(
+                f,
+                "no statements of account {} cover {} - {}",
+                account, self.start, self.end
+            ),
+            None => write!(f, "no statements cover {} - {}", self.start, self.end),
+        }
+    }
+}
+
+/// Finds days between the start and the end that statements don't cover.
+///
+/// Only accounts with statements in the period are checked,
+/// so accounts closed before the period don't produce gaps.
+pub fn coverage_gaps(
+    coverages: &[StatementCoverage],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Vec<CoverageGap> {
+    let mut relevant = coverages
+        .iter()
+        .filter(|coverage| coverage.overlaps(start, end))
+        .collect::<Vec<_>>();
+    if relevant.is_empty() {
+        return vec![CoverageGap {
+            account: None,
+            start,
+            end,
+        }];
+    }
+    relevant.sort_by(|a, b| (&a.account, a.start).cmp(&(&b.account, b.start)));
+    let mut gaps = vec![];
+    for account_coverages in relevant.chunk_by(|a, b| a.account == b.account) {
+        let account = &account_coverages[0].account;
+        let mut uncovered_from = Some(start);
+        for coverage in account_coverages {
+            let Some(from) = uncovered_from else {
+                break;
+            };
+            if coverage.start > from {
+                gaps.push(CoverageGap {
+                    account: Some(account.clone()),
+                    start: from,
+                    end: coverage.start.pred_opt().unwrap_or(from),
+                });
+            }
+            if coverage.end >= from {
+                uncovered_from = coverage.end.checked_add_days(Days::new(1));
+            }
+        }
+        if let Some(from) = uncovered_from.filter(|from| *from <= end) {
+            gaps.push(CoverageGap {
+                account: Some(account.clone()),
+                start: from,
+                end,
+            });
+        }
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(raw: &str) -> NaiveDate {
+        raw.parse().unwrap()
+    }
+
+    fn coverage(account: &str, start: &str, end: &str) -> StatementCoverage {
+        StatementCoverage::new(account.to_string(), date(start), date(end))
+    }
+
+    fn gap(account: Option<&str>, start: &str, end: &str) -> CoverageGap {
+        CoverageGap {
+            account: account.map(str::to_string),
+            start: date(start),
+            end: date(end),
+        }
+    }
+
+    #[test]
+    fn accept_fully_covered_period() {
+        let coverages = [
+            coverage("uah", "2024-01-01", "2024-01-31"),
+            coverage("uah", "2024-02-01", "2024-02-29"),
+            coverage("uah", "2024-02-15", "2024-03-31"),
+        ];
+
+        let gaps = coverage_gaps(&coverages, date("2024-01-01"), date("2024-03-31"));
+
+        assert_eq!(gaps, vec![]);
+    }
+
+    #[test]
+    fn find_missing_months_per_account() {
+        let coverages = [
+            coverage("uah", "2024-01-01", "2024-01-31"),
+            coverage("uah", "2024-03-01", "2024-03-31"),
+            coverage("usd", "2024-01-10", "2024-02-29"),
+        ];
+
+        let gaps = coverage_gaps(&coverages, date("2024-01-01"), date("2024-03-31"));
+
+        assert_eq!(
+            gaps,
+            vec![
+                gap(Some("uah"), "2024-02-01", "2024-02-29"),
+                gap(Some("usd"), "2024-01-01", "2024-01-09"),
+                gap(Some("usd"), "2024-03-01", "2024-03-31"),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_period_without_statements() {
+        let coverages = [coverage("uah", "2023-01-01", "2023-12-31")];
+
+        let gaps = coverage_gaps(&coverages, date("2024-01-01"), date("2024-03-31"));
+
+        assert_eq!(gaps, vec![gap(None, "2024-01-01", "2024-03-31")]);
+        assert_eq!(
+            gaps[0].to_string(),
+            "no statements cover 2024-01-01 - 2024-03-31"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Currencies of incomes and payments.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// ISO 4217 currency identified by its alphabetic code.
+/// It's serialized as the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+#[derive(Debug, Clone, Error)]
+#[error("invalid currency code {invalid_code}")]
+pub struct CurrencyError {
+    pub invalid_code: String,
+}
+
+/// Alphabetic and numeric codes of currencies that appear in statements.
+const KNOWN_CURRENCIES: [(&str, u16); 8] = [
+    ("UAH", 980),
+    ("USD", 840),
+    ("EUR", 978),
+    ("GBP", 826),
+    ("PLN", 985),
+    ("CHF", 756),
+    ("CZK", 203),
+    ("CAD", 124),
+];
+
+impl Currency {
+    /// Ukrainian hryvnia. The currency of taxes and reports.
+    pub const UAH: Currency = Currency(*b"UAH");
+    pub const USD: Currency = Currency(*b"USD");
+    pub const EUR: Currency = Currency(*b"EUR");
+
+    /// Creates the currency from the three-letter code like `UAH`.
+    pub fn new(code: &str) -> Result<Self, CurrencyError> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 3 || !bytes.iter().all(u8::is_ascii_alphabetic) {
+            return Err(CurrencyError {
+                invalid_code: code.to_string(),
+            });
+        }
+        let upper = code.to_ascii_uppercase();
+        let mut raw = [0u8; 3];
+        raw.copy_from_slice(upper.as_bytes());
+        Ok(Self(raw))
+    }
+
+    /// Creates the currency from the ISO 4217 numeric code like `980`.
+    ///
+    /// Only commonly used currencies are supported.
+    pub fn from_numeric(code: u16) -> Result<Self, CurrencyError> {
+        KNOWN_CURRENCIES
+            .iter()
+            .find(|(_, numeric)| *numeric == code)
+            .map(|(alpha, _)| Currency::new(alpha))
+            .unwrap_or_else(|| {
+                Err(CurrencyError {
+                    invalid_code: code.to_string(),
+                })
+            })
+    }
+
+    pub fn code(&self) -> &str {
+        // the code is validated to be ascii on creation
+        std::str::from_utf8(&self.0).unwrap()
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::UAH
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::new(s)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = CurrencyError;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Currency::new(&code)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.code().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_alphabetic_codes() {
+        assert_eq!(Currency::new("UAH").unwrap(), Currency::UAH);
+        assert_eq!(Currency::new("usd").unwrap(), Currency::USD);
+        assert_eq!("EUR".parse::<Currency>().unwrap().code(), "EUR");
+    }
+
+    #[test]
+    fn reject_invalid_codes() {
+        assert!(Currency::new("UA").is_err());
+        assert!(Currency::new("980").is_err());
+        assert!(Currency::new("ГРН").is_err());
+    }
+
+    #[test]
+    fn convert_numeric_codes() {
+        assert_eq!(Currency::from_numeric(980).unwrap(), Currency::UAH);
+        assert_eq!(Currency::from_numeric(840).unwrap(), Currency::USD);
+        assert_eq!(Currency::from_numeric(978).unwrap(), Currency::EUR);
+        assert!(Currency::from_numeric(1).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! Provenance of imported incomes.
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+pub type BatchID = i64;
+
+/// Statement file that incomes are imported from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSource {
+    name: String,
+    sha256: String,
+    format: String,
+}
+
+/// A single run of the import with its source and results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportBatch {
+    id: BatchID,
+    source: ImportSource,
+    imported_at: DateTime<Utc>,
+    total_rows: usize,
+    imported_rows: usize,
+    reverted_at: Option<DateTime<Utc>>,
+}
+
+impl ImportSource {
+    pub fn new(name: String, sha256: String, format: String) -> Self {
+        Self {
+            name,
+            sha256,
+            format,
+        }
+    }
+
+    /// Describes the source calculating SHA-256 of its content.
+    pub fn from_content(name: String, format: String, content: &[u8]) -> Self {
+        let sha256 = format!("{:x}", Sha256::digest(content));
+        Self::new(name, sha256, format)
+    }
+
+    /// Name of the statement file.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Hex-encoded SHA-256 of the statement content.
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// Format the statement was read with.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+}
+
+impl ImportBatch {
+    pub fn new(
+        id: BatchID,
+        source: ImportSource,
+        imported_at: DateTime<Utc>,
+        total_rows: usize,
+        imported_rows: usize,
+    ) -> Self {
+        Self {
+            id,
+            source,
+            imported_at,
+            total_rows,
+            imported_rows,
+            reverted_at: None,
+        }
+    }
+
+    pub fn with_reverted_at(self, reverted_at: DateTime<Utc>) -> Self {
+        Self {
+            reverted_at: Some(reverted_at),
+            ..self
+        }
+    }
+
+    pub fn id(&self) -> BatchID {
+        self.id
+    }
+
+    pub fn source(&self) -> &ImportSource {
+        &self.source
+    }
+
+    pub fn imported_at(&self) -> DateTime<Utc> {
+        self.imported_at
+    }
+
+    /// Number of incomes found in the statement.
+    pub fn total_rows(&self) -> usize {
+        self.total_rows
+    }
+
+    /// Number of incomes saved by the batch. Incomes that were already known are skipped.
+    pub fn imported_rows(&self) -> usize {
+        self.imported_rows
+    }
+
+    /// Moment when incomes of the batch were removed.
+    pub fn reverted_at(&self) -> Option<DateTime<Utc>> {
+        self.reverted_at
+    }
+
+    pub fn is_reverted(&self) -> bool {
+        self.reverted_at.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_source_content() {
+        let source = ImportSource::from_content("a.csv".to_string(), "dbo".to_string(), b"abc");
+
+        assert_eq!(
+            source.sha256(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,19 +1,47 @@
 use chrono::{NaiveDate, NaiveDateTime};
-use std::{cmp::Ordering, fmt::Display, iter::Sum, ops::Add};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt::Display, iter::Sum, ops::Add, str::FromStr};
 use thiserror::Error;
 
-#[derive(Debug, Clone)]
+use super::currency::Currency;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Income {
+    #[serde(rename = "payment_no", default)]
     income_no: i64,
     date: NaiveDateTime,
     amount: Amount,
+    #[serde(rename = "description", default)]
     comment: Option<String>,
+    #[serde(default)]
+    currency: Currency,
+    #[serde(default)]
+    source_ref: Option<String>,
+    #[serde(default)]
+    tax_paid: bool,
+    #[serde(default)]
+    kind: IncomeKind,
+}
+
+/// Kind of the income book entry.
+///
+/// Refunds are returned prepayments and funds received by mistake. They reduce the income.
+/// Corrections are entries added to fix the book, like incomes missing from statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IncomeKind {
+    #[default]
+    Income,
+    Refund,
+    Correction,
 }
 
 const MAX_AMOUNT: f64 = 1000000000.0;
 
 /// Monetary amount.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
 pub struct Amount(f64);
 
 #[derive(Debug, Clone, Error)]
@@ -22,6 +50,42 @@ pub struct AmountError {
     pub invalid_amount: f64,
 }
 
+/// Incomes in foreign currencies found where amounts in hryvnias are required.
+/// Such incomes are converted at the NBU rate of their date by correcting them
+/// with `incomes edit <payment_no> --amount <uah> --currency UAH`.
+#[derive(Debug, Clone, Error)]
+#[error(
+    "incomes are not in UAH, convert them at the NBU rate first: {}",
+    .incomes.iter().map(describe_foreign).collect::<Vec<_>>().join(", ")
+)]
+pub struct ForeignIncomesError {
+    pub incomes: Vec<Income>,
+}
+
+fn describe_foreign(income: &Income) -> String {
+    format!(
+        "{} of {} {} on {}",
+        income.income_no,
+        income.amount,
+        income.currency,
+        income.date()
+    )
+}
+
+/// Checks that all incomes are in hryvnias.
+pub fn ensure_uah(incomes: &[Income]) -> Result<(), ForeignIncomesError> {
+    let foreign = incomes
+        .iter()
+        .filter(|income| income.currency != Currency::UAH)
+        .cloned()
+        .collect::<Vec<_>>();
+    if foreign.is_empty() {
+        Ok(())
+    } else {
+        Err(ForeignIncomesError { incomes: foreign })
+    }
+}
+
 impl Income {
     pub fn new(date: NaiveDateTime, amount: Amount) -> Self {
         Self {
@@ -29,6 +93,10 @@ impl Income {
             date,
             amount,
             comment: None,
+            currency: Currency::default(),
+            source_ref: None,
+            tax_paid: false,
+            kind: IncomeKind::default(),
         }
     }
 
@@ -38,27 +106,54 @@ impl Income {
             date: date.and_hms_opt(0, 0, 0).unwrap(),
             amount,
             comment: None,
+            currency: Currency::default(),
+            source_ref: None,
+            tax_paid: false,
+            kind: IncomeKind::default(),
         }
     }
 
     pub fn with_comment(self, comment: String) -> Self {
         Income {
-            income_no: 0,
-            date: self.date,
-            amount: self.amount,
             comment: Some(comment),
+            ..self
         }
     }
 
     pub fn with_no(self, income_no: i64) -> Self {
+        Income { income_no, ..self }
+    }
+
+    pub fn with_date(self, date: NaiveDateTime) -> Self {
+        Income { date, ..self }
+    }
+
+    pub fn with_amount(self, amount: Amount) -> Self {
+        Income { amount, ..self }
+    }
+
+    pub fn with_currency(self, currency: Currency) -> Self {
+        Income { currency, ..self }
+    }
+
+    /// Sets the reference to the income in the source statement.
+    /// It's usually a bank transaction identifier.
+    pub fn with_source_ref(self, source_ref: String) -> Self {
         Income {
-            income_no,
-            date: self.date,
-            amount: self.amount,
-            comment: self.comment,
+            source_ref: Some(source_ref),
+            ..self
         }
     }
 
+    /// Marks whether taxes of the income are paid.
+    pub fn with_tax_paid(self, tax_paid: bool) -> Self {
+        Income { tax_paid, ..self }
+    }
+
+    pub fn with_kind(self, kind: IncomeKind) -> Self {
+        Income { kind, ..self }
+    }
+
     pub fn date(&self) -> NaiveDate {
         self.date.date()
     }
@@ -78,6 +173,55 @@ impl Income {
     pub fn income_no(&self) -> i64 {
         self.income_no
     }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn source_ref(&self) -> Option<&str> {
+        self.source_ref.as_deref()
+    }
+
+    pub fn tax_paid(&self) -> bool {
+        self.tax_paid
+    }
+
+    pub fn kind(&self) -> IncomeKind {
+        self.kind
+    }
+
+    pub fn is_refund(&self) -> bool {
+        self.kind == IncomeKind::Refund
+    }
+}
+
+impl IncomeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncomeKind::Income => "income",
+            IncomeKind::Refund => "refund",
+            IncomeKind::Correction => "correction",
+        }
+    }
+}
+
+impl Display for IncomeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for IncomeKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "income" => Ok(IncomeKind::Income),
+            "refund" => Ok(IncomeKind::Refund),
+            "correction" => Ok(IncomeKind::Correction),
+            _ => anyhow::bail!("unknown income kind {}", s),
+        }
+    }
 }
 
 impl AsRef<NaiveDateTime> for Income {
@@ -131,6 +275,12 @@ impl TryFrom<f64> for Amount {
     }
 }
 
+impl From<Amount> for f64 {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
 impl Eq for Amount {}
 
 impl PartialOrd for Amount {
@@ -173,3 +323,35 @@ impl Sum for Amount {
         iter.reduce(|acc, v| acc + v).unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn income(day: u32, amount: f64) -> Income {
+        let date = NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        Income::from_date(date, Amount::new(amount).unwrap())
+    }
+
+    #[test]
+    fn accept_incomes_in_uah() {
+        assert!(ensure_uah(&[income(1, 100.0), income(2, 200.0)]).is_ok());
+    }
+
+    #[test]
+    fn list_foreign_incomes() {
+        let incomes = [
+            income(1, 100.0),
+            income(5, 2500.0).with_no(2).with_currency(Currency::USD),
+        ];
+
+        let error = ensure_uah(&incomes).unwrap_err();
+
+        assert_eq!(error.incomes.len(), 1);
+        assert_eq!(
+            error.to_string(),
+            "incomes are not in UAH, convert them at the NBU rate first: \
+             2 of 2500 USD on 2024-03-05"
+        );
+    }
+}
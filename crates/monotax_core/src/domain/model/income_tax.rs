@@ -4,6 +4,7 @@ use std::ops::Mul;
 
 use anyhow::Result;
 use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -11,19 +12,29 @@ use super::income::Amount;
 
 pub type TaxID = Uuid;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct IncomeTax {
     id: TaxID,
     name: String,
     rates: Vec<IncomeTaxRate>,
 }
 
-#[derive(Debug)]
+/// Rate of the tax for a period. It's serialized with the start and optional end dates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "IncomeTaxRateDates", into = "IncomeTaxRateDates")]
 pub struct IncomeTaxRate {
     period: Period,
     rate: TaxRate,
 }
 
+#[derive(Serialize, Deserialize)]
+struct IncomeTaxRateDates {
+    start_date: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end_date: Option<NaiveDate>,
+    rate: TaxRate,
+}
+
 #[derive(Debug, Clone, Error)]
 #[error("the end date {end_date} is before the start date {start_date}")]
 pub struct IcorrectTaxRateDatesError {
@@ -31,7 +42,8 @@ pub struct IcorrectTaxRateDatesError {
     pub end_date: NaiveDate,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
 pub struct TaxRate(f64);
 
 #[derive(Debug, Clone, Error)]
@@ -40,7 +52,7 @@ pub struct TaxRateError {
     pub invalid_rate: f64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Period {
     Open {
         start_date: NaiveDate,
@@ -85,6 +97,10 @@ impl IncomeTax {
     pub fn id(&self) -> &TaxID {
         &self.id
     }
+
+    pub fn rates(&self) -> &[IncomeTaxRate] {
+        &self.rates
+    }
 }
 
 impl IncomeTaxRate {
@@ -104,6 +120,24 @@ impl IncomeTaxRate {
         Ok(Self { period, rate })
     }
 
+    pub fn start_date(&self) -> NaiveDate {
+        match self.period {
+            Period::Open { start_date } | Period::Closed { start_date, .. } => start_date,
+        }
+    }
+
+    /// First day the rate is not applied. Open rates have no end.
+    pub fn end_date(&self) -> Option<NaiveDate> {
+        match self.period {
+            Period::Open { .. } => None,
+            Period::Closed { end_date, .. } => Some(end_date),
+        }
+    }
+
+    pub fn rate(&self) -> TaxRate {
+        self.rate
+    }
+
     fn is_applicable(&self, date: NaiveDate) -> bool {
         self.period.contains(&date)
     }
@@ -139,6 +173,12 @@ impl TryFrom<f64> for TaxRate {
     }
 }
 
+impl From<TaxRate> for f64 {
+    fn from(rate: TaxRate) -> Self {
+        rate.0
+    }
+}
+
 impl Eq for TaxRate {}
 
 impl PartialOrd for TaxRate {
@@ -173,6 +213,27 @@ impl Mul<TaxRate> for Amount {
     }
 }
 
+impl TryFrom<IncomeTaxRateDates> for IncomeTaxRate {
+    type Error = IcorrectTaxRateDatesError;
+
+    fn try_from(dates: IncomeTaxRateDates) -> Result<Self, Self::Error> {
+        match dates.end_date {
+            Some(end_date) => IncomeTaxRate::closed(dates.start_date, end_date, dates.rate),
+            None => Ok(IncomeTaxRate::open(dates.start_date, dates.rate)),
+        }
+    }
+}
+
+impl From<IncomeTaxRate> for IncomeTaxRateDates {
+    fn from(rate: IncomeTaxRate) -> Self {
+        Self {
+            start_date: rate.start_date(),
+            end_date: rate.end_date(),
+            rate: rate.rate,
+        }
+    }
+}
+
 impl Period {
     pub fn open(start_date: NaiveDate) -> Self {
         Period::Open { start_date }
@@ -1,4 +1,8 @@
 //! Definitions of domain entities and values.
+pub mod coverage;
+pub mod currency;
+pub mod import_batch;
 pub mod income;
 pub mod income_tax;
+pub mod payment;
 pub mod quarter;
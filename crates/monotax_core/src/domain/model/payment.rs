@@ -0,0 +1,61 @@
+//! Taxes paid to the budget.
+//!
+//! Payments are recorded from bank statements of the account taxes are paid from.
+//! Taxes are paid in hryvnias, so payments have no currency.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::income::Amount;
+
+pub type PaymentID = i64;
+
+/// Payment of a tax. The tax is the name of the income tax like `ЄП`,
+/// payments recorded before taxes were named have no tax.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxPayment {
+    #[serde(default)]
+    id: PaymentID,
+    date: NaiveDate,
+    amount: Amount,
+    #[serde(default)]
+    tax: Option<String>,
+}
+
+impl TaxPayment {
+    pub fn new(date: NaiveDate, amount: Amount) -> Self {
+        Self {
+            id: 0,
+            date,
+            amount,
+            tax: None,
+        }
+    }
+
+    pub fn with_id(self, id: PaymentID) -> Self {
+        Self { id, ..self }
+    }
+
+    pub fn with_tax(self, tax: String) -> Self {
+        Self {
+            tax: Some(tax),
+            ..self
+        }
+    }
+
+    pub fn id(&self) -> PaymentID {
+        self.id
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn tax(&self) -> Option<&str> {
+        self.tax.as_deref()
+    }
+}
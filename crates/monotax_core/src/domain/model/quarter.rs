@@ -34,6 +34,24 @@ impl Quarter {
             Self::Q4 => 4,
         }
     }
+
+    /// Returns the first day of the quarter in the year.
+    pub fn first_day(&self, year: i32) -> NaiveDate {
+        let month = self.index() as u32 * 3 - 2;
+        NaiveDate::from_ymd_opt(year, month, 1).unwrap()
+    }
+
+    /// Returns the last day of the quarter in the year.
+    pub fn last_day(&self, year: i32) -> NaiveDate {
+        match self {
+            Self::Q4 => NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+            _ => Quarter::try_from(self.index() as u32 + 1)
+                .unwrap()
+                .first_day(year)
+                .pred_opt()
+                .unwrap(),
+        }
+    }
 }
 
 impl PartialOrd for Quarter {
@@ -140,6 +158,16 @@ mod tests {
         assert!(Quarter::try_from(5).is_err());
     }
 
+    #[test]
+    fn quarter_bounds() {
+        let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+        assert_eq!(Quarter::Q1.first_day(2024), date("2024-01-01"));
+        assert_eq!(Quarter::Q1.last_day(2024), date("2024-03-31"));
+        assert_eq!(Quarter::Q3.first_day(2024), date("2024-07-01"));
+        assert_eq!(Quarter::Q3.last_day(2024), date("2024-09-30"));
+        assert_eq!(Quarter::Q4.last_day(2024), date("2024-12-31"));
+    }
+
     #[test]
     fn compare_quarters() {
         assert!(Quarter::Q1 < Quarter::Q2);
@@ -0,0 +1,20 @@
+//! Periods covered by imported statements.
+
+use async_trait::async_trait;
+
+use crate::domain::model::coverage::StatementCoverage;
+use crate::domain::model::import_batch::BatchID;
+
+#[async_trait]
+pub trait CoverageRepository {
+    /// Remembers periods covered by the statement.
+    /// Coverage of the import batch is removed when the batch is reverted.
+    async fn save_all(
+        &self,
+        coverages: &[StatementCoverage],
+        batch_id: Option<BatchID>,
+    ) -> anyhow::Result<()>;
+
+    /// Lists all covered periods.
+    async fn find_all(&self) -> anyhow::Result<Vec<StatementCoverage>>;
+}
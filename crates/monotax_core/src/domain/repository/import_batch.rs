@@ -0,0 +1,29 @@
+//! Import batches keep track of where incomes came from.
+
+use async_trait::async_trait;
+
+use crate::domain::model::import_batch::{BatchID, ImportBatch, ImportSource};
+use crate::domain::Income;
+
+#[async_trait]
+pub trait ImportBatchRepository {
+    /// Saves incomes as a new batch of the source.
+    /// Incomes that are already saved are skipped and don't belong to the batch.
+    async fn import(&self, source: ImportSource, incomes: &[Income])
+        -> anyhow::Result<ImportBatch>;
+
+    /// Lists all batches in the order of import.
+    async fn find_all(&self) -> anyhow::Result<Vec<ImportBatch>>;
+
+    async fn find_by_id(&self, id: BatchID) -> anyhow::Result<Option<ImportBatch>>;
+
+    /// Finds batches imported from sources with the same content.
+    async fn find_by_sha256(&self, sha256: &str) -> anyhow::Result<Vec<ImportBatch>>;
+
+    /// Lists incomes saved by the batch.
+    async fn find_incomes(&self, id: BatchID) -> anyhow::Result<Vec<Income>>;
+
+    /// Removes incomes and coverage saved by the batch and marks the batch as reverted.
+    /// Returns the number of removed incomes.
+    async fn revert(&self, id: BatchID) -> anyhow::Result<usize>;
+}
@@ -3,6 +3,8 @@
 //! It can be used as a data source instead of reading bank CSV every time.
 
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use thiserror::Error;
 
 use crate::domain::{filter::income::IncomeCriteria, Income};
 
@@ -15,4 +17,37 @@ pub trait IncomeRepository {
     async fn find_by(&mut self, criteria: IncomeCriteria) -> anyhow::Result<Vec<Income>>;
 
     async fn find_by_payment_no(&mut self, payment_no: i64) -> anyhow::Result<Option<Income>>;
+
+    /// Saves incomes keeping their payment numbers, like when restoring a backup.
+    /// Incomes that are already saved or have taken payment numbers are skipped.
+    /// Returns the number of saved incomes.
+    async fn restore_all(&mut self, incomes: &[Income]) -> anyhow::Result<usize>;
+
+    /// Saves a single income numbering it after the last known payment.
+    /// Returns the saved income with its payment number.
+    /// Fails with [`IncomeRepositoryError::Duplicate`] if the same income is already saved.
+    async fn add(&mut self, income: &Income) -> anyhow::Result<Income>;
+
+    /// Replaces the income that has the same payment number.
+    /// Returns false when there is no such income.
+    async fn update(&mut self, income: &Income) -> anyhow::Result<bool>;
+
+    /// Removes the income by its payment number.
+    /// Returns false when there is no such income.
+    async fn delete(&mut self, payment_no: i64) -> anyhow::Result<bool>;
+}
+
+#[derive(Debug, Error)]
+pub enum IncomeRepositoryError {
+    #[error("income of {amount} on {date} already exists")]
+    Duplicate { date: NaiveDateTime, amount: f64 },
+}
+
+impl IncomeRepositoryError {
+    pub fn duplicate(income: &Income) -> Self {
+        IncomeRepositoryError::Duplicate {
+            date: income.datetime(),
+            amount: income.amount().amount(),
+        }
+    }
 }
@@ -6,4 +6,7 @@ use crate::domain::model::income_tax::IncomeTax;
 pub trait IncomeTaxRepository {
     /// Lists all income taxes.
     async fn find_all(&self) -> anyhow::Result<Vec<IncomeTax>>;
+
+    /// Saves the tax replacing the tax with the same id and its rates.
+    async fn save(&self, tax: &IncomeTax) -> anyhow::Result<()>;
 }
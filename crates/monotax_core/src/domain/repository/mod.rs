@@ -1,6 +1,10 @@
 //! Repository traits and definitions.
 
+pub mod coverage;
+pub mod import_batch;
 pub mod income;
 pub mod income_tax;
+pub mod payment;
+pub mod sync;
 
 pub use income::IncomeRepository;
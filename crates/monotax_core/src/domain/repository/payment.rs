@@ -0,0 +1,21 @@
+//! Recorded tax payments.
+
+use async_trait::async_trait;
+
+use crate::domain::model::payment::{PaymentID, TaxPayment};
+
+#[async_trait]
+pub trait PaymentRepository {
+    /// Saves the payment and returns it with the assigned identifier.
+    async fn add(&self, payment: &TaxPayment) -> anyhow::Result<TaxPayment>;
+
+    /// Saves payments keeping their identifiers. Payments with known identifiers are skipped.
+    /// Returns the number of saved payments.
+    async fn restore_all(&self, payments: &[TaxPayment]) -> anyhow::Result<usize>;
+
+    /// Lists all payments ordered by date.
+    async fn find_all(&self) -> anyhow::Result<Vec<TaxPayment>>;
+
+    /// Removes the payment. Returns `false` when there is no such payment.
+    async fn delete(&self, id: PaymentID) -> anyhow::Result<bool>;
+}
@@ -0,0 +1,18 @@
+//! Progress of statement synchronization with banks.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// Keeps the moment up to which the account statement was synchronized.
+#[async_trait]
+pub trait SyncStateRepository {
+    /// Provides the end of the last synchronized period of the account.
+    async fn last_synced(&self, account: &str) -> anyhow::Result<Option<DateTime<Utc>>>;
+
+    /// Remembers that the account statement is synchronized up to the moment.
+    async fn save_last_synced(&self, account: &str, synced_at: DateTime<Utc>)
+        -> anyhow::Result<()>;
+
+    /// Lists accounts with the end of their last synchronized period.
+    async fn find_all(&self) -> anyhow::Result<Vec<(String, DateTime<Utc>)>>;
+}
@@ -2,7 +2,7 @@
 
 use chrono::{Datelike, Local, NaiveDate};
 
-use crate::domain::filter::income::{QuarterFilter, YearFilter};
+use crate::domain::filter::income::{IncomeCriteria, IncomeCriterion, QuarterFilter, YearFilter};
 use crate::domain::Income;
 use crate::domain::Quarter;
 
@@ -38,6 +38,42 @@ impl YearFilter {
     }
 }
 
+impl IncomeCriteria {
+    /// Determines whether the date passes all criteria.
+    pub fn filter(&self, date: &NaiveDate) -> bool {
+        self.criteria().iter().all(|criterion| match criterion {
+            IncomeCriterion::Quarter(filter) => filter.filter(date),
+            IncomeCriterion::Year(filter) => filter.filter(date),
+        })
+    }
+
+    /// Returns the first and the last days of the period the criteria select.
+    /// Criteria without a particular year don't select a single period.
+    pub fn period(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let mut year = None;
+        let mut quarter = QuarterFilter::Any;
+        for criterion in self.criteria() {
+            match criterion {
+                IncomeCriterion::Year(YearFilter::One(one)) => year = Some(*one),
+                IncomeCriterion::Year(YearFilter::Current) => {
+                    year = Some(Local::now().naive_local().year())
+                }
+                IncomeCriterion::Year(YearFilter::Any) => {}
+                IncomeCriterion::Quarter(filter) => quarter = *filter,
+            }
+        }
+        let year = year?;
+        let (first, last) = match quarter {
+            QuarterFilter::Only(quarter) => (quarter, quarter),
+            QuarterFilter::Ytd(quarter) => (Quarter::Q1, quarter),
+            QuarterFilter::Any => (Quarter::Q1, Quarter::Q4),
+            QuarterFilter::Current => (Quarter::current(), Quarter::current()),
+            QuarterFilter::CurrentToDate => (Quarter::Q1, Quarter::current()),
+        };
+        Some((first.first_day(year), last.last_day(year)))
+    }
+}
+
 impl IncomePredicate for QuarterFilter {
     fn test(&self, income: &Income) -> bool {
         self.filter_income(income)
@@ -54,6 +90,44 @@ impl IncomePredicate for YearFilter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn period_of_criteria() {
+        let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+        let criteria = |year, quarter| {
+            IncomeCriteria::new(&[
+                IncomeCriterion::Year(year),
+                IncomeCriterion::Quarter(quarter),
+            ])
+        };
+
+        assert_eq!(
+            criteria(YearFilter::One(2024), QuarterFilter::Only(Quarter::Q2)).period(),
+            Some((date("2024-04-01"), date("2024-06-30")))
+        );
+        assert_eq!(
+            criteria(YearFilter::One(2024), QuarterFilter::Ytd(Quarter::Q3)).period(),
+            Some((date("2024-01-01"), date("2024-09-30")))
+        );
+        assert_eq!(
+            criteria(YearFilter::One(2024), QuarterFilter::Any).period(),
+            Some((date("2024-01-01"), date("2024-12-31")))
+        );
+        assert_eq!(criteria(YearFilter::Any, QuarterFilter::Any).period(), None);
+    }
+
+    #[test]
+    fn filter_dates_by_criteria() {
+        let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+        let criteria = IncomeCriteria::new(&[
+            IncomeCriterion::Year(YearFilter::One(2024)),
+            IncomeCriterion::Quarter(QuarterFilter::Only(Quarter::Q2)),
+        ]);
+
+        assert!(criteria.filter(&date("2024-04-19")));
+        assert!(!criteria.filter(&date("2024-03-31")));
+        assert!(!criteria.filter(&date("2023-04-19")));
+    }
+
     #[test]
     fn filter_by_exact_quarter() {
         let q1_date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
@@ -0,0 +1,427 @@
+//! Interchange format of the dataset.
+//!
+//! The dataset has incomes with their payment numbers and tax payment marks, income taxes
+//! with their rates, tax payments, periods covered by statements, moments Monobank accounts
+//! are synchronized up to, and metadata of the export. It's written as a JSON document or as
+//! NDJSON, where every line is a record tagged with its `type`: the metadata goes first,
+//! then taxes, incomes, payments, coverages and sync cursors. NDJSON is convenient for scripts
+//! processing records one by one.
+//!
+//! Import batches are not part of the dataset. Restored incomes belong to no batch,
+//! so they can't be reverted with `imports revert`, and statement files imported before
+//! are not recognized as duplicates. Their incomes are still skipped as already saved.
+//!
+//! The `version` of the metadata changes with every incompatible change of the format.
+//! Datasets of newer versions are rejected, older ones are read as far as they are compatible.
+
+use std::io::{BufRead, Write};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::model::coverage::StatementCoverage;
+use crate::domain::model::income_tax::IncomeTax;
+use crate::domain::model::payment::TaxPayment;
+use crate::domain::Income;
+
+/// Version of the format written by this release.
+pub const DATASET_VERSION: u32 = 1;
+
+/// Output format of the dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum DatasetFormat {
+    #[default]
+    Json,
+    Ndjson,
+}
+
+/// Incomes, taxes and their payments along with the metadata of the export.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Dataset {
+    pub metadata: DatasetMetadata,
+    #[serde(default)]
+    pub taxes: Vec<IncomeTax>,
+    #[serde(default)]
+    pub incomes: Vec<Income>,
+    #[serde(default)]
+    pub payments: Vec<TaxPayment>,
+    #[serde(default)]
+    pub coverages: Vec<StatementCoverage>,
+    #[serde(default)]
+    pub sync_cursors: Vec<SyncCursor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetMetadata {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Application that wrote the dataset, like `monotax 0.3.0`.
+    pub generator: String,
+}
+
+/// End of the last synchronized period of the bank account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncCursor {
+    pub account: String,
+    pub synced_until: DateTime<Utc>,
+}
+
+/// Line of the NDJSON dataset.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum DatasetRecord {
+    Metadata(DatasetMetadata),
+    Tax(IncomeTax),
+    Income(Income),
+    Payment(TaxPayment),
+    Coverage(StatementCoverage),
+    Sync(SyncCursor),
+}
+
+#[derive(Debug, Error)]
+pub enum DatasetError {
+    #[error(
+        "dataset version {0} is not supported. the latest supported version is {DATASET_VERSION}"
+    )]
+    UnsupportedVersion(u32),
+    #[error("the first line of the NDJSON dataset must have its metadata")]
+    MissingMetadata,
+    #[error("failed to read line {line} of the dataset: {source}")]
+    InvalidLine {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+impl Dataset {
+    /// Creates the dataset exported now by the generator.
+    pub fn new(generator: String, taxes: Vec<IncomeTax>, incomes: Vec<Income>) -> Self {
+        Self {
+            metadata: DatasetMetadata {
+                version: DATASET_VERSION,
+                exported_at: Utc::now(),
+                generator,
+            },
+            taxes,
+            incomes,
+            payments: vec![],
+            coverages: vec![],
+            sync_cursors: vec![],
+        }
+    }
+
+    pub fn with_payments(self, payments: Vec<TaxPayment>) -> Self {
+        Self { payments, ..self }
+    }
+
+    pub fn with_coverages(self, coverages: Vec<StatementCoverage>) -> Self {
+        Self { coverages, ..self }
+    }
+
+    pub fn with_sync_cursors(self, sync_cursors: Vec<SyncCursor>) -> Self {
+        Self {
+            sync_cursors,
+            ..self
+        }
+    }
+
+    pub fn write(&self, format: DatasetFormat, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match format {
+            DatasetFormat::Json => {
+                serde_json::to_writer_pretty(&mut *writer, self)?;
+                writeln!(writer)?;
+            }
+            DatasetFormat::Ndjson => {
+                write_line(writer, &DatasetRecord::Metadata(self.metadata.clone()))?;
+                for tax in &self.taxes {
+                    write_line(writer, &TaxRecord::from(tax))?;
+                }
+                for income in &self.incomes {
+                    write_line(writer, &IncomeRecord::from(income))?;
+                }
+                for payment in &self.payments {
+                    write_line(writer, &PaymentRecord::from(payment))?;
+                }
+                for coverage in &self.coverages {
+                    write_line(writer, &CoverageRecord::from(coverage))?;
+                }
+                for cursor in &self.sync_cursors {
+                    write_line(writer, &SyncRecord::from(cursor))?;
+                }
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads the dataset in any format. NDJSON is told by the `type` of the first line.
+    pub fn read(reader: impl BufRead) -> anyhow::Result<Self> {
+        let mut lines = reader.lines();
+        let first = lines.next().transpose()?.unwrap_or_default();
+        let dataset = match serde_json::from_str::<DatasetRecord>(&first) {
+            Ok(DatasetRecord::Metadata(metadata)) => read_records(metadata, lines)?,
+            Ok(_) => return Err(DatasetError::MissingMetadata.into()),
+            Err(_) => {
+                let mut content = first;
+                for line in lines {
+                    content.push('\n');
+                    content.push_str(&line?);
+                }
+                serde_json::from_str::<Dataset>(&content)?
+            }
+        };
+        if dataset.metadata.version > DATASET_VERSION {
+            return Err(DatasetError::UnsupportedVersion(dataset.metadata.version).into());
+        }
+        Ok(dataset)
+    }
+}
+
+fn read_records(
+    metadata: DatasetMetadata,
+    lines: impl Iterator<Item = std::io::Result<String>>,
+) -> anyhow::Result<Dataset> {
+    if metadata.version > DATASET_VERSION {
+        return Err(DatasetError::UnsupportedVersion(metadata.version).into());
+    }
+    let mut dataset = Dataset {
+        metadata,
+        taxes: vec![],
+        incomes: vec![],
+        payments: vec![],
+        coverages: vec![],
+        sync_cursors: vec![],
+    };
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str::<DatasetRecord>(&line).map_err(|source| {
+            DatasetError::InvalidLine {
+                // the metadata is the first line
+                line: index + 2,
+                source,
+            }
+        })?;
+        match record {
+            DatasetRecord::Metadata(_) => return Err(DatasetError::MissingMetadata.into()),
+            DatasetRecord::Tax(tax) => dataset.taxes.push(tax),
+            DatasetRecord::Income(income) => dataset.incomes.push(income),
+            DatasetRecord::Payment(payment) => dataset.payments.push(payment),
+            DatasetRecord::Coverage(coverage) => dataset.coverages.push(coverage),
+            DatasetRecord::Sync(cursor) => dataset.sync_cursors.push(cursor),
+        }
+    }
+    Ok(dataset)
+}
+
+fn write_line(writer: &mut dyn Write, record: &impl Serialize) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Borrowed tax line. Written records are read as [`DatasetRecord::Tax`].
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "tax")]
+struct TaxRecord<'a> {
+    #[serde(flatten)]
+    tax: &'a IncomeTax,
+}
+
+/// Borrowed income line. Written records are read as [`DatasetRecord::Income`].
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "income")]
+struct IncomeRecord<'a> {
+    #[serde(flatten)]
+    income: &'a Income,
+}
+
+/// Borrowed payment line. Written records are read as [`DatasetRecord::Payment`].
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "payment")]
+struct PaymentRecord<'a> {
+    #[serde(flatten)]
+    payment: &'a TaxPayment,
+}
+
+/// Borrowed coverage line. Written records are read as [`DatasetRecord::Coverage`].
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "coverage")]
+struct CoverageRecord<'a> {
+    #[serde(flatten)]
+    coverage: &'a StatementCoverage,
+}
+
+/// Borrowed sync cursor line. Written records are read as [`DatasetRecord::Sync`].
+#[derive(Serialize)]
+#[serde(tag = "type", rename = "sync")]
+struct SyncRecord<'a> {
+    #[serde(flatten)]
+    cursor: &'a SyncCursor,
+}
+
+impl<'a> From<&'a IncomeTax> for TaxRecord<'a> {
+    fn from(tax: &'a IncomeTax) -> Self {
+        Self { tax }
+    }
+}
+
+impl<'a> From<&'a Income> for IncomeRecord<'a> {
+    fn from(income: &'a Income) -> Self {
+        Self { income }
+    }
+}
+
+impl<'a> From<&'a TaxPayment> for PaymentRecord<'a> {
+    fn from(payment: &'a TaxPayment) -> Self {
+        Self { payment }
+    }
+}
+
+impl<'a> From<&'a StatementCoverage> for CoverageRecord<'a> {
+    fn from(coverage: &'a StatementCoverage) -> Self {
+        Self { coverage }
+    }
+}
+
+impl<'a> From<&'a SyncCursor> for SyncRecord<'a> {
+    fn from(cursor: &'a SyncCursor) -> Self {
+        Self { cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use crate::domain::model::income::{Amount, IncomeKind};
+    use crate::domain::model::income_tax::{IncomeTaxRate, TaxRate};
+    use crate::domain::Currency;
+
+    use super::*;
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    fn dataset() -> Dataset {
+        let rate = |raw| TaxRate::new(raw).unwrap();
+        let tax = IncomeTax::new(
+            Uuid::new_v4(),
+            "Military levy".to_string(),
+            vec![
+                IncomeTaxRate::closed(date("2024-01-01"), date("2025-01-01"), rate(0.015)).unwrap(),
+                IncomeTaxRate::open(date("2025-01-01"), rate(0.01)),
+            ],
+        );
+        let incomes = vec![
+            Income::from_date(date("2024-02-10"), Amount::new(1000.5).unwrap())
+                .with_comment("Invoice 1".to_string())
+                .with_no(3)
+                .with_tax_paid(true),
+            Income::from_date(date("2024-03-01"), Amount::new(250.0).unwrap())
+                .with_no(4)
+                .with_currency(Currency::USD)
+                .with_source_ref("REF-2".to_string())
+                .with_kind(IncomeKind::Refund),
+        ];
+        Dataset::new("monotax test".to_string(), vec![tax], incomes)
+            .with_payments(vec![TaxPayment::new(
+                date("2024-04-19"),
+                Amount::new(50.0).unwrap(),
+            )
+            .with_id(2)
+            .with_tax("Military levy".to_string())])
+            .with_coverages(vec![StatementCoverage::new(
+                "UA213223130000026007233566001".to_string(),
+                date("2024-01-01"),
+                date("2024-03-31"),
+            )])
+            .with_sync_cursors(vec![SyncCursor {
+                account: "uah".to_string(),
+                synced_until: "2024-04-01T10:00:00Z".parse().unwrap(),
+            }])
+    }
+
+    fn write(dataset: &Dataset, format: DatasetFormat) -> Vec<u8> {
+        let mut buffer = vec![];
+        dataset.write(format, &mut buffer).unwrap();
+        buffer
+    }
+
+    fn assert_same(read: &Dataset, original: &Dataset) {
+        assert_eq!(read.incomes.len(), original.incomes.len());
+        assert_eq!(read.metadata.version, DATASET_VERSION);
+        assert_eq!(read.metadata.exported_at, original.metadata.exported_at);
+        let tax = &read.taxes[0];
+        assert_eq!(tax.id(), original.taxes[0].id());
+        assert_eq!(tax.name(), "Military levy");
+        assert_eq!(tax.rates()[0].end_date(), Some(date("2025-01-01")));
+        assert_eq!(tax.rates()[1].end_date(), None);
+        assert_eq!(tax.rates()[1].rate().rate(), 0.01);
+        for (income, expected) in read.incomes.iter().zip(&original.incomes) {
+            assert_eq!(income.income_no(), expected.income_no());
+            assert_eq!(income.datetime(), expected.datetime());
+            assert_eq!(income.amount(), expected.amount());
+            assert_eq!(income.comment(), expected.comment());
+            assert_eq!(income.currency(), expected.currency());
+            assert_eq!(income.source_ref(), expected.source_ref());
+            assert_eq!(income.tax_paid(), expected.tax_paid());
+            assert_eq!(income.kind(), expected.kind());
+        }
+        assert_eq!(read.payments, original.payments);
+        assert_eq!(read.coverages, original.coverages);
+        assert_eq!(read.sync_cursors, original.sync_cursors);
+    }
+
+    #[test]
+    fn read_written_json() {
+        let original = dataset();
+        let buffer = write(&original, DatasetFormat::Json);
+
+        let read = Dataset::read(buffer.as_slice()).unwrap();
+        assert_same(&read, &original);
+    }
+
+    #[test]
+    fn read_written_ndjson() {
+        let original = dataset();
+        let buffer = write(&original, DatasetFormat::Ndjson);
+        let types = String::from_utf8(buffer.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["type"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            ["metadata", "tax", "income", "income", "payment", "coverage", "sync"]
+        );
+
+        let read = Dataset::read(buffer.as_slice()).unwrap();
+        assert_same(&read, &original);
+    }
+
+    #[test]
+    fn reject_newer_versions() {
+        let content = format!(
+            r#"{{"type":"metadata","version":{},"exported_at":"2024-01-01T00:00:00Z","generator":"monotax"}}"#,
+            DATASET_VERSION + 1
+        );
+        let error = Dataset::read(content.as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("is not supported"));
+    }
+
+    #[test]
+    fn reject_invalid_values() {
+        let content = r#"{"type":"metadata","version":1,"exported_at":"2024-01-01T00:00:00Z","generator":"monotax"}
+{"type":"income","date":"2024-01-01T00:00:00","amount":-5.0}
+"#;
+        let error = Dataset::read(content.as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{}", error);
+    }
+}
@@ -1,2 +1,5 @@
 //! Integrations with external systems.
+pub mod dataset;
+pub mod payment;
+pub mod statement;
 pub mod taxer;
@@ -0,0 +1,43 @@
+//! Payment orders for banks.
+//!
+//! Orders are credit transfers in hryvnias from the account of the entrepreneur. They are
+//! created by integrations with tax authorities and written in formats banking systems import.
+
+use chrono::NaiveDate;
+
+use crate::domain::model::income::Amount;
+
+/// Party of a payment with its bank account.
+///
+/// The `code` is the tax number of a person (РНОКПП) or the EDRPOU code of an organization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentParty {
+    pub name: String,
+    pub code: String,
+    pub iban: String,
+}
+
+/// Credit transfer in hryvnias.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentOrder {
+    /// Number of the order among orders of the date, starting from one.
+    pub number: u32,
+    pub date: NaiveDate,
+    pub amount: Amount,
+    pub payer: PaymentParty,
+    pub recipient: PaymentParty,
+    pub purpose: String,
+}
+
+impl PaymentOrder {
+    /// Amount rounded to kopecks, halves are rounded up.
+    pub fn kopecks(&self) -> i64 {
+        (self.amount.amount() * 100.0).round() as i64
+    }
+
+    /// Amount with two decimals separated by a dot.
+    pub fn formatted_amount(&self) -> String {
+        let kopecks = self.kopecks();
+        format!("{}.{:02}", kopecks / 100, kopecks % 100)
+    }
+}
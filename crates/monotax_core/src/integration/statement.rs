@@ -0,0 +1,259 @@
+//! Bank statement readers.
+//!
+//! Every bank exports statements in its own format.
+//! A [`StatementReader`] knows how to recognize one format and how to read incomes from it.
+//! Readers are collected in [`StatementReaders`] registry that picks the right reader
+//! either by its name or by sniffing the statement content.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use monotax_core::domain::Income;
+//! use monotax_core::integration::statement::{StatementReader, StatementReaders};
+//!
+//! struct EmptyReader;
+//!
+//! impl StatementReader for EmptyReader {
+//!     fn format(&self) -> &str {
+//!         "empty"
+//!     }
+//!
+//!     fn detect(&self, content: &[u8]) -> bool {
+//!         content.is_empty()
+//!     }
+//!
+//!     fn read_incomes(&self, _content: &[u8]) -> anyhow::Result<Vec<Income>> {
+//!         Ok(vec![])
+//!     }
+//! }
+//!
+//! let readers = StatementReaders::new().with_reader(EmptyReader);
+//! let statement = readers.read(b"", None).unwrap();
+//! assert_eq!(statement.format(), "empty");
+//! ```
+
+use thiserror::Error;
+
+use crate::domain::model::coverage::StatementCoverage;
+use crate::domain::Income;
+
+/// Reads incomes from a statement of a particular format.
+pub trait StatementReader: Send + Sync {
+    /// Short name of the format. Used to pick the reader explicitly.
+    fn format(&self) -> &str;
+
+    /// Checks whether the content looks like a statement of the reader's format.
+    fn detect(&self, content: &[u8]) -> bool;
+
+    /// Reads all incomes from the statement content.
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>>;
+
+    /// Reads periods of accounts the statement covers.
+    ///
+    /// Formats that don't state their periods return nothing,
+    /// then no coverage is recorded for the statement.
+    fn read_coverage(&self, _content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        Ok(vec![])
+    }
+}
+
+/// Registry of known statement readers.
+#[derive(Default)]
+pub struct StatementReaders {
+    readers: Vec<Box<dyn StatementReader>>,
+}
+
+/// Incomes read from a statement along with the format of the statement.
+#[derive(Debug)]
+pub struct Statement {
+    format: String,
+    incomes: Vec<Income>,
+    coverages: Vec<StatementCoverage>,
+}
+
+#[derive(Debug, Error)]
+pub enum StatementError {
+    #[error("unknown statement format {format}. known formats are: {}", known.join(", "))]
+    UnknownFormat { format: String, known: Vec<String> },
+    #[error("cannot detect the statement format. consider specifying it explicitly")]
+    UndetectedFormat,
+}
+
+impl StatementReaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the reader to the registry.
+    ///
+    /// The format detection checks readers in the order of registration.
+    pub fn with_reader(mut self, reader: impl StatementReader + 'static) -> Self {
+        self.readers.push(Box::new(reader));
+        self
+    }
+
+    /// Finds the reader by the name of its format.
+    pub fn find(&self, format: &str) -> Option<&dyn StatementReader> {
+        self.readers
+            .iter()
+            .find(|reader| reader.format().eq_ignore_ascii_case(format))
+            .map(|reader| reader.as_ref())
+    }
+
+    /// Finds the first reader that recognizes the content.
+    pub fn detect(&self, content: &[u8]) -> Option<&dyn StatementReader> {
+        self.readers
+            .iter()
+            .find(|reader| reader.detect(content))
+            .map(|reader| reader.as_ref())
+    }
+
+    /// Lists names of all registered formats.
+    pub fn formats(&self) -> Vec<&str> {
+        self.readers.iter().map(|reader| reader.format()).collect()
+    }
+
+    /// Reads the statement using the reader for the format.
+    /// Detects the format from the content when the format is not specified.
+    pub fn read(&self, content: &[u8], format: Option<&str>) -> anyhow::Result<Statement> {
+        let reader = match format {
+            Some(format) => self
+                .find(format)
+                .ok_or_else(|| StatementError::UnknownFormat {
+                    format: format.to_string(),
+                    known: self.formats().iter().map(|f| f.to_string()).collect(),
+                })?,
+            None => self
+                .detect(content)
+                .ok_or(StatementError::UndetectedFormat)?,
+        };
+        let incomes = reader.read_incomes(content)?;
+        let coverages = reader.read_coverage(content)?;
+        Ok(Statement::new(reader.format().to_string(), incomes).with_coverages(coverages))
+    }
+}
+
+impl Statement {
+    /// Creates the statement that covers no period.
+    pub fn new(format: String, incomes: Vec<Income>) -> Self {
+        Self {
+            format,
+            incomes,
+            coverages: vec![],
+        }
+    }
+
+    /// Sets periods of accounts stated in the statement.
+    pub fn with_coverages(self, coverages: Vec<StatementCoverage>) -> Self {
+        Self { coverages, ..self }
+    }
+
+    /// Name of the format the statement was read with.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn incomes(&self) -> &[Income] {
+        &self.incomes
+    }
+
+    pub fn into_incomes(self) -> Vec<Income> {
+        self.incomes
+    }
+
+    /// Periods of accounts the statement covers.
+    pub fn coverages(&self) -> &[StatementCoverage] {
+        &self.coverages
+    }
+
+    /// Splits the statement into its incomes and coverage.
+    pub fn into_parts(self) -> (Vec<Income>, Vec<StatementCoverage>) {
+        (self.incomes, self.coverages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::domain::model::income::Amount;
+
+    use super::*;
+
+    struct PrefixReader {
+        format: &'static str,
+        prefix: &'static [u8],
+        amount: f64,
+    }
+
+    impl StatementReader for PrefixReader {
+        fn format(&self) -> &str {
+            self.format
+        }
+
+        fn detect(&self, content: &[u8]) -> bool {
+            content.starts_with(self.prefix)
+        }
+
+        fn read_incomes(&self, _content: &[u8]) -> anyhow::Result<Vec<Income>> {
+            let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            Ok(vec![Income::from_date(date, Amount::new(self.amount)?)])
+        }
+    }
+
+    fn readers() -> StatementReaders {
+        StatementReaders::new()
+            .with_reader(PrefixReader {
+                format: "alpha",
+                prefix: b"alpha",
+                amount: 1.0,
+            })
+            .with_reader(PrefixReader {
+                format: "beta",
+                prefix: b"beta",
+                amount: 2.0,
+            })
+    }
+
+    #[test]
+    fn detect_format_from_content() {
+        let statement = readers().read(b"beta;1;2;3", None).unwrap();
+
+        assert_eq!(statement.format(), "beta");
+        assert_eq!(statement.incomes()[0].amount(), Amount::new(2.0).unwrap());
+    }
+
+    #[test]
+    fn use_explicit_format() {
+        let statement = readers().read(b"beta;1;2;3", Some("ALPHA")).unwrap();
+
+        assert_eq!(statement.format(), "alpha");
+    }
+
+    #[test]
+    fn record_no_coverage_when_period_is_not_stated() {
+        let statement = readers().read(b"alpha", None).unwrap();
+
+        assert!(statement.coverages().is_empty());
+    }
+
+    #[test]
+    fn reject_unknown_format() {
+        let err = readers().read(b"alpha", Some("gamma")).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<StatementError>(),
+            Some(StatementError::UnknownFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_unrecognized_content() {
+        let err = readers().read(b"gamma", None).unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<StatementError>(),
+            Some(StatementError::UndetectedFormat)
+        ));
+    }
+}
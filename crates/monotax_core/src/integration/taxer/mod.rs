@@ -0,0 +1,455 @@
+//! Integration with Taxer software.
+//!
+//! The [taxer](https://taxer.ua/) is a Ukrainian software for accounting and tax reporting.
+//! This module provides a way to export income data to a CSV file that can be imported into Taxer.
+//! Recorded tax payments are exported as expense operations. Payment files always have
+//! the header row with the operation column, so they are not read back as incomes.
+//! Incomes kept in Taxer are read back with the [`reader::TaxerStatementReader`].
+
+pub mod reader;
+
+use std::io::Write;
+
+use clap::ValueEnum;
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::payment::TaxPayment;
+use crate::domain::{Currency, Income};
+
+const DATE_FORMAT: &str = "%d.%m.%Y";
+const OPERATION_HEADER: &str = "Тип операції";
+const EXPENSE_OPERATION: &str = "Витрата";
+
+pub struct TaxerIncome<'a> {
+    income: &'a Income,
+    tax_number: &'a str,
+    comment: &'a str,
+    account: &'a str,
+}
+
+/// Tax payment to import into Taxer as an expense.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxerPayment {
+    date: chrono::NaiveDate,
+    amount: f64,
+    comment: String,
+    counterparty: String,
+}
+
+/// Layout of the Taxer import file.
+///
+/// - The `short` template has the tax number, date, amount and comment.
+/// - The `full` template adds the account, currency, counterparty and document number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxerTemplate {
+    #[default]
+    Short,
+    Full,
+}
+
+/// Configuration for the Taxer import.
+///
+/// - The `id` is person's national tax identifier.
+/// - The `account_name` is the name of the account in the Taxer.
+/// - The `default_comment` is a comment that will be used if the income has no comment.
+/// - The `template` is the layout of exported files.
+/// - The `header` adds the header row to exported files.
+/// - The `accounts` are Taxer accounts of currencies when there are several bank accounts.
+///   Incomes in other currencies are exported to the `account_name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxerImportConfig {
+    id: String,
+    account_name: String,
+    default_comment: String,
+    #[serde(default)]
+    template: TaxerTemplate,
+    #[serde(default)]
+    header: bool,
+    #[serde(default)]
+    accounts: Vec<TaxerAccount>,
+}
+
+/// Taxer account receiving incomes in the currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxerAccount {
+    pub name: String,
+    pub currency: String,
+}
+
+/// Export incomes to a CSV file that can be imported into Taxer.
+/// Refunds are not incomes in Taxer and are skipped.
+pub fn export_csv<W>(
+    income: impl IntoIterator<Item = Income>,
+    config: &TaxerImportConfig,
+    writer: W,
+) -> anyhow::Result<()>
+where
+    W: Write,
+{
+    let incomes = income.into_iter().collect::<Vec<_>>();
+    let taxer_records: Vec<TaxerIncome> = incomes
+        .iter()
+        .filter(|income| !income.is_refund())
+        .map(|income| {
+            let tax_number = config.id();
+            let comment = income.comment().unwrap_or(config.default_comment());
+            let account = config.account_of(income.currency());
+            TaxerIncome::new(income, tax_number, comment).with_account(account)
+        })
+        .collect();
+    let mut csv_writer = csv::WriterBuilder::new().from_writer(writer);
+    write_header(&mut csv_writer, config)?;
+    for record in taxer_records {
+        record.write(&mut csv_writer, config.template())?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// Export tax payments to a CSV file that can be imported into Taxer as expenses.
+/// Payments are made in hryvnias from the account of the currency.
+/// The header row is always written, the last column marks rows as expenses.
+pub fn export_payments_csv<W>(
+    payments: &[TaxerPayment],
+    config: &TaxerImportConfig,
+    writer: W,
+) -> anyhow::Result<()>
+where
+    W: Write,
+{
+    let mut csv_writer = csv::WriterBuilder::new().from_writer(writer);
+    let mut header = header(config.template());
+    header.push(OPERATION_HEADER);
+    csv_writer.write_record(header)?;
+    for payment in payments {
+        payment.write(&mut csv_writer, config)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// Keeps incomes of the Taxer account. Fails if the account is not configured.
+pub fn select_account(
+    incomes: Vec<Income>,
+    config: &TaxerImportConfig,
+    account: &str,
+) -> anyhow::Result<Vec<Income>> {
+    let known = config.account_name() == account
+        || config.accounts().iter().any(|known| known.name == account);
+    if !known {
+        anyhow::bail!("taxer account {} is not configured", account);
+    }
+    Ok(incomes
+        .into_iter()
+        .filter(|income| config.account_of(income.currency()) == account)
+        .collect())
+}
+
+fn write_header<W: Write>(
+    writer: &mut Writer<W>,
+    config: &TaxerImportConfig,
+) -> anyhow::Result<()> {
+    if !config.header() {
+        return Ok(());
+    }
+    writer.write_record(header(config.template()))?;
+    Ok(())
+}
+
+fn header(template: TaxerTemplate) -> Vec<&'static str> {
+    let mut header = vec!["Податковий номер", "Дата", "Сума", "Коментар"];
+    if template == TaxerTemplate::Full {
+        header.extend(["Рахунок", "Валюта", "Контрагент", "Номер документа"]);
+    }
+    header
+}
+
+impl<'a> TaxerIncome<'a> {
+    pub fn new(income: &'a Income, tax_number: &'a str, comment: &'a str) -> Self {
+        Self {
+            income,
+            tax_number,
+            comment,
+            account: "",
+        }
+    }
+
+    pub fn with_account(self, account: &'a str) -> Self {
+        Self { account, ..self }
+    }
+
+    /// Writes the income. Monotax doesn't keep counterparties, so the column is empty.
+    /// The document number is the bank reference or the payment number.
+    pub fn write<W>(&self, writer: &mut Writer<W>, template: TaxerTemplate) -> anyhow::Result<()>
+    where
+        W: Write,
+    {
+        let date = self.income.date().format(DATE_FORMAT).to_string();
+        let amount = format!("{:.2}", self.income.amount().amount());
+        let short = [self.tax_number, date.as_str(), &amount, self.comment];
+        match template {
+            TaxerTemplate::Short => writer.write_record(short)?,
+            TaxerTemplate::Full => {
+                let currency = self.income.currency().to_string();
+                let document = match self.income.source_ref() {
+                    Some(reference) => reference.to_string(),
+                    None => self.income.income_no().to_string(),
+                };
+                writer.write_record(short.into_iter().chain([
+                    self.account,
+                    &currency,
+                    "",
+                    &document,
+                ]))?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TaxerPayment {
+    pub fn new(
+        date: chrono::NaiveDate,
+        amount: f64,
+        comment: String,
+        counterparty: String,
+    ) -> Self {
+        Self {
+            date,
+            amount,
+            comment,
+            counterparty,
+        }
+    }
+
+    /// Expense of the recorded tax payment.
+    pub fn from_payment(payment: &TaxPayment, counterparty: &str) -> Self {
+        let comment = match payment.tax() {
+            Some(tax) => format!("{} payment", tax),
+            None => "Tax payment".to_string(),
+        };
+        Self::new(
+            payment.date(),
+            payment.amount().amount(),
+            comment,
+            counterparty.to_string(),
+        )
+    }
+
+    pub fn date(&self) -> chrono::NaiveDate {
+        self.date
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    fn write<W>(&self, writer: &mut Writer<W>, config: &TaxerImportConfig) -> anyhow::Result<()>
+    where
+        W: Write,
+    {
+        let date = self.date.format(DATE_FORMAT).to_string();
+        let amount = format!("{:.2}", self.amount);
+        let short = [config.id(), date.as_str(), &amount, &self.comment];
+        match config.template() {
+            TaxerTemplate::Short => {
+                writer.write_record(short.into_iter().chain([EXPENSE_OPERATION]))?
+            }
+            TaxerTemplate::Full => writer.write_record(short.into_iter().chain([
+                config.account_of(Currency::UAH),
+                "UAH",
+                &self.counterparty,
+                "",
+                EXPENSE_OPERATION,
+            ]))?,
+        }
+        Ok(())
+    }
+}
+
+impl TaxerImportConfig {
+    /// Provide the national tax identifier.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Provide the name of the account in the Taxer.
+    pub fn account_name(&self) -> &str {
+        &self.account_name
+    }
+
+    /// Provide the default comment that will be used if the income has no comment.
+    pub fn default_comment(&self) -> &str {
+        &self.default_comment
+    }
+
+    pub fn template(&self) -> TaxerTemplate {
+        self.template
+    }
+
+    pub fn header(&self) -> bool {
+        self.header
+    }
+
+    pub fn accounts(&self) -> &[TaxerAccount] {
+        &self.accounts
+    }
+
+    pub fn with_template(self, template: TaxerTemplate) -> Self {
+        Self { template, ..self }
+    }
+
+    pub fn with_header(self, header: bool) -> Self {
+        Self { header, ..self }
+    }
+
+    pub fn with_accounts(self, accounts: Vec<TaxerAccount>) -> Self {
+        Self { accounts, ..self }
+    }
+
+    /// Name of the Taxer account receiving incomes in the currency.
+    pub fn account_of(&self, currency: Currency) -> &str {
+        self.accounts
+            .iter()
+            .find(|account| account.currency.eq_ignore_ascii_case(&currency.to_string()))
+            .map(|account| account.name.as_str())
+            .unwrap_or(&self.account_name)
+    }
+}
+
+impl Default for TaxerImportConfig {
+    fn default() -> Self {
+        Self {
+            id: "1234567890".to_string(),
+            account_name: Default::default(),
+            default_comment: Default::default(),
+            template: TaxerTemplate::default(),
+            header: false,
+            accounts: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::domain::model::income::{Amount, IncomeKind};
+
+    use super::*;
+
+    fn config() -> TaxerImportConfig {
+        TaxerImportConfig {
+            id: "1234567890".to_string(),
+            account_name: "Main".to_string(),
+            default_comment: "Income".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    fn incomes() -> Vec<Income> {
+        vec![
+            Income::from_date(date("2024-02-10"), Amount::new(1000.0).unwrap())
+                .with_comment("Invoice 1".to_string())
+                .with_no(3),
+            Income::from_date(date("2024-03-01"), Amount::new(250.5).unwrap())
+                .with_no(4)
+                .with_currency(Currency::USD)
+                .with_source_ref("REF-42".to_string()),
+            Income::from_date(date("2024-03-02"), Amount::new(100.0).unwrap())
+                .with_no(5)
+                .with_kind(IncomeKind::Refund),
+        ]
+    }
+
+    fn export(config: &TaxerImportConfig, incomes: Vec<Income>) -> String {
+        let mut buffer = vec![];
+        export_csv(incomes, config, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn export_short_template() {
+        let config = config().with_template(TaxerTemplate::Short);
+
+        let expected = "\
+1234567890,10.02.2024,1000.00,Invoice 1
+1234567890,01.03.2024,250.50,Income
+";
+        assert_eq!(export(&config, incomes()), expected);
+    }
+
+    #[test]
+    fn export_full_template_with_header() {
+        let config = config()
+            .with_template(TaxerTemplate::Full)
+            .with_header(true)
+            .with_accounts(vec![TaxerAccount {
+                name: "Currency".to_string(),
+                currency: "usd".to_string(),
+            }]);
+
+        let expected = "\
+Податковий номер,Дата,Сума,Коментар,Рахунок,Валюта,Контрагент,Номер документа
+1234567890,10.02.2024,1000.00,Invoice 1,Main,UAH,,3
+1234567890,01.03.2024,250.50,Income,Currency,USD,,REF-42
+";
+        assert_eq!(export(&config, incomes()), expected);
+    }
+
+    #[test]
+    fn select_incomes_of_account() {
+        let config = config().with_accounts(vec![TaxerAccount {
+            name: "Currency".to_string(),
+            currency: "USD".to_string(),
+        }]);
+
+        let selected = select_account(incomes(), &config, "Currency").unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].income_no(), 4);
+        assert!(select_account(incomes(), &config, "Unknown").is_err());
+    }
+
+    #[test]
+    fn export_payments() {
+        let payment = TaxPayment::new(date("2024-08-09"), Amount::new(200.0).unwrap())
+            .with_tax("Single tax".to_string());
+        let payments = vec![
+            TaxerPayment::from_payment(&payment, "Tax office"),
+            TaxerPayment::new(
+                date("2024-08-12"),
+                40.0,
+                "Tax payment".to_string(),
+                "Tax office".to_string(),
+            ),
+        ];
+        let export = |config: &TaxerImportConfig| {
+            let mut buffer = vec![];
+            export_payments_csv(&payments, config, &mut buffer).unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        let expected = "\
+Податковий номер,Дата,Сума,Коментар,Тип операції
+1234567890,09.08.2024,200.00,Single tax payment,Витрата
+1234567890,12.08.2024,40.00,Tax payment,Витрата
+";
+        assert_eq!(export(&config()), expected);
+        let expected = "\
+Податковий номер,Дата,Сума,Коментар,Рахунок,Валюта,Контрагент,Номер документа,Тип операції
+1234567890,09.08.2024,200.00,Single tax payment,Main,UAH,Tax office,,Витрата
+";
+        let full = export(&config().with_template(TaxerTemplate::Full));
+        assert!(full.starts_with(expected), "{}", full);
+    }
+}
@@ -0,0 +1,287 @@
+//! Reader of incomes kept in Taxer.
+//!
+//! Reads files written by [`export_csv`](super::export_csv) with both templates, with or
+//! without the header row, and income exports of Taxer. Taxer exports have a header row,
+//! columns are found by their names. Only files with the tax number or the operation column
+//! are detected as Taxer files. Operations other than incomes and refunds are skipped.
+//! Exports without the operation column have refunds as negative amounts.
+
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::domain::model::income::{Amount, IncomeKind};
+use crate::domain::{Currency, Income};
+use crate::integration::statement::StatementReader;
+
+use super::DATE_FORMAT;
+
+const TAX_NUMBER_HEADERS: [&str; 1] = ["податковий номер"];
+const DATE_HEADERS: [&str; 2] = ["дата", "дата операції"];
+const AMOUNT_HEADERS: [&str; 2] = ["сума", "сума операції"];
+const COMMENT_HEADERS: [&str; 3] = ["коментар", "призначення платежу", "опис"];
+const CURRENCY_HEADERS: [&str; 1] = ["валюта"];
+const DOCUMENT_HEADERS: [&str; 1] = ["номер документа"];
+const OPERATION_HEADERS: [&str; 2] = ["тип операції", "операція"];
+
+/// Reads incomes from Taxer CSV files.
+pub struct TaxerStatementReader;
+
+/// Positions of known columns.
+#[derive(Debug, PartialEq)]
+struct Columns {
+    date: usize,
+    amount: usize,
+    comment: Option<usize>,
+    currency: Option<usize>,
+    document: Option<usize>,
+    operation: Option<usize>,
+}
+
+impl Columns {
+    /// Columns of files written by monotax without the header row.
+    fn positional(row: &[String]) -> Option<Self> {
+        let looks_like_export = row.len() >= 4
+            && is_tax_number(&row[0])
+            && NaiveDate::parse_from_str(row[1].trim(), DATE_FORMAT).is_ok();
+        looks_like_export.then(|| Self {
+            date: 1,
+            amount: 2,
+            comment: Some(3),
+            currency: (row.len() >= 8).then_some(5),
+            document: (row.len() >= 8).then_some(7),
+            operation: None,
+        })
+    }
+
+    fn from_header(row: &[String]) -> Option<Self> {
+        let find = |names: &[&str]| {
+            row.iter()
+                .position(|cell| names.contains(&cell.trim().to_lowercase().as_str()))
+        };
+        // the tax number or the operation tells Taxer files from other statements with dates
+        find(&TAX_NUMBER_HEADERS).or(find(&OPERATION_HEADERS))?;
+        Some(Self {
+            date: find(&DATE_HEADERS)?,
+            amount: find(&AMOUNT_HEADERS)?,
+            comment: find(&COMMENT_HEADERS),
+            currency: find(&CURRENCY_HEADERS),
+            document: find(&DOCUMENT_HEADERS),
+            operation: find(&OPERATION_HEADERS),
+        })
+    }
+
+    /// Reads the income from the row. Rows of other operations are skipped.
+    fn income(&self, row: &[String]) -> anyhow::Result<Option<Income>> {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| row.get(column))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let Some(date) = field(Some(self.date)) else {
+            return Ok(None);
+        };
+        let Some(amount) = field(Some(self.amount)) else {
+            return Ok(None);
+        };
+        let amount = parse_amount(amount)?;
+        let kind = match field(self.operation).map(str::to_lowercase) {
+            Some(operation) if operation.contains("повернення") => IncomeKind::Refund,
+            Some(operation) if operation.contains("дохід") => IncomeKind::Income,
+            Some(_) => return Ok(None),
+            None if amount < 0.0 => IncomeKind::Refund,
+            None => IncomeKind::Income,
+        };
+        let date = NaiveDate::parse_from_str(date, DATE_FORMAT)
+            .with_context(|| format!("failed to parse date {}", date))?;
+        let mut income =
+            Income::new(date.and_time(NaiveTime::MIN), Amount::new(amount.abs())?).with_kind(kind);
+        if let Some(comment) = field(self.comment) {
+            income = income.with_comment(comment.to_string());
+        }
+        if let Some(currency) = field(self.currency) {
+            income = income.with_currency(Currency::new(currency)?);
+        }
+        if let Some(document) = field(self.document) {
+            income = income.with_source_ref(document.to_string());
+        }
+        Ok(Some(income))
+    }
+}
+
+fn is_tax_number(raw: &str) -> bool {
+    let raw = raw.trim();
+    (8..=10).contains(&raw.len()) && raw.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Parses amounts like `1 234,56` or `1234.56`.
+fn parse_amount(raw: &str) -> anyhow::Result<f64> {
+    let normalized: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    normalized
+        .parse()
+        .with_context(|| format!("failed to parse amount {}", raw))
+}
+
+/// Reads rows of the file. Taxer exports use semicolons, files of monotax use commas.
+fn rows(content: &[u8]) -> anyhow::Result<Vec<Vec<String>>> {
+    let text = std::str::from_utf8(content)?;
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    let mut rows = vec![];
+    for record in reader.records() {
+        rows.push(record?.iter().map(str::to_string).collect());
+    }
+    Ok(rows)
+}
+
+/// Finds columns by the header or by the first row of headerless files.
+/// Returns the columns and the number of rows before incomes.
+fn columns(rows: &[Vec<String>]) -> Option<(Columns, usize)> {
+    let first = rows.first()?;
+    Columns::from_header(first)
+        .map(|columns| (columns, 1))
+        .or_else(|| Columns::positional(first).map(|columns| (columns, 0)))
+}
+
+impl StatementReader for TaxerStatementReader {
+    fn format(&self) -> &str {
+        "taxer"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        rows(content).is_ok_and(|rows| columns(&rows).is_some())
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        let rows = rows(content)?;
+        let (columns, skip) = columns(&rows).context("the file has no Taxer columns")?;
+        let mut incomes = vec![];
+        for (index, row) in rows.iter().enumerate().skip(skip) {
+            let income = columns
+                .income(row)
+                .with_context(|| format!("failed to read row {}", index + 1))?;
+            incomes.extend(income);
+        }
+        Ok(incomes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::model::payment::TaxPayment;
+    use crate::integration::taxer::{
+        export_csv, export_payments_csv, TaxerImportConfig, TaxerPayment, TaxerTemplate,
+    };
+
+    use super::*;
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    fn incomes() -> Vec<Income> {
+        vec![
+            Income::from_date(date("2024-02-10"), Amount::new(1000.0).unwrap())
+                .with_comment("Invoice 1".to_string())
+                .with_source_ref("REF-1".to_string()),
+            Income::from_date(date("2024-03-01"), Amount::new(250.5).unwrap())
+                .with_comment("Invoice 2".to_string())
+                .with_currency(Currency::USD)
+                .with_source_ref("REF-2".to_string()),
+        ]
+    }
+
+    fn round_trip(config: TaxerImportConfig) -> Vec<Income> {
+        let mut buffer = vec![];
+        export_csv(incomes(), &config, &mut buffer).unwrap();
+        assert!(TaxerStatementReader.detect(&buffer));
+        TaxerStatementReader.read_incomes(&buffer).unwrap()
+    }
+
+    #[test]
+    fn read_exported_templates() {
+        for header in [false, true] {
+            let config = TaxerImportConfig::default()
+                .with_template(TaxerTemplate::Full)
+                .with_header(header);
+            let read = round_trip(config);
+            assert_eq!(read.len(), 2);
+            assert_eq!(read[1].date(), date("2024-03-01"));
+            assert_eq!(read[1].amount().amount(), 250.5);
+            assert_eq!(read[1].currency(), Currency::USD);
+            assert_eq!(read[1].comment(), Some("Invoice 2"));
+            assert_eq!(read[1].source_ref(), Some("REF-2"));
+
+            let config = TaxerImportConfig::default()
+                .with_template(TaxerTemplate::Short)
+                .with_header(header);
+            let read = round_trip(config);
+            assert_eq!(read[0].amount().amount(), 1000.0);
+            assert_eq!(read[0].currency(), Currency::UAH);
+            assert_eq!(read[0].comment(), Some("Invoice 1"));
+        }
+    }
+
+    #[test]
+    fn read_taxer_export() {
+        let content = "\
+Дата;Тип операції;Сума;Валюта;Рахунок;Контрагент;Призначення платежу;Номер документа
+15.01.2024;Дохід;1 200,50;UAH;Основний;ТОВ Клієнт;Оплата за послуги;101
+20.01.2024;Витрата;300,00;UAH;Основний;ДПС;Єдиний податок;102
+25.01.2024;Повернення коштів;200,00;UAH;Основний;ТОВ Клієнт;Повернення передоплати;103
+";
+        assert!(TaxerStatementReader.detect(content.as_bytes()));
+        let incomes = TaxerStatementReader
+            .read_incomes(content.as_bytes())
+            .unwrap();
+
+        assert_eq!(incomes.len(), 2);
+        assert_eq!(incomes[0].amount().amount(), 1200.5);
+        assert_eq!(incomes[0].kind(), IncomeKind::Income);
+        assert_eq!(incomes[0].comment(), Some("Оплата за послуги"));
+        assert_eq!(incomes[0].source_ref(), Some("101"));
+        assert_eq!(incomes[1].kind(), IncomeKind::Refund);
+        assert_eq!(incomes[1].amount().amount(), 200.0);
+    }
+
+    #[test]
+    fn skip_exported_payments() {
+        let payment = TaxPayment::new(date("2024-04-19"), Amount::new(500.0).unwrap());
+        let payments = [TaxerPayment::from_payment(&payment, "Tax office")];
+        for template in [TaxerTemplate::Short, TaxerTemplate::Full] {
+            let config = TaxerImportConfig::default().with_template(template);
+            let mut buffer = vec![];
+            export_payments_csv(&payments, &config, &mut buffer).unwrap();
+
+            let incomes = TaxerStatementReader.read_incomes(&buffer).unwrap();
+
+            assert!(incomes.is_empty());
+        }
+    }
+
+    #[test]
+    fn ignore_other_csv_files() {
+        let content = "Date,Amount,Description\n2024-01-15,100.00,Payment\n";
+        assert!(!TaxerStatementReader.detect(content.as_bytes()));
+    }
+
+    #[test]
+    fn ignore_statements_with_currency() {
+        let content = "Дата;Сума;Валюта;Опис\n15.01.2024;1200,50;UAH;Оплата за послуги\n";
+        assert!(!TaxerStatementReader.detect(content.as_bytes()));
+    }
+}
@@ -1,18 +1,25 @@
-use chrono::NaiveDate;
+use std::io::Write;
 
-use crate::domain::{
-    model::{income::Amount, income_tax::IncomeTax},
-    Income,
-};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::domain::model::income::{ensure_uah, ForeignIncomesError};
+use crate::domain::{model::income_tax::IncomeTax, Income, Quarter};
+
+use super::render::{Column, ReportRenderer, RowKind, Table};
+use super::template::Templates;
 
 #[derive(Debug)]
 pub struct BalanceReport {
+    taxes: Vec<String>,
     income_obligations: Vec<IncomeRow>,
 }
 
+/// Income with obligations of every tax.
+/// Refunds have negative amounts and obligations.
 #[derive(Debug)]
 pub struct IncomeRow {
-    amount: Amount,
+    amount: f64,
     date: NaiveDate,
     obligations: Vec<IncomeTaxObligation>,
 }
@@ -20,35 +27,162 @@ pub struct IncomeRow {
 #[derive(Debug)]
 pub struct IncomeTaxObligation {
     name: String,
-    obligation: Amount,
+    obligation: f64,
+}
+
+/// Incomes and obligations summed over a period.
+#[derive(Debug)]
+pub struct BalanceTotal {
+    period: String,
+    amount: f64,
+    obligations: Vec<IncomeTaxObligation>,
+}
+
+#[derive(Debug, Serialize)]
+struct BalanceDocument {
+    taxes: Vec<String>,
+    incomes: Vec<BalanceDocumentRow>,
+    months: Vec<BalanceDocumentRow>,
+    quarters: Vec<BalanceDocumentRow>,
+    total: BalanceDocumentRow,
+}
+
+#[derive(Debug, Serialize)]
+struct BalanceDocumentRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<String>,
+    amount: f64,
+    obligations: Vec<BalanceDocumentObligation>,
+    total_obligations: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BalanceDocumentObligation {
+    name: String,
+    amount: f64,
 }
 
 impl BalanceReport {
-    pub fn new(incomes: Vec<Income>, income_taxes: Vec<IncomeTax>) -> Self {
+    /// Creates the report of the incomes.
+    /// Refunds reduce the income and obligations of the day they are made on.
+    /// Fails when some incomes are not in hryvnias.
+    pub fn new(
+        incomes: Vec<Income>,
+        income_taxes: Vec<IncomeTax>,
+    ) -> Result<Self, ForeignIncomesError> {
+        ensure_uah(&incomes)?;
+        Ok(Self::unchecked(incomes, income_taxes))
+    }
+
+    /// Creates the report without checking currencies of the incomes.
+    /// Callers refuse foreign incomes themselves.
+    pub(super) fn unchecked(mut incomes: Vec<Income>, income_taxes: Vec<IncomeTax>) -> Self {
+        incomes.sort();
         let mut income_obligations = vec![];
         for income in incomes {
-            let income_amount = income.amount();
+            let sign = if income.is_refund() { -1.0 } else { 1.0 };
             let income_date = income.date();
             let obligations = income_taxes
                 .iter()
                 .map(|tax| {
-                    let obligation = tax.calculate_obligation(income_amount, income_date);
-                    IncomeTaxObligation::new(tax.name().to_string(), obligation)
+                    let obligation = tax.calculate_obligation(income.amount(), income_date);
+                    IncomeTaxObligation::new(tax.name().to_string(), sign * obligation.amount())
                 })
                 .collect::<Vec<_>>();
 
-            income_obligations.push(IncomeRow::new(income_amount, income_date, obligations));
+            income_obligations.push(IncomeRow::new(
+                sign * income.amount().amount(),
+                income_date,
+                obligations,
+            ));
+        }
+        let taxes = income_taxes
+            .iter()
+            .map(|tax| tax.name().to_string())
+            .collect();
+        Self {
+            taxes,
+            income_obligations,
         }
-        Self { income_obligations }
+    }
+
+    /// Names of taxes in the order of income obligations.
+    pub fn taxes(&self) -> &[String] {
+        &self.taxes
     }
 
     pub fn income_obligations(&self) -> &Vec<IncomeRow> {
         &self.income_obligations
     }
+
+    /// Totals of every month with incomes.
+    pub fn monthly_totals(&self) -> Vec<BalanceTotal> {
+        self.income_obligations
+            .chunk_by(|a, b| month(a.date) == month(b.date))
+            .map(|rows| self.sum(month_label(rows[0].date), rows))
+            .collect()
+    }
+
+    /// Totals of every quarter with incomes.
+    pub fn quarterly_totals(&self) -> Vec<BalanceTotal> {
+        self.income_obligations
+            .chunk_by(|a, b| quarter(a.date) == quarter(b.date))
+            .map(|rows| self.sum(quarter_label(rows[0].date), rows))
+            .collect()
+    }
+
+    /// Total of incomes dated from the start to the end, both inclusive.
+    pub fn period_total(&self, start: NaiveDate, end: NaiveDate) -> BalanceTotal {
+        // rows are sorted by date
+        let rows = &self.income_obligations;
+        let first = rows.partition_point(|row| row.date < start);
+        let last = rows.partition_point(|row| row.date <= end).max(first);
+        self.sum(format!("{} - {}", start, end), &rows[first..last])
+    }
+
+    /// Grand total of the report.
+    pub fn total(&self) -> BalanceTotal {
+        self.sum("Total".to_string(), &self.income_obligations)
+    }
+
+    fn sum(&self, period: String, rows: &[IncomeRow]) -> BalanceTotal {
+        let obligations = self
+            .taxes
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let obligation = rows.iter().map(|row| row.obligations[index].obligation);
+                IncomeTaxObligation::new(name.clone(), obligation.sum())
+            })
+            .collect();
+        BalanceTotal {
+            period,
+            amount: rows.iter().map(|row| row.amount).sum(),
+            obligations,
+        }
+    }
+}
+
+fn month(date: NaiveDate) -> (i32, u32) {
+    (date.year(), date.month())
+}
+
+fn quarter(date: NaiveDate) -> (i32, Quarter) {
+    (date.year(), Quarter::from(&date))
+}
+
+fn month_label(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+fn quarter_label(date: NaiveDate) -> String {
+    format!("{} {}", Quarter::from(&date), date.year())
 }
 
 impl IncomeRow {
-    pub fn new(amount: Amount, date: NaiveDate, obligations: Vec<IncomeTaxObligation>) -> Self {
+    pub fn new(amount: f64, date: NaiveDate, obligations: Vec<IncomeTaxObligation>) -> Self {
         Self {
             amount,
             date,
@@ -56,7 +190,7 @@ impl IncomeRow {
         }
     }
 
-    pub fn amount(&self) -> Amount {
+    pub fn amount(&self) -> f64 {
         self.amount
     }
 
@@ -68,13 +202,13 @@ impl IncomeRow {
         &self.obligations
     }
 
-    pub fn total_obligations(&self) -> Amount {
+    pub fn total_obligations(&self) -> f64 {
         self.obligations.iter().map(|t| t.obligation).sum()
     }
 }
 
 impl IncomeTaxObligation {
-    pub fn new(name: String, obligation: Amount) -> Self {
+    pub fn new(name: String, obligation: f64) -> Self {
         Self { name, obligation }
     }
 
@@ -82,7 +216,296 @@ impl IncomeTaxObligation {
         &self.name
     }
 
-    pub fn obligation(&self) -> Amount {
+    pub fn obligation(&self) -> f64 {
         self.obligation
     }
 }
+
+impl BalanceTotal {
+    pub fn period(&self) -> &str {
+        &self.period
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn obligations(&self) -> &[IncomeTaxObligation] {
+        &self.obligations
+    }
+
+    pub fn total_obligations(&self) -> f64 {
+        self.obligations.iter().map(|t| t.obligation).sum()
+    }
+}
+
+/// The table has a column per tax. Incomes of each month are followed by the month subtotal,
+/// the last month of a quarter is also followed by the quarter subtotal.
+impl ReportRenderer for BalanceReport {
+    fn table(&self) -> Table {
+        let mut columns = vec![Column::left("Date"), Column::right("Amount")];
+        columns.extend(self.taxes.iter().map(Column::right));
+        columns.push(Column::right("Taxes"));
+        let mut table = Table::new(columns);
+        let quarters = self
+            .income_obligations
+            .chunk_by(|a, b| quarter(a.date) == quarter(b.date));
+        for quarter_rows in quarters {
+            for month_rows in quarter_rows.chunk_by(|a, b| month(a.date) == month(b.date)) {
+                for row in month_rows {
+                    table.push(
+                        RowKind::Item,
+                        amount_cells(
+                            row.date.to_string(),
+                            row.amount,
+                            &row.obligations,
+                            row.total_obligations(),
+                        ),
+                    );
+                }
+                let month_total = self.sum(month_label(month_rows[0].date), month_rows);
+                table.push(RowKind::Subtotal, total_cells(&month_total));
+            }
+            let quarter_total = self.sum(quarter_label(quarter_rows[0].date), quarter_rows);
+            table.push(RowKind::Subtotal, total_cells(&quarter_total));
+        }
+        table.push(RowKind::Total, total_cells(&self.total()));
+        table
+    }
+
+    fn write_json(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(&mut *writer, &self.document())?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn title(&self) -> String {
+        "Balance".to_string()
+    }
+
+    fn render_html(&self, templates: &Templates, writer: &mut dyn Write) -> anyhow::Result<()> {
+        templates.render("balance.html", &self.title(), &self.document(), writer)
+    }
+}
+
+impl BalanceReport {
+    fn document(&self) -> BalanceDocument {
+        BalanceDocument {
+            taxes: self.taxes.clone(),
+            incomes: self
+                .income_obligations
+                .iter()
+                .map(|row| BalanceDocumentRow {
+                    date: Some(row.date.to_string()),
+                    period: None,
+                    amount: row.amount,
+                    obligations: document_obligations(&row.obligations),
+                    total_obligations: row.total_obligations(),
+                })
+                .collect(),
+            months: self.monthly_totals().iter().map(Into::into).collect(),
+            quarters: self.quarterly_totals().iter().map(Into::into).collect(),
+            total: (&self.total()).into(),
+        }
+    }
+}
+
+fn amount_cells(
+    label: String,
+    amount: f64,
+    obligations: &[IncomeTaxObligation],
+    total_obligations: f64,
+) -> Vec<String> {
+    let mut cells = vec![label, format_amount(amount)];
+    cells.extend(obligations.iter().map(|o| format_amount(o.obligation)));
+    cells.push(format_amount(total_obligations));
+    cells
+}
+
+fn total_cells(total: &BalanceTotal) -> Vec<String> {
+    amount_cells(
+        total.period.clone(),
+        total.amount,
+        &total.obligations,
+        total.total_obligations(),
+    )
+}
+
+fn format_amount(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
+
+fn document_obligations(obligations: &[IncomeTaxObligation]) -> Vec<BalanceDocumentObligation> {
+    obligations
+        .iter()
+        .map(|o| BalanceDocumentObligation {
+            name: o.name.clone(),
+            amount: o.obligation,
+        })
+        .collect()
+}
+
+impl From<&BalanceTotal> for BalanceDocumentRow {
+    fn from(total: &BalanceTotal) -> Self {
+        Self {
+            date: None,
+            period: Some(total.period.clone()),
+            amount: total.amount,
+            obligations: document_obligations(&total.obligations),
+            total_obligations: total.total_obligations(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::model::income::{Amount, IncomeKind};
+    use crate::domain::model::income_tax::IncomeTaxRate;
+    use crate::domain::model::income_tax::TaxRate;
+    use crate::domain::Currency;
+    use crate::report::render::ReportFormat;
+
+    use super::*;
+
+    fn income(date: &str, amount: f64) -> Income {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Income::from_date(date, Amount::new(amount).unwrap())
+    }
+
+    fn tax(name: &str, rate: f64) -> IncomeTax {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        IncomeTax::new(
+            uuid::Uuid::new_v4(),
+            name.to_string(),
+            vec![IncomeTaxRate::open(start, TaxRate::new(rate).unwrap())],
+        )
+    }
+
+    fn report() -> BalanceReport {
+        BalanceReport::new(
+            vec![
+                income("2024-04-02", 300.0),
+                income("2024-01-10", 1000.0),
+                income("2024-01-25", 500.0),
+                income("2024-02-03", 200.0),
+            ],
+            vec![tax("Single tax", 0.05), tax("Military", 0.01)],
+        )
+        .unwrap()
+    }
+
+    fn render(format: ReportFormat) -> String {
+        let mut buffer = vec![];
+        report().render(format, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn sum_by_months_and_quarters() {
+        let report = report();
+
+        let months = report.monthly_totals();
+        let periods = months.iter().map(BalanceTotal::period).collect::<Vec<_>>();
+        assert_eq!(periods, ["2024-01", "2024-02", "2024-04"]);
+        assert_eq!(months[0].amount(), 1500.0);
+        assert_eq!(months[0].obligations()[0].obligation(), 75.0);
+
+        let quarters = report.quarterly_totals();
+        let periods = quarters
+            .iter()
+            .map(BalanceTotal::period)
+            .collect::<Vec<_>>();
+        assert_eq!(periods, ["Q1 2024", "Q2 2024"]);
+        assert_eq!(quarters[0].amount(), 1700.0);
+
+        let total = report.total();
+        assert_eq!(total.amount(), 2000.0);
+        assert_eq!(total.total_obligations(), 120.0);
+    }
+
+    #[test]
+    fn subtract_refunds() {
+        let report = BalanceReport::new(
+            vec![
+                income("2024-01-10", 1000.0),
+                income("2024-02-05", 200.0).with_kind(IncomeKind::Refund),
+            ],
+            vec![tax("Single tax", 0.05)],
+        )
+        .unwrap();
+
+        let rows = report.income_obligations();
+        assert_eq!(rows[1].amount(), -200.0);
+        assert_eq!(rows[1].obligations()[0].obligation(), -10.0);
+        let total = report.total();
+        assert_eq!(total.amount(), 800.0);
+        assert_eq!(total.total_obligations(), 40.0);
+    }
+
+    #[test]
+    fn refuse_foreign_incomes() {
+        let usd = income("2024-02-05", 50.0).with_currency(Currency::USD);
+
+        let error = BalanceReport::new(
+            vec![income("2024-01-10", 1000.0), usd.clone()],
+            vec![tax("Single tax", 0.05)],
+        )
+        .unwrap_err();
+
+        assert_eq!(error.incomes, vec![usd]);
+    }
+
+    #[test]
+    fn render_table_with_subtotals() {
+        let expected = "\
+Date       |  Amount | Single tax | Military |  Taxes
+-----------+---------+------------+----------+-------
+2024-01-10 | 1000.00 |      50.00 |    10.00 |  60.00
+2024-01-25 |  500.00 |      25.00 |     5.00 |  30.00
+-----------+---------+------------+----------+-------
+2024-01    | 1500.00 |      75.00 |    15.00 |  90.00
+-----------+---------+------------+----------+-------
+2024-02-03 |  200.00 |      10.00 |     2.00 |  12.00
+-----------+---------+------------+----------+-------
+2024-02    |  200.00 |      10.00 |     2.00 |  12.00
+Q1 2024    | 1700.00 |      85.00 |    17.00 | 102.00
+-----------+---------+------------+----------+-------
+2024-04-02 |  300.00 |      15.00 |     3.00 |  18.00
+-----------+---------+------------+----------+-------
+2024-04    |  300.00 |      15.00 |     3.00 |  18.00
+Q2 2024    |  300.00 |      15.00 |     3.00 |  18.00
+-----------+---------+------------+----------+-------
+Total      | 2000.00 |     100.00 |    20.00 | 120.00
+";
+        assert_eq!(render(ReportFormat::Table), expected);
+    }
+
+    #[test]
+    fn render_json_document() {
+        let json: serde_json::Value = serde_json::from_str(&render(ReportFormat::Json)).unwrap();
+
+        assert_eq!(json["taxes"][1], "Military");
+        assert_eq!(json["incomes"][0]["date"], "2024-01-10");
+        assert_eq!(json["incomes"][0]["obligations"][0]["amount"], 50.0);
+        assert_eq!(json["months"][1]["period"], "2024-02");
+        assert_eq!(json["quarters"][0]["total_obligations"], 102.0);
+        assert_eq!(json["total"]["amount"], 2000.0);
+    }
+
+    #[test]
+    fn render_csv_and_markdown() {
+        let csv = render(ReportFormat::Csv);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Date,Amount,Single tax,Military,Taxes");
+        assert_eq!(lines[3], "2024-01,1500.00,75.00,15.00,90.00");
+        assert_eq!(lines.last(), Some(&"Total,2000.00,100.00,20.00,120.00"));
+
+        let markdown = render(ReportFormat::Markdown);
+        let lines = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "| --- | ---: | ---: | ---: | ---: |");
+        assert_eq!(
+            lines.last(),
+            Some(&"| **Total** | **2000.00** | **100.00** | **20.00** | **120.00** |")
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! Quarterly declaration summary.
+//!
+//! Single-tax declarations are cumulative: each quarter declares the income from the start
+//! of the year and the tax due on it. The tax declared for previous quarters is subtracted,
+//! so only the increase has to be paid for the quarter.
+
+use std::io::Write;
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::domain::model::income::{ensure_uah, Amount, ForeignIncomesError};
+use crate::domain::model::income_tax::IncomeTax;
+use crate::domain::{Income, Quarter};
+
+use super::balance::BalanceReport;
+use super::render::{Column, ReportRenderer, RowKind, Table};
+use super::template::Templates;
+
+/// Year-to-date figures of the quarterly declaration.
+#[derive(Debug)]
+pub struct DeclarationReport {
+    year: i32,
+    quarter: Quarter,
+    quarter_income: Amount,
+    ytd_income: Amount,
+    taxes: Vec<DeclaredTax>,
+    foreign_incomes: Vec<Income>,
+}
+
+/// Tax figures of the declaration.
+#[derive(Debug)]
+pub struct DeclaredTax {
+    name: String,
+    due: Amount,
+    declared: Amount,
+}
+
+#[derive(Debug, Serialize)]
+struct DeclarationDocument<'a> {
+    year: i32,
+    quarter: String,
+    start: String,
+    end: String,
+    quarter_income: f64,
+    ytd_income: f64,
+    taxes: Vec<DeclarationDocumentTax<'a>>,
+    total: DeclarationDocumentTax<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct DeclarationDocumentTax<'a> {
+    name: &'a str,
+    due: f64,
+    declared: f64,
+    to_pay: f64,
+}
+
+impl DeclarationReport {
+    /// Creates the report of the quarter.
+    /// Incomes of other years and of quarters after the declared one are ignored.
+    /// Refunds reduce the income and the tax due of the period they are made in.
+    pub fn new(
+        year: i32,
+        quarter: Quarter,
+        incomes: Vec<Income>,
+        income_taxes: Vec<IncomeTax>,
+    ) -> Self {
+        let incomes = incomes
+            .into_iter()
+            .filter(|income| income.date().year() == year && Quarter::of(income) <= quarter)
+            .collect::<Vec<_>>();
+        let foreign_incomes = ensure_uah(&incomes)
+            .err()
+            .map(|error| error.incomes)
+            .unwrap_or_default();
+        let year_start = Quarter::Q1.first_day(year);
+        let quarter_start = quarter.first_day(year);
+        let earlier_end = quarter_start.pred_opt().unwrap();
+        let balance = BalanceReport::unchecked(incomes, income_taxes);
+        let ytd = balance.total();
+        let earlier = balance.period_total(year_start, earlier_end);
+        let taxes = ytd
+            .obligations()
+            .iter()
+            .zip(earlier.obligations())
+            .map(|(due, declared)| DeclaredTax {
+                name: due.name().to_string(),
+                due: net(due.obligation()),
+                declared: net(declared.obligation()),
+            })
+            .collect();
+        let quarter_income = balance
+            .period_total(quarter_start, quarter.last_day(year))
+            .amount();
+        Self {
+            year,
+            quarter,
+            quarter_income: net(quarter_income),
+            ytd_income: net(ytd.amount()),
+            taxes,
+            foreign_incomes,
+        }
+    }
+
+    /// Checks that all declared incomes are in hryvnias.
+    pub fn ensure_uah(&self) -> Result<(), ForeignIncomesError> {
+        ensure_uah(&self.foreign_incomes)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn quarter(&self) -> Quarter {
+        self.quarter
+    }
+
+    /// First day of the declared period. Declarations always start with the year.
+    pub fn start(&self) -> NaiveDate {
+        Quarter::Q1.first_day(self.year)
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.quarter.last_day(self.year)
+    }
+
+    /// Income of the declared quarter alone.
+    pub fn quarter_income(&self) -> Amount {
+        self.quarter_income
+    }
+
+    /// Income from the start of the year to the end of the quarter.
+    pub fn ytd_income(&self) -> Amount {
+        self.ytd_income
+    }
+
+    pub fn taxes(&self) -> &[DeclaredTax] {
+        &self.taxes
+    }
+
+    /// Sum of all taxes.
+    pub fn total(&self) -> DeclaredTax {
+        DeclaredTax {
+            name: "Total".to_string(),
+            due: self.taxes.iter().map(DeclaredTax::due).sum(),
+            declared: self.taxes.iter().map(DeclaredTax::declared).sum(),
+        }
+    }
+}
+
+impl DeclaredTax {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Tax on the year-to-date income.
+    pub fn due(&self) -> Amount {
+        self.due
+    }
+
+    /// Tax declared for previous quarters of the year.
+    pub fn declared(&self) -> Amount {
+        self.declared
+    }
+
+    /// Tax to pay for the declared quarter.
+    pub fn to_pay(&self) -> Amount {
+        // rounding errors must not make the difference negative
+        Amount::new((self.due.amount() - self.declared.amount()).max(0.0)).unwrap()
+    }
+}
+
+impl ReportRenderer for DeclarationReport {
+    fn table(&self) -> Table {
+        let mut table = Table::new(vec![
+            Column::left("Tax"),
+            Column::right("Quarter income"),
+            Column::right("YTD income"),
+            Column::right("Tax due"),
+            Column::right("Declared"),
+            Column::right("To pay"),
+        ]);
+        let cells = |tax: &DeclaredTax| {
+            vec![
+                tax.name.clone(),
+                format_amount(self.quarter_income),
+                format_amount(self.ytd_income),
+                format_amount(tax.due),
+                format_amount(tax.declared),
+                format_amount(tax.to_pay()),
+            ]
+        };
+        for tax in &self.taxes {
+            table.push(RowKind::Item, cells(tax));
+        }
+        table.push(RowKind::Total, cells(&self.total()));
+        table
+    }
+
+    fn write_json(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let total = self.total();
+        serde_json::to_writer_pretty(&mut *writer, &self.document(&total))?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn title(&self) -> String {
+        format!("Declaration {} {}", self.quarter, self.year)
+    }
+
+    fn render_html(&self, templates: &Templates, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let total = self.total();
+        let document = self.document(&total);
+        templates.render("declaration.html", &self.title(), &document, writer)
+    }
+}
+
+impl DeclarationReport {
+    fn document<'a>(&'a self, total: &'a DeclaredTax) -> DeclarationDocument<'a> {
+        DeclarationDocument {
+            year: self.year,
+            quarter: self.quarter.to_string(),
+            start: self.start().to_string(),
+            end: self.end().to_string(),
+            quarter_income: self.quarter_income.amount(),
+            ytd_income: self.ytd_income.amount(),
+            taxes: self.taxes.iter().map(Into::into).collect(),
+            total: total.into(),
+        }
+    }
+}
+
+/// Income reduced by refunds. Refunds exceeding the income leave nothing to declare.
+fn net(amount: f64) -> Amount {
+    Amount::new(amount.max(0.0)).unwrap()
+}
+
+fn format_amount(amount: Amount) -> String {
+    format!("{:.2}", amount.amount())
+}
+
+impl<'a> From<&'a DeclaredTax> for DeclarationDocumentTax<'a> {
+    fn from(tax: &'a DeclaredTax) -> Self {
+        Self {
+            name: &tax.name,
+            due: tax.due.amount(),
+            declared: tax.declared.amount(),
+            to_pay: tax.to_pay().amount(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::model::income::IncomeKind;
+    use crate::domain::model::income_tax::{IncomeTaxRate, TaxRate};
+    use crate::report::render::ReportFormat;
+
+    use super::*;
+
+    fn income(date: &str, amount: f64) -> Income {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Income::from_date(date, Amount::new(amount).unwrap())
+    }
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    fn taxes() -> Vec<IncomeTax> {
+        let rate = |raw| TaxRate::new(raw).unwrap();
+        let mut military = IncomeTax::new(Uuid::new_v4(), "Military".to_string(), vec![]);
+        military.add_rate(
+            IncomeTaxRate::closed(date("2024-01-01"), date("2024-07-01"), rate(0.01)).unwrap(),
+        );
+        military.add_rate(IncomeTaxRate::open(date("2024-07-01"), rate(0.015)));
+        vec![
+            IncomeTax::new(
+                Uuid::new_v4(),
+                "Single tax".to_string(),
+                vec![IncomeTaxRate::open(date("2020-01-01"), rate(0.05))],
+            ),
+            military,
+        ]
+    }
+
+    fn incomes() -> Vec<Income> {
+        vec![
+            income("2023-12-20", 5000.0),
+            income("2024-02-10", 1000.0),
+            income("2024-05-15", 2000.0),
+            income("2024-08-01", 4000.0),
+            income("2024-11-03", 8000.0),
+        ]
+    }
+
+    #[test]
+    fn declare_year_to_date() {
+        let report = DeclarationReport::new(2024, Quarter::Q3, incomes(), taxes());
+
+        assert_eq!(report.start(), date("2024-01-01"));
+        assert_eq!(report.end(), date("2024-09-30"));
+        assert_eq!(report.quarter_income().amount(), 4000.0);
+        assert_eq!(report.ytd_income().amount(), 7000.0);
+
+        let single_tax = &report.taxes()[0];
+        assert_eq!(single_tax.due().amount(), 350.0);
+        assert_eq!(single_tax.declared().amount(), 150.0);
+        assert_eq!(single_tax.to_pay().amount(), 200.0);
+
+        // the rate changed in the third quarter
+        let military = &report.taxes()[1];
+        assert_eq!(military.due().amount(), 90.0);
+        assert_eq!(military.declared().amount(), 30.0);
+        assert_eq!(military.to_pay().amount(), 60.0);
+
+        assert_eq!(report.total().to_pay().amount(), 260.0);
+    }
+
+    #[test]
+    fn nothing_declared_before_first_quarter() {
+        let report = DeclarationReport::new(2024, Quarter::Q1, incomes(), taxes());
+
+        assert_eq!(report.ytd_income().amount(), 1000.0);
+        assert_eq!(report.quarter_income().amount(), 1000.0);
+        assert_eq!(report.total().declared().amount(), 0.0);
+        assert_eq!(report.total().to_pay().amount(), 60.0);
+    }
+
+    #[test]
+    fn subtract_refunds() {
+        let mut incomes = incomes();
+        incomes.push(income("2024-03-01", 400.0).with_kind(IncomeKind::Refund));
+        incomes.push(income("2024-08-10", 1000.0).with_kind(IncomeKind::Refund));
+        let report = DeclarationReport::new(2024, Quarter::Q3, incomes, taxes());
+
+        assert_eq!(report.quarter_income().amount(), 3000.0);
+        assert_eq!(report.ytd_income().amount(), 5600.0);
+        let single_tax = &report.taxes()[0];
+        assert_eq!(single_tax.due().amount(), 280.0);
+        assert_eq!(single_tax.declared().amount(), 130.0);
+        assert_eq!(single_tax.to_pay().amount(), 150.0);
+    }
+
+    #[test]
+    fn render_table() {
+        let report = DeclarationReport::new(2024, Quarter::Q2, incomes(), taxes());
+        let mut buffer = vec![];
+        report.render(ReportFormat::Table, &mut buffer).unwrap();
+
+        let expected = "\
+Tax        | Quarter income | YTD income | Tax due | Declared | To pay
+-----------+----------------+------------+---------+----------+-------
+Single tax |        2000.00 |    3000.00 |  150.00 |    50.00 | 100.00
+Military   |        2000.00 |    3000.00 |   30.00 |    10.00 |  20.00
+-----------+----------------+------------+---------+----------+-------
+Total      |        2000.00 |    3000.00 |  180.00 |    60.00 | 120.00
+";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+}
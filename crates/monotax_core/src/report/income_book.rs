@@ -0,0 +1,354 @@
+//! Book of income records (книга обліку доходів).
+//!
+//! Single tax payers keep a book of incomes received during the year. Entries are numbered
+//! from one every year and the book has the total of every day and the total from the start
+//! of the year. Refunds are recorded apart from incomes and reduce the totals. Refunds and
+//! corrections are marked, so they can be told apart from incomes of bank statements.
+//!
+//! Printed books are HTML pages rendered from the `income_book.html` template or PDF documents.
+
+use std::io::Write;
+
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::domain::model::income::{Amount, IncomeKind};
+use crate::domain::Income;
+
+use super::render::{Column, RowKind, Table};
+use super::template::Templates;
+
+/// Output format of the income book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum BookFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Html,
+    Pdf,
+}
+
+/// Income book of a year.
+#[derive(Debug)]
+pub struct IncomeBook {
+    year: i32,
+    entries: Vec<IncomeBookEntry>,
+}
+
+/// Record of the income book.
+#[derive(Debug, Serialize)]
+pub struct IncomeBookEntry {
+    number: usize,
+    date: NaiveDate,
+    payment_no: i64,
+    kind: IncomeKind,
+    amount: Amount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    daily_total: Option<f64>,
+    running_total: f64,
+}
+
+/// Context of the income book template.
+#[derive(Debug, Serialize)]
+struct IncomeBookDocument<'a> {
+    year: i32,
+    entries: &'a [IncomeBookEntry],
+    income: f64,
+    refunds: f64,
+    total: f64,
+}
+
+impl IncomeBook {
+    /// Creates the book of the year. Incomes of other years are ignored.
+    pub fn new(year: i32, mut incomes: Vec<Income>) -> Self {
+        incomes.retain(|income| income.date().year() == year);
+        incomes.sort();
+        let mut entries: Vec<IncomeBookEntry> = vec![];
+        let mut running_total = 0.0;
+        let mut daily_total = 0.0;
+        for (index, income) in incomes.iter().enumerate() {
+            let signed = signed_amount(income.kind(), income.amount());
+            running_total += signed;
+            daily_total += signed;
+            let last_of_day = incomes
+                .get(index + 1)
+                .is_none_or(|next| next.date() != income.date());
+            entries.push(IncomeBookEntry {
+                number: index + 1,
+                date: income.date(),
+                payment_no: income.income_no(),
+                kind: income.kind(),
+                amount: income.amount(),
+                description: income.comment().map(str::to_string),
+                daily_total: last_of_day.then_some(daily_total),
+                running_total,
+            });
+            if last_of_day {
+                daily_total = 0.0;
+            }
+        }
+        Self { year, entries }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn entries(&self) -> &[IncomeBookEntry] {
+        &self.entries
+    }
+
+    /// Incomes and corrections of the year without refunds.
+    pub fn income(&self) -> Amount {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind != IncomeKind::Refund)
+            .map(IncomeBookEntry::amount)
+            .sum()
+    }
+
+    /// Refunds of the year.
+    pub fn refunds(&self) -> Amount {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == IncomeKind::Refund)
+            .map(IncomeBookEntry::amount)
+            .sum()
+    }
+
+    /// Income of the year reduced by refunds.
+    pub fn total(&self) -> f64 {
+        self.entries
+            .last()
+            .map(|entry| entry.running_total)
+            .unwrap_or_default()
+    }
+
+    pub fn title(&self) -> String {
+        format!("Income book {}", self.year)
+    }
+
+    /// Entries of the book with the total of the year.
+    pub fn table(&self) -> Table {
+        let mut table = Table::new(vec![
+            Column::right("No"),
+            Column::left("Date"),
+            Column::right("Payment no"),
+            Column::right("Income"),
+            Column::right("Refund"),
+            Column::right("Daily total"),
+            Column::right("Running total"),
+            Column::left("Mark"),
+            Column::left("Description"),
+        ]);
+        for entry in &self.entries {
+            let (income, refund) = match entry.kind {
+                IncomeKind::Refund => (String::new(), format_amount(entry.amount.amount())),
+                _ => (format_amount(entry.amount.amount()), String::new()),
+            };
+            let mark = match entry.kind {
+                IncomeKind::Income => String::new(),
+                kind => kind.to_string(),
+            };
+            table.push(
+                RowKind::Item,
+                vec![
+                    entry.number.to_string(),
+                    entry.date.to_string(),
+                    entry.payment_no.to_string(),
+                    income,
+                    refund,
+                    entry.daily_total.map(format_amount).unwrap_or_default(),
+                    format_amount(entry.running_total),
+                    mark,
+                    entry.description.clone().unwrap_or_default(),
+                ],
+            );
+        }
+        table.push(
+            RowKind::Total,
+            vec![
+                String::new(),
+                "Total".to_string(),
+                String::new(),
+                format_amount(self.income().amount()),
+                format_amount(self.refunds().amount()),
+                String::new(),
+                format_amount(self.total()),
+            ],
+        );
+        table
+    }
+
+    /// Writes the book in the format. HTML pages are rendered from the built-in template.
+    pub fn write(&self, format: BookFormat, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match format {
+            BookFormat::Csv => self.table().write_csv(writer),
+            BookFormat::Xlsx => self.table().write_xlsx(&self.title(), writer),
+            BookFormat::Html => self.write_html(&Templates::builtin(), writer),
+            BookFormat::Pdf => self.table().write_pdf(&self.title(), writer),
+        }
+    }
+
+    /// Writes the book as an HTML page rendered from the `income_book.html` template.
+    pub fn write_html(&self, templates: &Templates, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let document = IncomeBookDocument {
+            year: self.year,
+            entries: &self.entries,
+            income: self.income().amount(),
+            refunds: self.refunds().amount(),
+            total: self.total(),
+        };
+        templates.render("income_book.html", &self.title(), &document, writer)
+    }
+}
+
+impl IncomeBookEntry {
+    /// Number of the entry in the year, starting from one.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn payment_no(&self) -> i64 {
+        self.payment_no
+    }
+
+    pub fn kind(&self) -> IncomeKind {
+        self.kind
+    }
+
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Total of the day. Only the last entry of the day has it.
+    pub fn daily_total(&self) -> Option<f64> {
+        self.daily_total
+    }
+
+    /// Total from the start of the year including the entry.
+    pub fn running_total(&self) -> f64 {
+        self.running_total
+    }
+}
+
+fn signed_amount(kind: IncomeKind, amount: Amount) -> f64 {
+    match kind {
+        IncomeKind::Refund => -amount.amount(),
+        _ => amount.amount(),
+    }
+}
+
+fn format_amount(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn income(date: &str, amount: f64) -> Income {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Income::from_date(date, Amount::new(amount).unwrap())
+    }
+
+    fn book() -> IncomeBook {
+        IncomeBook::new(
+            2024,
+            vec![
+                income("2024-03-05", 300.0),
+                income("2023-12-31", 5000.0),
+                income("2024-01-10", 1000.0),
+                income("2024-03-05", 200.0).with_kind(IncomeKind::Refund),
+                income("2024-01-10", 500.0),
+                income("2024-04-01", 50.0)
+                    .with_kind(IncomeKind::Correction)
+                    .with_comment("missing from the statement".to_string()),
+                income("2025-01-02", 700.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn number_entries_of_year() {
+        let book = book();
+
+        let numbers = book
+            .entries()
+            .iter()
+            .map(IncomeBookEntry::number)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+        assert_eq!(book.entries()[0].date().to_string(), "2024-01-10");
+        assert_eq!(book.entries()[4].date().to_string(), "2024-04-01");
+    }
+
+    #[test]
+    fn sum_days_and_year() {
+        let book = book();
+
+        let daily = book
+            .entries()
+            .iter()
+            .map(IncomeBookEntry::daily_total)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            daily,
+            vec![None, Some(1500.0), None, Some(100.0), Some(50.0)]
+        );
+        let running = book
+            .entries()
+            .iter()
+            .map(IncomeBookEntry::running_total)
+            .collect::<Vec<_>>();
+        assert_eq!(running, vec![1000.0, 1500.0, 1800.0, 1600.0, 1650.0]);
+        assert_eq!(book.income().amount(), 1850.0);
+        assert_eq!(book.refunds().amount(), 200.0);
+        assert_eq!(book.total(), 1650.0);
+    }
+
+    #[test]
+    fn write_csv() {
+        let mut buffer = vec![];
+        book().write(BookFormat::Csv, &mut buffer).unwrap();
+
+        let expected = "\
+No,Date,Payment no,Income,Refund,Daily total,Running total,Mark,Description
+1,2024-01-10,0,1000.00,,,1000.00,,
+2,2024-01-10,0,500.00,,1500.00,1500.00,,
+3,2024-03-05,0,300.00,,,1800.00,,
+4,2024-03-05,0,,200.00,100.00,1600.00,refund,
+5,2024-04-01,0,50.00,,50.00,1650.00,correction,missing from the statement
+,Total,,1850.00,200.00,,1650.00,,
+";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+
+    #[test]
+    fn write_html_from_template() {
+        let mut buffer = vec![];
+        book().write(BookFormat::Html, &mut buffer).unwrap();
+
+        let html = String::from_utf8(buffer).unwrap();
+        assert!(html.contains("<title>Income book 2024</title>"));
+        assert!(html.contains(
+            "<td class=\"right\">4</td><td>2024-03-05</td><td class=\"right\">0</td>\
+             <td class=\"right\"></td><td class=\"right\">200.00</td>\
+             <td class=\"right\">100.00</td><td class=\"right\">1600.00</td>\
+             <td>refund</td><td></td>"
+        ));
+        assert!(html.contains("<td>correction</td><td>missing from the statement</td>"));
+        assert!(html.contains("<td class=\"right\">1850.00</td><td class=\"right\">200.00</td>"));
+    }
+}
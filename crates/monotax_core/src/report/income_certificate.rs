@@ -0,0 +1,532 @@
+//! Certificate of income of the entrepreneur.
+//!
+//! Banks and visa centers ask for the income received over the last months. The certificate
+//! has the details of the entrepreneur, the income of every month of the period including
+//! months without income, its total and taxes on it. Refunds reduce the income of the month
+//! they are made in. Accrued taxes are calculated from the income, paid taxes are recorded
+//! payments of the tax made in the period. Amounts are certified in hryvnias, so incomes must
+//! be in UAH.
+
+use std::io::Write;
+
+use chrono::{Datelike, Months, NaiveDate};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::income::{ensure_uah, ForeignIncomesError};
+use crate::domain::model::income_tax::IncomeTax;
+use crate::domain::model::payment::TaxPayment;
+use crate::domain::Income;
+
+use super::pdf::Document;
+use super::render::{Column, RowKind, Table};
+use super::template::Templates;
+
+/// Output format of the income certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CertificateFormat {
+    #[default]
+    Html,
+    Pdf,
+    Markdown,
+}
+
+/// Entrepreneur the certificate is issued to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Taxpayer {
+    pub name: String,
+    /// Taxpayer identification number (РНОКПП).
+    pub tin: String,
+    pub address: String,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Income certificate for the period.
+#[derive(Debug)]
+pub struct IncomeCertificate {
+    taxpayer: Taxpayer,
+    from: NaiveDate,
+    to: NaiveDate,
+    issued: NaiveDate,
+    months: Vec<CertificateMonth>,
+    taxes: Vec<CertificateTax>,
+}
+
+/// Income of a month of the period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateMonth {
+    month: String,
+    income: f64,
+    refunds: f64,
+    net: f64,
+}
+
+/// Tax on the income of the period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateTax {
+    name: String,
+    accrued: f64,
+    paid: f64,
+}
+
+/// Context of the income certificate template.
+#[derive(Debug, Serialize)]
+struct CertificateDocument<'a> {
+    taxpayer: &'a Taxpayer,
+    from: String,
+    to: String,
+    issued: String,
+    months: &'a [CertificateMonth],
+    total: CertificateMonth,
+    taxes: &'a [CertificateTax],
+    total_taxes: CertificateTax,
+}
+
+impl IncomeCertificate {
+    /// Creates the certificate of incomes from `from` to `to` inclusive.
+    /// Incomes and payments out of the period are ignored, as well as payments without a tax.
+    /// Fails if incomes of the period are not in UAH.
+    pub fn new(
+        taxpayer: Taxpayer,
+        from: NaiveDate,
+        to: NaiveDate,
+        issued: NaiveDate,
+        mut incomes: Vec<Income>,
+        payments: &[TaxPayment],
+        taxes: &[IncomeTax],
+    ) -> Result<Self, ForeignIncomesError> {
+        incomes.retain(|income| (from..=to).contains(&income.date()));
+        ensure_uah(&incomes)?;
+        let months = months(from, to)
+            .into_iter()
+            .map(|start| {
+                let of_month = incomes
+                    .iter()
+                    .filter(|income| month_start(income.date()) == start);
+                let (refunds, income): (Vec<&Income>, Vec<&Income>) =
+                    of_month.partition(|income| income.is_refund());
+                let income = sum(income.iter().map(|income| income.amount().amount()));
+                let refunds = sum(refunds.iter().map(|income| income.amount().amount()));
+                CertificateMonth {
+                    month: start.format("%Y-%m").to_string(),
+                    income,
+                    refunds,
+                    net: round(income - refunds),
+                }
+            })
+            .collect();
+        let taxes = taxes
+            .iter()
+            .map(|tax| {
+                let obligation = |income: &Income| {
+                    let obligation = tax
+                        .calculate_obligation(income.amount(), income.date())
+                        .amount();
+                    match income.is_refund() {
+                        true => -obligation,
+                        false => obligation,
+                    }
+                };
+                CertificateTax {
+                    name: tax.name().to_string(),
+                    accrued: sum(incomes.iter().map(obligation)),
+                    paid: sum(payments
+                        .iter()
+                        .filter(|payment| payment.tax() == Some(tax.name()))
+                        .filter(|payment| (from..=to).contains(&payment.date()))
+                        .map(|payment| payment.amount().amount())),
+                }
+            })
+            .collect();
+        Ok(Self {
+            taxpayer,
+            from,
+            to,
+            issued,
+            months,
+            taxes,
+        })
+    }
+
+    pub fn months(&self) -> &[CertificateMonth] {
+        &self.months
+    }
+
+    pub fn taxes(&self) -> &[CertificateTax] {
+        &self.taxes
+    }
+
+    /// Income of the period reduced by refunds.
+    pub fn total(&self) -> CertificateMonth {
+        let income = sum(self.months.iter().map(|month| month.income));
+        let refunds = sum(self.months.iter().map(|month| month.refunds));
+        CertificateMonth {
+            month: "Total".to_string(),
+            income,
+            refunds,
+            net: round(income - refunds),
+        }
+    }
+
+    pub fn total_taxes(&self) -> CertificateTax {
+        CertificateTax {
+            name: "Total".to_string(),
+            accrued: sum(self.taxes.iter().map(|tax| tax.accrued)),
+            paid: sum(self.taxes.iter().map(|tax| tax.paid)),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        "Income certificate".to_string()
+    }
+
+    /// Details of the entrepreneur and the period as labels and values.
+    pub fn details(&self) -> Vec<(&'static str, String)> {
+        let mut details = vec![
+            ("Entrepreneur", self.taxpayer.name.clone()),
+            ("Taxpayer number", self.taxpayer.tin.clone()),
+            ("Address", self.taxpayer.address.clone()),
+        ];
+        if let Some(phone) = &self.taxpayer.phone {
+            details.push(("Phone", phone.clone()));
+        }
+        if let Some(email) = &self.taxpayer.email {
+            details.push(("Email", email.clone()));
+        }
+        details.push(("Period", format!("{} – {}", self.from, self.to)));
+        details.push(("Issued", self.issued.to_string()));
+        details
+    }
+
+    /// Income of every month with the total of the period.
+    pub fn months_table(&self) -> Table {
+        let mut table = Table::new(vec![
+            Column::left("Month"),
+            Column::right("Income"),
+            Column::right("Refunds"),
+            Column::right("Net income"),
+        ]);
+        for month in self.months.iter() {
+            table.push(RowKind::Item, month_cells(month));
+        }
+        table.push(RowKind::Total, month_cells(&self.total()));
+        table
+    }
+
+    /// Taxes accrued on the income and paid in the period with their totals.
+    pub fn taxes_table(&self) -> Table {
+        let mut table = Table::new(vec![
+            Column::left("Tax"),
+            Column::right("Accrued"),
+            Column::right("Paid"),
+        ]);
+        for tax in self.taxes.iter() {
+            table.push(RowKind::Item, tax_cells(tax));
+        }
+        table.push(RowKind::Total, tax_cells(&self.total_taxes()));
+        table
+    }
+
+    /// Writes the certificate in the format. HTML pages are rendered from the built-in template.
+    pub fn write(&self, format: CertificateFormat, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match format {
+            CertificateFormat::Html => self.write_html(&Templates::builtin(), writer),
+            CertificateFormat::Pdf => self.write_pdf(writer),
+            CertificateFormat::Markdown => self.write_markdown(writer),
+        }
+    }
+
+    /// Writes the certificate as an HTML page rendered from the `income_certificate.html` template.
+    pub fn write_html(&self, templates: &Templates, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let document = CertificateDocument {
+            taxpayer: &self.taxpayer,
+            from: self.from.to_string(),
+            to: self.to.to_string(),
+            issued: self.issued.to_string(),
+            months: &self.months,
+            total: self.total(),
+            taxes: &self.taxes,
+            total_taxes: self.total_taxes(),
+        };
+        templates.render("income_certificate.html", &self.title(), &document, writer)
+    }
+
+    fn write_pdf(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let details = self
+            .details()
+            .iter()
+            .map(|(label, value)| format!("{}: {}", label, value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Document::new(self.title())
+            .text(details)
+            .heading("Monthly income")
+            .table(self.months_table())
+            .heading("Taxes")
+            .table(self.taxes_table())
+            .write(writer)
+    }
+
+    fn write_markdown(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(writer, "# {}", self.title())?;
+        writeln!(writer)?;
+        for (label, value) in self.details() {
+            writeln!(writer, "- {}: {}", label, value)?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "## Monthly income")?;
+        writeln!(writer)?;
+        self.months_table().write_markdown(writer)?;
+        writeln!(writer)?;
+        writeln!(writer, "## Taxes")?;
+        writeln!(writer)?;
+        self.taxes_table().write_markdown(writer)
+    }
+}
+
+impl CertificateMonth {
+    /// Month as `YYYY-MM`.
+    pub fn month(&self) -> &str {
+        &self.month
+    }
+
+    pub fn income(&self) -> f64 {
+        self.income
+    }
+
+    pub fn refunds(&self) -> f64 {
+        self.refunds
+    }
+
+    pub fn net(&self) -> f64 {
+        self.net
+    }
+}
+
+impl CertificateTax {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn accrued(&self) -> f64 {
+        self.accrued
+    }
+
+    pub fn paid(&self) -> f64 {
+        self.paid
+    }
+}
+
+/// First days of the months of the period.
+fn months(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let mut months = vec![];
+    let mut month = month_start(from);
+    while month <= to {
+        months.push(month);
+        month = month + Months::new(1);
+    }
+    months
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("months have the first day")
+}
+
+/// Sum rounded to kopecks.
+fn sum(amounts: impl Iterator<Item = f64>) -> f64 {
+    // empty sums are negative zeros
+    round(amounts.sum::<f64>() + 0.0)
+}
+
+fn round(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+fn month_cells(month: &CertificateMonth) -> Vec<String> {
+    vec![
+        month.month.clone(),
+        format_amount(month.income),
+        format_amount(month.refunds),
+        format_amount(month.net),
+    ]
+}
+
+fn tax_cells(tax: &CertificateTax) -> Vec<String> {
+    vec![
+        tax.name.clone(),
+        format_amount(tax.accrued),
+        format_amount(tax.paid),
+    ]
+}
+
+fn format_amount(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::domain::model::income::{Amount, IncomeKind};
+    use crate::domain::model::income_tax::{IncomeTaxRate, TaxRate};
+    use crate::domain::Currency;
+
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn income(day: &str, amount: f64) -> Income {
+        Income::from_date(date(day), Amount::new(amount).unwrap())
+    }
+
+    fn payment(day: &str, amount: f64) -> TaxPayment {
+        TaxPayment::new(date(day), Amount::new(amount).unwrap())
+    }
+
+    fn incomes() -> Vec<Income> {
+        vec![
+            income("2024-01-10", 9000.0),
+            income("2024-01-20", 1000.0),
+            income("2024-03-05", 3000.0),
+            income("2024-03-06", 500.0).with_kind(IncomeKind::Refund),
+            income("2024-04-10", 200.0),
+            income("2024-04-11", 7000.0),
+        ]
+    }
+
+    fn certificate_of(incomes: Vec<Income>) -> Result<IncomeCertificate, ForeignIncomesError> {
+        let rate = IncomeTaxRate::open(date("2020-01-01"), TaxRate::new(0.05).unwrap());
+        let tax = IncomeTax::new(Uuid::new_v4(), "ЄП".to_string(), vec![rate]);
+        IncomeCertificate::new(
+            Taxpayer {
+                name: "Шевченко Тарас Григорович".to_string(),
+                tin: "1234567890".to_string(),
+                address: "м. Київ".to_string(),
+                phone: None,
+                email: Some("taras@example.com".to_string()),
+            },
+            date("2024-01-15"),
+            date("2024-04-10"),
+            date("2024-04-11"),
+            incomes,
+            &[
+                payment("2024-01-19", 450.0).with_tax("ЄП".to_string()),
+                payment("2024-02-01", 10.0),
+                payment("2024-04-19", 50.0).with_tax("ЄП".to_string()),
+            ],
+            &[tax],
+        )
+    }
+
+    fn certificate() -> IncomeCertificate {
+        certificate_of(incomes()).unwrap()
+    }
+
+    #[test]
+    fn sum_incomes_of_every_month() {
+        let certificate = certificate();
+
+        let months = certificate
+            .months()
+            .iter()
+            .map(|month| (month.month(), month.income(), month.refunds(), month.net()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            months,
+            vec![
+                ("2024-01", 1000.0, 0.0, 1000.0),
+                ("2024-02", 0.0, 0.0, 0.0),
+                ("2024-03", 3000.0, 500.0, 2500.0),
+                ("2024-04", 200.0, 0.0, 200.0),
+            ]
+        );
+        assert_eq!(certificate.total().net(), 3700.0);
+    }
+
+    #[test]
+    fn sum_accrued_and_paid_taxes() {
+        let certificate = certificate();
+
+        let tax = &certificate.taxes()[0];
+        assert_eq!(tax.name(), "ЄП");
+        assert_eq!(tax.accrued(), 185.0);
+        // payments without the tax and out of the period are not counted
+        assert_eq!(tax.paid(), 450.0);
+        assert_eq!(certificate.total_taxes().accrued(), 185.0);
+    }
+
+    #[test]
+    fn reject_foreign_incomes() {
+        let mut incomes = incomes();
+        incomes.push(income("2024-02-01", 100.0).with_currency(Currency::USD));
+
+        let error = certificate_of(incomes).unwrap_err();
+
+        assert_eq!(error.incomes.len(), 1);
+    }
+
+    #[test]
+    fn write_markdown() {
+        let mut buffer = vec![];
+        certificate()
+            .write(CertificateFormat::Markdown, &mut buffer)
+            .unwrap();
+
+        let expected = "\
+# Income certificate
+
+- Entrepreneur: Шевченко Тарас Григорович
+- Taxpayer number: 1234567890
+- Address: м. Київ
+- Email: taras@example.com
+- Period: 2024-01-15 – 2024-04-10
+- Issued: 2024-04-11
+
+## Monthly income
+
+| Month | Income | Refunds | Net income |
+| --- | ---: | ---: | ---: |
+| 2024-01 | 1000.00 | 0.00 | 1000.00 |
+| 2024-02 | 0.00 | 0.00 | 0.00 |
+| 2024-03 | 3000.00 | 500.00 | 2500.00 |
+| 2024-04 | 200.00 | 0.00 | 200.00 |
+| **Total** | **4200.00** | **500.00** | **3700.00** |
+
+## Taxes
+
+| Tax | Accrued | Paid |
+| --- | ---: | ---: |
+| ЄП | 185.00 | 450.00 |
+| **Total** | **185.00** | **450.00** |
+";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+
+    #[test]
+    fn write_html_from_template() {
+        let mut buffer = vec![];
+        certificate()
+            .write(CertificateFormat::Html, &mut buffer)
+            .unwrap();
+
+        let html = String::from_utf8(buffer).unwrap();
+        assert!(html.contains("<dd>Шевченко Тарас Григорович</dd>"));
+        assert!(!html.contains("Phone"));
+        assert!(html.contains("<td>2024-02</td><td class=\"right\">0.00</td>"));
+        assert!(html
+            .contains("<td>ЄП</td><td class=\"right\">185.00</td><td class=\"right\">450.00</td>"));
+    }
+
+    #[test]
+    fn write_pdf() {
+        let mut buffer = vec![];
+        certificate()
+            .write(CertificateFormat::Pdf, &mut buffer)
+            .unwrap();
+
+        assert!(buffer.starts_with(b"%PDF-"));
+    }
+}
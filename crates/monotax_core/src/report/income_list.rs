@@ -0,0 +1,347 @@
+//! List of incomes for reviewing the database.
+//!
+//! The list is written as a human-readable table, or in CSV, JSON and NDJSON formats
+//! for processing by other tools. The table and CSV end with totals per currency,
+//! the JSON document has them in the `totals` field. NDJSON has only incomes.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::domain::model::income::Amount;
+use crate::domain::Income;
+
+use super::render::{Column, RowKind, Table};
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Output format of the income list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ListFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+    Ndjson,
+}
+
+/// Field to sort incomes by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum IncomeSort {
+    #[default]
+    Date,
+    Amount,
+    PaymentNo,
+}
+
+/// Incomes in the order of listing.
+#[derive(Debug)]
+pub struct IncomeList {
+    incomes: Vec<Income>,
+}
+
+/// Sum of incomes in a single currency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IncomeTotal {
+    pub currency: String,
+    pub amount: f64,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct IncomeListRow<'a> {
+    payment_no: i64,
+    date: String,
+    amount: f64,
+    currency: String,
+    tax_paid: bool,
+    description: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct IncomeListDocument<'a> {
+    incomes: Vec<IncomeListRow<'a>>,
+    totals: Vec<IncomeTotal>,
+}
+
+impl IncomeList {
+    pub fn new(incomes: Vec<Income>) -> Self {
+        Self { incomes }
+    }
+
+    /// Sorts incomes by the field. Incomes with equal fields are ordered by date.
+    pub fn sorted(mut self, by: IncomeSort, descending: bool) -> Self {
+        self.incomes.sort_by(|a, b| {
+            let ordering = match by {
+                IncomeSort::Date => a.datetime().cmp(&b.datetime()),
+                IncomeSort::Amount => a.amount().cmp(&b.amount()),
+                IncomeSort::PaymentNo => a.income_no().cmp(&b.income_no()),
+            };
+            ordering.then_with(|| a.datetime().cmp(&b.datetime()))
+        });
+        if descending {
+            self.incomes.reverse();
+        }
+        self
+    }
+
+    pub fn incomes(&self) -> &[Income] {
+        &self.incomes
+    }
+
+    /// Sums incomes per currency, as amounts in different currencies can't be added.
+    pub fn totals(&self) -> Vec<IncomeTotal> {
+        let mut totals: BTreeMap<String, (Amount, usize)> = BTreeMap::new();
+        for income in &self.incomes {
+            let total = totals.entry(income.currency().to_string()).or_default();
+            total.0 = total.0 + income.amount();
+            total.1 += 1;
+        }
+        totals
+            .into_iter()
+            .map(|(currency, (amount, count))| IncomeTotal {
+                currency,
+                amount: amount.amount(),
+                count,
+            })
+            .collect()
+    }
+
+    pub fn write(&self, format: ListFormat, writer: impl Write) -> anyhow::Result<()> {
+        match format {
+            ListFormat::Table => self.write_table(writer),
+            ListFormat::Csv => self.write_csv(writer),
+            ListFormat::Json => self.write_json(writer),
+            ListFormat::Ndjson => self.write_ndjson(writer),
+        }
+    }
+
+    fn rows(&self) -> Vec<IncomeListRow<'_>> {
+        self.incomes.iter().map(IncomeListRow::from).collect()
+    }
+
+    fn write_table(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        let mut table = Table::new(vec![
+            Column::left("No"),
+            Column::left("Date"),
+            Column::right("Amount"),
+            Column::left("Currency"),
+            Column::left("Tax"),
+            Column::left("Description"),
+        ]);
+        for row in self.rows() {
+            table.push(
+                RowKind::Item,
+                vec![
+                    row.payment_no.to_string(),
+                    row.date,
+                    format!("{:.2}", row.amount),
+                    row.currency,
+                    tax_status(row.tax_paid).to_string(),
+                    row.description.unwrap_or_default().to_string(),
+                ],
+            );
+        }
+        for total in self.totals() {
+            table.push(
+                RowKind::Total,
+                vec![
+                    String::new(),
+                    format!("Total ({})", total.count),
+                    format!("{:.2}", total.amount),
+                    total.currency,
+                ],
+            );
+        }
+        table.write_text(&mut writer)
+    }
+
+    fn write_csv(&self, writer: impl Write) -> anyhow::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record([
+            "payment_no",
+            "date",
+            "amount",
+            "currency",
+            "tax_paid",
+            "description",
+        ])?;
+        for row in self.rows() {
+            csv_writer.write_record([
+                row.payment_no.to_string(),
+                row.date,
+                format!("{:.2}", row.amount),
+                row.currency,
+                row.tax_paid.to_string(),
+                row.description.unwrap_or_default().to_string(),
+            ])?;
+        }
+        for total in self.totals() {
+            csv_writer.write_record([
+                String::new(),
+                String::new(),
+                format!("{:.2}", total.amount),
+                total.currency,
+                String::new(),
+                "Total".to_string(),
+            ])?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    fn write_json(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        let document = IncomeListDocument {
+            incomes: self.rows(),
+            totals: self.totals(),
+        };
+        serde_json::to_writer_pretty(&mut writer, &document)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn write_ndjson(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        for row in self.rows() {
+            serde_json::to_writer(&mut writer, &row)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn tax_status(tax_paid: bool) -> &'static str {
+    if tax_paid {
+        "paid"
+    } else {
+        "pending"
+    }
+}
+
+impl<'a> From<&'a Income> for IncomeListRow<'a> {
+    fn from(income: &'a Income) -> Self {
+        Self {
+            payment_no: income.income_no(),
+            date: income.datetime().format(DATE_FORMAT).to_string(),
+            amount: income.amount().amount(),
+            currency: income.currency().to_string(),
+            tax_paid: income.tax_paid(),
+            description: income.comment(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use crate::domain::Currency;
+
+    use super::*;
+
+    fn income(no: i64, date: &str, amount: f64) -> Income {
+        let date = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+        Income::new(date, Amount::new(amount).unwrap()).with_no(no)
+    }
+
+    fn list() -> IncomeList {
+        IncomeList::new(vec![
+            income(1, "2024-03-05 10:00", 250.0)
+                .with_comment("invoice 1".to_string())
+                .with_no(1),
+            income(2, "2024-01-15 12:30", 1000.5).with_tax_paid(true),
+            income(3, "2024-02-01 09:00", 99.5).with_currency(Currency::USD),
+        ])
+    }
+
+    fn output(list: &IncomeList, format: ListFormat) -> String {
+        let mut buffer = vec![];
+        list.write(format, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn sort_incomes() {
+        let numbers = |list: &IncomeList| {
+            list.incomes()
+                .iter()
+                .map(Income::income_no)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(numbers(&list().sorted(IncomeSort::Date, false)), [2, 3, 1]);
+        assert_eq!(numbers(&list().sorted(IncomeSort::Amount, true)), [2, 1, 3]);
+        assert_eq!(
+            numbers(&list().sorted(IncomeSort::PaymentNo, false)),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn sum_incomes_per_currency() {
+        let totals = list().totals();
+
+        assert_eq!(
+            totals,
+            vec![
+                IncomeTotal {
+                    currency: "UAH".to_string(),
+                    amount: 1250.5,
+                    count: 2
+                },
+                IncomeTotal {
+                    currency: "USD".to_string(),
+                    amount: 99.5,
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn write_table_with_totals() {
+        let table = output(&list().sorted(IncomeSort::Date, false), ListFormat::Table);
+
+        let expected = "\
+No | Date                |  Amount | Currency | Tax     | Description
+---+---------------------+---------+----------+---------+------------
+2  | 2024-01-15 12:30:00 | 1000.50 | UAH      | paid    |
+3  | 2024-02-01 09:00:00 |   99.50 | USD      | pending |
+1  | 2024-03-05 10:00:00 |  250.00 | UAH      | pending | invoice 1
+---+---------------------+---------+----------+---------+------------
+   | Total (2)           | 1250.50 | UAH      |         |
+   | Total (1)           |   99.50 | USD      |         |
+";
+        assert_eq!(table, expected);
+    }
+
+    #[test]
+    fn write_csv_with_totals() {
+        let csv = output(&list(), ListFormat::Csv);
+
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(
+            lines[0],
+            "payment_no,date,amount,currency,tax_paid,description"
+        );
+        assert_eq!(lines[1], "1,2024-03-05 10:00:00,250.00,UAH,false,invoice 1");
+        assert_eq!(lines[4], ",,1250.50,UAH,,Total");
+        assert_eq!(lines.len(), 6);
+    }
+
+    #[test]
+    fn write_json_document_and_lines() {
+        let json: serde_json::Value =
+            serde_json::from_str(&output(&list(), ListFormat::Json)).unwrap();
+        assert_eq!(json["incomes"][1]["tax_paid"], true);
+        assert_eq!(json["incomes"][2]["currency"], "USD");
+        assert_eq!(json["totals"][0]["amount"], 1250.5);
+
+        let ndjson = output(&list(), ListFormat::Ndjson);
+        let lines = ndjson.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["description"], "invoice 1");
+    }
+}
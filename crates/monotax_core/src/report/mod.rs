@@ -1 +1,10 @@
 pub mod balance;
+pub mod declaration;
+pub mod income_book;
+pub mod income_certificate;
+pub mod income_list;
+pub mod pdf;
+pub mod reconciliation;
+pub mod render;
+pub mod template;
+pub mod workbook;
@@ -0,0 +1,456 @@
+//! PDF documents of reports.
+//!
+//! Documents are laid out without a browser or system fonts: headings, paragraphs and tables
+//! flow one after another on A4 pages printed with the bundled DejaVu Sans fonts, which have
+//! Cyrillic glyphs. Text is measured with the metrics of the fonts. Documents with tables
+//! wider than a portrait page are printed in landscape, tables still too wide are shrunk,
+//! and headers of tables are repeated on every page.
+
+use std::io::Write;
+
+use printpdf::{
+    IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+};
+use ttf_parser::Face;
+
+use super::render::{Align, Row, RowKind, Table};
+
+const REGULAR_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+const BOLD_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+// Sizes are in millimeters and font sizes in points.
+const A4_SHORT_SIDE: f32 = 210.0;
+const A4_LONG_SIDE: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const PADDING: f32 = 1.5;
+const ROW_HEIGHT: f32 = 5.0;
+const TITLE_HEIGHT: f32 = 10.0;
+const HEADING_HEIGHT: f32 = 8.0;
+const BLOCK_SPACING: f32 = 3.0;
+const FOOTER_HEIGHT: f32 = 6.0;
+const FONT_SIZE: f32 = 8.0;
+const TEXT_FONT_SIZE: f32 = 9.0;
+const HEADING_FONT_SIZE: f32 = 11.0;
+const TITLE_FONT_SIZE: f32 = 14.0;
+const FOOTER_FONT_SIZE: f32 = 7.0;
+const LINE_THICKNESS: f32 = 0.5;
+/// Longer cells, usually descriptions, are shortened.
+const MAX_COLUMN_WIDTH: f32 = 70.0;
+const MM_PER_POINT: f32 = 25.4 / 72.0;
+
+/// Part of a document.
+#[derive(Debug, Clone)]
+pub enum Block {
+    Heading(String),
+    /// Paragraph wrapped to the width of the page. Line breaks start new lines.
+    Text(String),
+    Table(Table),
+}
+
+/// Document of blocks with the title on the first page and page numbers on every page.
+#[derive(Debug, Clone)]
+pub struct Document {
+    title: String,
+    blocks: Vec<Block>,
+}
+
+/// Font embedded in the document with its metrics.
+struct Font {
+    face: Face<'static>,
+    reference: IndirectFontRef,
+}
+
+/// Pages of the document written so far and the position on the last one.
+struct Pages<'a> {
+    document: &'a PdfDocumentReference,
+    width: f32,
+    height: f32,
+    layers: Vec<PdfLayerReference>,
+    y: f32,
+}
+
+/// Widths of columns and sizes of a table fitted to the page.
+struct TableLayout {
+    widths: Vec<f32>,
+    font_size: f32,
+    row_height: f32,
+}
+
+impl Document {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            blocks: vec![],
+        }
+    }
+
+    pub fn heading(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Heading(text.into()));
+        self
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Text(text.into()));
+        self
+    }
+
+    pub fn table(mut self, table: Table) -> Self {
+        self.blocks.push(Block::Table(table));
+        self
+    }
+
+    pub fn write(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let (document, _) = self.render()?;
+        writer.write_all(&document.save_to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Lays out the blocks and returns the document with the number of its pages.
+    fn render(&self) -> anyhow::Result<(PdfDocumentReference, usize)> {
+        let regular_face = Face::parse(REGULAR_FONT, 0)?;
+        let bold_face = Face::parse(BOLD_FONT, 0)?;
+        let (width, height) = if self.is_wide(&bold_face) {
+            (A4_LONG_SIDE, A4_SHORT_SIDE)
+        } else {
+            (A4_SHORT_SIDE, A4_LONG_SIDE)
+        };
+        let (document, page, layer) = PdfDocument::new(&self.title, Mm(width), Mm(height), "Page");
+        let regular = Font {
+            face: regular_face,
+            reference: document.add_external_font(REGULAR_FONT)?,
+        };
+        let bold = Font {
+            face: bold_face,
+            reference: document.add_external_font(BOLD_FONT)?,
+        };
+        let mut pages = Pages::new(
+            &document,
+            width,
+            height,
+            document.get_page(page).get_layer(layer),
+        );
+        let content_width = width - 2.0 * MARGIN;
+        pages.layer().use_text(
+            &self.title,
+            TITLE_FONT_SIZE,
+            Mm(MARGIN),
+            Mm(pages.y - TITLE_FONT_SIZE * MM_PER_POINT),
+            &bold.reference,
+        );
+        pages.y -= TITLE_HEIGHT;
+        for block in &self.blocks {
+            match block {
+                Block::Heading(text) => {
+                    // headings are kept with the first lines of their blocks
+                    pages.reserve(BLOCK_SPACING + HEADING_HEIGHT + 2.0 * ROW_HEIGHT);
+                    pages.y -= BLOCK_SPACING;
+                    let text = fit(&bold.face, text, HEADING_FONT_SIZE, content_width);
+                    pages.text(&text, HEADING_FONT_SIZE, MARGIN, HEADING_HEIGHT, &bold);
+                    pages.y -= HEADING_HEIGHT;
+                }
+                Block::Text(text) => {
+                    for line in wrap(&regular.face, text, TEXT_FONT_SIZE, content_width) {
+                        pages.reserve(ROW_HEIGHT);
+                        pages.text(&line, TEXT_FONT_SIZE, MARGIN, ROW_HEIGHT, &regular);
+                        pages.y -= ROW_HEIGHT;
+                    }
+                    pages.y -= BLOCK_SPACING;
+                }
+                Block::Table(table) => {
+                    let layout = TableLayout::new(table, &bold.face, content_width);
+                    layout.write(table, &mut pages, &regular, &bold);
+                    pages.y -= BLOCK_SPACING;
+                }
+            }
+        }
+        let count = pages.layers.len();
+        for (index, layer) in pages.layers.iter().enumerate() {
+            let footer = format!("{} — {} / {}", self.title, index + 1, count);
+            let footer_width = text_width(&regular.face, &footer, FOOTER_FONT_SIZE);
+            layer.use_text(
+                &footer,
+                FOOTER_FONT_SIZE,
+                Mm(width - MARGIN - footer_width),
+                Mm(MARGIN),
+                &regular.reference,
+            );
+        }
+        Ok((document, count))
+    }
+
+    /// Whether some table doesn't fit a portrait page.
+    fn is_wide(&self, bold: &Face) -> bool {
+        self.blocks.iter().any(|block| match block {
+            Block::Table(table) => {
+                natural_widths(table, bold).iter().sum::<f32>() > A4_SHORT_SIDE - 2.0 * MARGIN
+            }
+            _ => false,
+        })
+    }
+}
+
+impl<'a> Pages<'a> {
+    fn new(
+        document: &'a PdfDocumentReference,
+        width: f32,
+        height: f32,
+        layer: PdfLayerReference,
+    ) -> Self {
+        layer.set_outline_thickness(LINE_THICKNESS);
+        Self {
+            document,
+            width,
+            height,
+            layers: vec![layer],
+            y: height - MARGIN,
+        }
+    }
+
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("documents have pages")
+    }
+
+    /// Starts a new page unless the height fits the current one.
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN + FOOTER_HEIGHT {
+            let (page, layer) = self
+                .document
+                .add_page(Mm(self.width), Mm(self.height), "Page");
+            let layer = self.document.get_page(page).get_layer(layer);
+            layer.set_outline_thickness(LINE_THICKNESS);
+            self.layers.push(layer);
+            self.y = self.height - MARGIN;
+        }
+    }
+
+    /// Writes the text in the line of the height starting at the current position.
+    fn text(&self, text: &str, size: f32, x: f32, height: f32, font: &Font) {
+        let baseline = self.y - height / 2.0 - size * MM_PER_POINT / 3.0;
+        self.layer()
+            .use_text(text, size, Mm(x), Mm(baseline), &font.reference);
+    }
+
+    /// Draws a horizontal line at the current position.
+    fn rule(&self, width: f32) {
+        self.layer().add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(MARGIN + width), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+}
+
+impl TableLayout {
+    fn new(table: &Table, bold: &Face, content_width: f32) -> Self {
+        let widths = natural_widths(table, bold);
+        let scale = (content_width / widths.iter().sum::<f32>()).min(1.0);
+        Self {
+            widths: widths.iter().map(|width| width * scale).collect(),
+            font_size: FONT_SIZE * scale,
+            row_height: ROW_HEIGHT * scale,
+        }
+    }
+
+    fn width(&self) -> f32 {
+        self.widths.iter().sum()
+    }
+
+    /// Writes the table from the current position. Lines separate the header
+    /// and every change between items, subtotals and totals.
+    fn write(&self, table: &Table, pages: &mut Pages, regular: &Font, bold: &Font) {
+        let titles = table
+            .columns()
+            .iter()
+            .map(|column| column.title().to_string())
+            .collect::<Vec<_>>();
+        pages.reserve(2.0 * self.row_height);
+        self.header(table, &titles, pages, bold);
+        let mut previous = None;
+        for row in table.rows() {
+            if pages.y - self.row_height < MARGIN + FOOTER_HEIGHT {
+                pages.rule(self.width());
+                pages.reserve(self.row_height);
+                self.header(table, &titles, pages, bold);
+                previous = None;
+            }
+            if previous.is_some_and(|kind| kind != row.kind()) {
+                pages.rule(self.width());
+            }
+            let font = match row.kind() {
+                RowKind::Item => regular,
+                RowKind::Subtotal | RowKind::Total => bold,
+            };
+            self.row(table, row.cells(), pages, font);
+            previous = Some(row.kind());
+        }
+        pages.rule(self.width());
+    }
+
+    fn header(&self, table: &Table, titles: &[String], pages: &mut Pages, bold: &Font) {
+        pages.rule(self.width());
+        self.row(table, titles, pages, bold);
+        pages.rule(self.width());
+    }
+
+    fn row(&self, table: &Table, cells: &[String], pages: &mut Pages, font: &Font) {
+        let mut x = MARGIN;
+        for ((cell, width), column) in cells.iter().zip(&self.widths).zip(table.columns()) {
+            let text = fit(&font.face, cell, self.font_size, width - 2.0 * PADDING);
+            let left = match column.align() {
+                Align::Left => x + PADDING,
+                Align::Right => x + width - PADDING - text_width(&font.face, &text, self.font_size),
+            };
+            pages.text(&text, self.font_size, left, self.row_height, font);
+            x += width;
+        }
+        pages.y -= self.row_height;
+    }
+}
+
+/// Widths of columns fitting their cells, without shrinking.
+fn natural_widths(table: &Table, bold: &Face) -> Vec<f32> {
+    table
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let content = table
+                .rows()
+                .iter()
+                .map(|row: &Row| text_width(bold, &row.cells()[index], FONT_SIZE))
+                .fold(text_width(bold, column.title(), FONT_SIZE), f32::max);
+            content.min(MAX_COLUMN_WIDTH) + 2.0 * PADDING
+        })
+        .collect()
+}
+
+fn text_width(face: &Face, text: &str, size: f32) -> f32 {
+    let units = text
+        .chars()
+        .filter_map(|c| face.glyph_index(c))
+        .filter_map(|glyph| face.glyph_hor_advance(glyph))
+        .map(f32::from)
+        .sum::<f32>();
+    units / f32::from(face.units_per_em()) * size * MM_PER_POINT
+}
+
+/// Shortens the text with an ellipsis to fit the width.
+fn fit(face: &Face, text: &str, size: f32, width: f32) -> String {
+    if text_width(face, text, size) <= width {
+        return text.to_string();
+    }
+    let mut fitted = text.to_string();
+    while !fitted.is_empty() && text_width(face, &format!("{}…", fitted), size) > width {
+        fitted.pop();
+    }
+    format!("{}…", fitted.trim_end())
+}
+
+/// Splits the text into lines of words fitting the width.
+/// Words longer than the width are shortened.
+fn wrap(face: &Face, text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let extended = match line.is_empty() {
+                true => word.to_string(),
+                false => format!("{} {}", line, word),
+            };
+            if !line.is_empty() && text_width(face, &extended, size) > width {
+                lines.push(fit(face, &line, size, width));
+                line = word.to_string();
+            } else {
+                line = extended;
+            }
+        }
+        lines.push(fit(face, &line, size, width));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::render::Column;
+    use super::*;
+
+    fn table(columns: usize, rows: usize) -> Table {
+        let mut table = Table::new(
+            (0..columns)
+                .map(|index| Column::right(format!("Column {}", index)))
+                .collect(),
+        );
+        for index in 0..rows {
+            table.push(RowKind::Item, vec![format!("{}.00", index); columns]);
+        }
+        table.push(RowKind::Total, vec!["Total".to_string()]);
+        table
+    }
+
+    fn bold() -> Face<'static> {
+        Face::parse(BOLD_FONT, 0).unwrap()
+    }
+
+    #[test]
+    fn print_narrow_tables_in_portrait() {
+        let document = Document::new("Narrow").table(table(3, 10));
+
+        assert!(!document.is_wide(&bold()));
+        let (_, pages) = document.render().unwrap();
+        assert_eq!(pages, 1);
+    }
+
+    #[test]
+    fn print_wide_tables_in_landscape() {
+        let document = Document::new("Wide")
+            .table(table(3, 10))
+            .table(table(12, 10));
+
+        assert!(document.is_wide(&bold()));
+        let layout = TableLayout::new(&table(12, 10), &bold(), A4_LONG_SIDE - 2.0 * MARGIN);
+        assert_eq!(layout.font_size, FONT_SIZE);
+    }
+
+    #[test]
+    fn shrink_tables_wider_than_page() {
+        let content_width = A4_LONG_SIDE - 2.0 * MARGIN;
+        let layout = TableLayout::new(&table(40, 10), &bold(), content_width);
+
+        assert!(layout.font_size < FONT_SIZE);
+        assert!(layout.width() <= content_width + 0.01);
+    }
+
+    #[test]
+    fn split_long_tables_into_pages() {
+        let document = Document::new("Long").text("Incomes").table(table(3, 120));
+
+        let (_, pages) = document.render().unwrap();
+        assert_eq!(pages, 3);
+    }
+
+    #[test]
+    fn shorten_long_cells() {
+        assert_eq!(fit(&bold(), "Оплата", FONT_SIZE, 50.0), "Оплата");
+        let fitted = fit(
+            &bold(),
+            "Оплата за послуги згідно з рахунком",
+            FONT_SIZE,
+            20.0,
+        );
+        assert!(fitted.ends_with('…'));
+        assert!(text_width(&bold(), &fitted, FONT_SIZE) <= 20.0);
+    }
+
+    #[test]
+    fn wrap_text_by_words() {
+        let text = "Оплата за послуги згідно з рахунком\nДругий рядок";
+        let lines = wrap(&bold(), text, FONT_SIZE, 35.0);
+
+        assert_eq!(
+            lines,
+            vec!["Оплата за послуги", "згідно з рахунком", "Другий рядок"]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Reconciliation of incomes kept in Taxer with incomes in the database.
+//!
+//! Incomes match when they have the same date, amount, currency and kind. Every income
+//! matches at most one income of the other side, so duplicates on one side are reported.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::domain::Income;
+
+use super::render::{Column, ReportRenderer, RowKind, Table};
+
+/// Incomes found on one side only.
+#[derive(Debug)]
+pub struct Reconciliation {
+    matched: usize,
+    only_in_taxer: Vec<Income>,
+    only_in_monotax: Vec<Income>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReconciliationDocument<'a> {
+    matched: usize,
+    only_in_taxer: Vec<ReconciliationDocumentIncome<'a>>,
+    only_in_monotax: Vec<ReconciliationDocumentIncome<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReconciliationDocumentIncome<'a> {
+    date: String,
+    amount: f64,
+    currency: String,
+    kind: &'static str,
+    payment_no: i64,
+    description: Option<&'a str>,
+}
+
+impl Reconciliation {
+    pub fn new(taxer: Vec<Income>, mut monotax: Vec<Income>) -> Self {
+        let mut matched = 0;
+        let mut only_in_taxer = vec![];
+        for income in taxer {
+            match monotax.iter().position(|other| same(&income, other)) {
+                Some(position) => {
+                    monotax.remove(position);
+                    matched += 1;
+                }
+                None => only_in_taxer.push(income),
+            }
+        }
+        only_in_taxer.sort();
+        monotax.sort();
+        Self {
+            matched,
+            only_in_taxer,
+            only_in_monotax: monotax,
+        }
+    }
+
+    /// Number of incomes found on both sides.
+    pub fn matched(&self) -> usize {
+        self.matched
+    }
+
+    pub fn only_in_taxer(&self) -> &[Income] {
+        &self.only_in_taxer
+    }
+
+    pub fn only_in_monotax(&self) -> &[Income] {
+        &self.only_in_monotax
+    }
+
+    pub fn is_reconciled(&self) -> bool {
+        self.only_in_taxer.is_empty() && self.only_in_monotax.is_empty()
+    }
+}
+
+fn same(income: &Income, other: &Income) -> bool {
+    income.date() == other.date()
+        && kopecks(income) == kopecks(other)
+        && income.currency() == other.currency()
+        && income.kind() == other.kind()
+}
+
+fn kopecks(income: &Income) -> i64 {
+    (income.amount().amount() * 100.0).round() as i64
+}
+
+impl ReportRenderer for Reconciliation {
+    fn table(&self) -> Table {
+        let mut table = Table::new(vec![
+            Column::left("Found in"),
+            Column::left("Date"),
+            Column::right("Amount"),
+            Column::left("Currency"),
+            Column::left("Kind"),
+            Column::right("Payment no"),
+            Column::left("Description"),
+        ]);
+        let sides = [
+            ("Taxer", &self.only_in_taxer),
+            ("monotax", &self.only_in_monotax),
+        ];
+        for (side, incomes) in sides {
+            for income in incomes {
+                let payment_no = match side {
+                    "monotax" => income.income_no().to_string(),
+                    _ => String::new(),
+                };
+                table.push(
+                    RowKind::Item,
+                    vec![
+                        side.to_string(),
+                        income.date().to_string(),
+                        format!("{:.2}", income.amount().amount()),
+                        income.currency().to_string(),
+                        income.kind().to_string(),
+                        payment_no,
+                        income.comment().unwrap_or_default().to_string(),
+                    ],
+                );
+            }
+        }
+        table.push(RowKind::Total, vec![format!("{} matched", self.matched)]);
+        table
+    }
+
+    fn write_json(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let document = ReconciliationDocument {
+            matched: self.matched,
+            only_in_taxer: self.only_in_taxer.iter().map(Into::into).collect(),
+            only_in_monotax: self.only_in_monotax.iter().map(Into::into).collect(),
+        };
+        serde_json::to_writer_pretty(&mut *writer, &document)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    fn title(&self) -> String {
+        "Reconciliation with Taxer".to_string()
+    }
+}
+
+impl<'a> From<&'a Income> for ReconciliationDocumentIncome<'a> {
+    fn from(income: &'a Income) -> Self {
+        Self {
+            date: income.date().to_string(),
+            amount: income.amount().amount(),
+            currency: income.currency().to_string(),
+            kind: income.kind().as_str(),
+            payment_no: income.income_no(),
+            description: income.comment(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use crate::domain::model::income::Amount;
+    use crate::report::render::ReportFormat;
+
+    use super::*;
+
+    fn income(date: &str, amount: f64) -> Income {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Income::from_date(date, Amount::new(amount).unwrap())
+    }
+
+    fn reconciliation() -> Reconciliation {
+        let taxer = vec![
+            income("2024-01-10", 1000.0),
+            income("2024-02-01", 300.0),
+            income("2024-02-01", 300.0),
+            income("2024-03-05", 99.99),
+        ];
+        let monotax = vec![
+            income("2024-03-05", 99.99).with_no(3),
+            income("2024-02-01", 300.0).with_no(2),
+            income("2024-01-10", 1000.01).with_no(1),
+        ];
+        Reconciliation::new(taxer, monotax)
+    }
+
+    #[test]
+    fn find_incomes_of_one_side() {
+        let reconciliation = reconciliation();
+
+        assert_eq!(reconciliation.matched(), 2);
+        assert!(!reconciliation.is_reconciled());
+        let taxer = reconciliation
+            .only_in_taxer()
+            .iter()
+            .map(|income| income.amount().amount())
+            .collect::<Vec<_>>();
+        assert_eq!(taxer, vec![1000.0, 300.0]);
+        assert_eq!(reconciliation.only_in_monotax().len(), 1);
+        assert_eq!(reconciliation.only_in_monotax()[0].income_no(), 1);
+    }
+
+    #[test]
+    fn render_table() {
+        let mut buffer = vec![];
+        reconciliation()
+            .render(ReportFormat::Table, &mut buffer)
+            .unwrap();
+
+        let expected = "\
+Found in  | Date       |  Amount | Currency | Kind   | Payment no | Description
+----------+------------+---------+----------+--------+------------+------------
+Taxer     | 2024-01-10 | 1000.00 | UAH      | income |            |
+Taxer     | 2024-02-01 |  300.00 | UAH      | income |            |
+monotax   | 2024-01-10 | 1000.01 | UAH      | income |          1 |
+----------+------------+---------+----------+--------+------------+------------
+2 matched |            |         |          |        |            |
+";
+        assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    }
+}
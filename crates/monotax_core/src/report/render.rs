@@ -0,0 +1,464 @@
+//! Rendering of reports for people and other tools.
+//!
+//! Reports implement [`ReportRenderer`] by describing themselves as a [`Table`] of items,
+//! subtotals and totals, and as a JSON document. The table is then written as aligned text
+//! for the terminal, as CSV or as Markdown. Documents meant for printing and spreadsheets
+//! also write the table as an HTML page, a PDF document or an Excel workbook. Reports with
+//! a [template](super::template) render their HTML pages from it instead of the table.
+
+use std::io::Write;
+
+use clap::ValueEnum;
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+
+use super::pdf;
+use super::template::Templates;
+
+/// Output format of reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Table,
+    Csv,
+    Json,
+    Markdown,
+    Html,
+    Pdf,
+}
+
+/// Report that can be written in every [`ReportFormat`].
+pub trait ReportRenderer {
+    /// Tabular view of the report for the text, CSV and Markdown formats.
+    fn table(&self) -> Table;
+
+    /// Writes the report as a JSON document.
+    fn write_json(&self, writer: &mut dyn Write) -> anyhow::Result<()>;
+
+    /// Title of HTML pages and PDF documents.
+    fn title(&self) -> String;
+
+    /// Writes the report as an HTML page. Reports without a template write their table.
+    fn render_html(&self, _templates: &Templates, writer: &mut dyn Write) -> anyhow::Result<()> {
+        self.table().write_html(&self.title(), writer)
+    }
+
+    /// Writes the report in the format. HTML pages are rendered from built-in templates.
+    fn render(&self, format: ReportFormat, writer: &mut dyn Write) -> anyhow::Result<()> {
+        match format {
+            ReportFormat::Table => self.table().write_text(writer),
+            ReportFormat::Csv => self.table().write_csv(writer),
+            ReportFormat::Json => self.write_json(writer),
+            ReportFormat::Markdown => self.table().write_markdown(writer),
+            ReportFormat::Html => self.render_html(&Templates::builtin(), writer),
+            ReportFormat::Pdf => self.table().write_pdf(&self.title(), writer),
+        }
+    }
+}
+
+/// Horizontal alignment of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    title: String,
+    align: Align,
+}
+
+/// Kind of a table row. Summary rows are set apart from items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RowKind {
+    Item,
+    Subtotal,
+    Total,
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+    kind: RowKind,
+    cells: Vec<String>,
+}
+
+/// Report contents as rows of formatted cells.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Row>,
+}
+
+impl Column {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn align(&self) -> Align {
+        self.align
+    }
+
+    pub fn left(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            align: Align::Left,
+        }
+    }
+
+    pub fn right(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            align: Align::Right,
+        }
+    }
+}
+
+impl Row {
+    pub fn kind(&self) -> RowKind {
+        self.kind
+    }
+
+    pub fn cells(&self) -> &[String] {
+        &self.cells
+    }
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Appends the row. Missing cells are left empty.
+    pub fn push(&mut self, kind: RowKind, mut cells: Vec<String>) {
+        cells.resize(self.columns.len(), String::new());
+        self.rows.push(Row { kind, cells });
+    }
+
+    /// Writes the table aligned for the terminal.
+    ///
+    /// Lines separate the header and every change between items, subtotals and totals.
+    pub fn write_text(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let mut widths = self
+            .columns
+            .iter()
+            .map(|column| column.title.chars().count())
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(&row.cells) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let separator = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-");
+        let titles = self
+            .columns
+            .iter()
+            .map(|column| column.title.clone())
+            .collect::<Vec<_>>();
+        self.write_text_line(writer, &titles, &widths)?;
+        let mut previous = None;
+        for row in &self.rows {
+            if previous != Some(row.kind) {
+                writeln!(writer, "{}", separator)?;
+            }
+            self.write_text_line(writer, &row.cells, &widths)?;
+            previous = Some(row.kind);
+        }
+        Ok(())
+    }
+
+    fn write_text_line(
+        &self,
+        writer: &mut dyn Write,
+        cells: &[String],
+        widths: &[usize],
+    ) -> anyhow::Result<()> {
+        let cells = cells
+            .iter()
+            .zip(widths)
+            .zip(&self.columns)
+            .map(|((cell, width), column)| match column.align {
+                Align::Left => format!("{:<width$}", cell),
+                Align::Right => format!("{:>width$}", cell),
+            })
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", cells.join(" | ").trim_end())?;
+        Ok(())
+    }
+
+    pub fn write_csv(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer.write_record(self.columns.iter().map(|column| &column.title))?;
+        for row in &self.rows {
+            csv_writer.write_record(&row.cells)?;
+        }
+        csv_writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes a GitHub-flavored Markdown table. Summary rows are in bold.
+    pub fn write_markdown(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let titles = self
+            .columns
+            .iter()
+            .map(|column| escape_markdown(&column.title))
+            .collect::<Vec<_>>();
+        writeln!(writer, "| {} |", titles.join(" | "))?;
+        let alignments = self
+            .columns
+            .iter()
+            .map(|column| match column.align {
+                Align::Left => "---",
+                Align::Right => "---:",
+            })
+            .collect::<Vec<_>>();
+        writeln!(writer, "| {} |", alignments.join(" | "))?;
+        for row in &self.rows {
+            let cells = row
+                .cells
+                .iter()
+                .map(|cell| match row.kind {
+                    RowKind::Item => escape_markdown(cell),
+                    _ if cell.is_empty() => String::new(),
+                    _ => format!("**{}**", escape_markdown(cell)),
+                })
+                .collect::<Vec<_>>();
+            writeln!(writer, "| {} |", cells.join(" | "))?;
+        }
+        Ok(())
+    }
+
+    /// Writes a standalone HTML page with the table, styled for printing.
+    pub fn write_html(&self, title: &str, writer: &mut dyn Write) -> anyhow::Result<()> {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, "<meta charset=\"utf-8\">")?;
+        writeln!(writer, "<title>{}</title>", escape_html(title))?;
+        writeln!(writer, "<style>{}</style>", HTML_STYLE)?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<h1>{}</h1>", escape_html(title))?;
+        writeln!(writer, "<table>")?;
+        writeln!(writer, "<thead>")?;
+        let titles = self
+            .columns
+            .iter()
+            .map(|column| html_cell("th", column.align, &column.title))
+            .collect::<String>();
+        writeln!(writer, "<tr>{}</tr>", titles)?;
+        writeln!(writer, "</thead>")?;
+        writeln!(writer, "<tbody>")?;
+        for row in &self.rows {
+            let cells = row
+                .cells
+                .iter()
+                .zip(&self.columns)
+                .map(|(cell, column)| html_cell("td", column.align, cell))
+                .collect::<String>();
+            match row.kind {
+                RowKind::Item => writeln!(writer, "<tr>{}</tr>", cells)?,
+                RowKind::Subtotal => writeln!(writer, "<tr class=\"subtotal\">{}</tr>", cells)?,
+                RowKind::Total => writeln!(writer, "<tr class=\"total\">{}</tr>", cells)?,
+            }
+        }
+        writeln!(writer, "</tbody>")?;
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")?;
+        Ok(())
+    }
+
+    /// Writes a PDF document with the table on A4 pages. Summary rows are in bold.
+    pub fn write_pdf(&self, title: &str, writer: &mut dyn Write) -> anyhow::Result<()> {
+        pdf::Document::new(title).table(self.clone()).write(writer)
+    }
+
+    /// Writes an Excel workbook with the table on a single sheet.
+    ///
+    /// Cells of right-aligned columns holding numbers are written as numbers,
+    /// so they can be summed in the spreadsheet. Summary rows are in bold.
+    pub fn write_xlsx(&self, sheet: &str, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(sheet)?;
+        let header = Format::new()
+            .set_bold()
+            .set_border_bottom(FormatBorder::Thin);
+        let bold = Format::new().set_bold();
+        let number = Format::new().set_num_format("0.00");
+        let bold_number = Format::new().set_bold().set_num_format("0.00");
+        for (index, column) in self.columns.iter().enumerate() {
+            let index = index as u16;
+            worksheet.write_string_with_format(0, index, &column.title, &header)?;
+            let width = self
+                .rows
+                .iter()
+                .map(|row| row.cells[index as usize].chars().count())
+                .chain([column.title.chars().count()])
+                .max()
+                .unwrap_or_default();
+            worksheet.set_column_width(index, width as f64 + 2.0)?;
+        }
+        for (index, row) in self.rows.iter().enumerate() {
+            let row_index = index as u32 + 1;
+            let summary = row.kind != RowKind::Item;
+            for (column_index, (cell, column)) in row.cells.iter().zip(&self.columns).enumerate() {
+                let column_index = column_index as u16;
+                let value = match column.align {
+                    Align::Right => cell.parse::<f64>().ok(),
+                    Align::Left => None,
+                };
+                match (value, summary) {
+                    (Some(value), false) => worksheet.write_number_with_format(
+                        row_index,
+                        column_index,
+                        value,
+                        &number,
+                    )?,
+                    (Some(value), true) => worksheet.write_number_with_format(
+                        row_index,
+                        column_index,
+                        value,
+                        &bold_number,
+                    )?,
+                    (None, _) if cell.is_empty() => worksheet,
+                    (None, false) => worksheet.write_string(row_index, column_index, cell)?,
+                    (None, true) => {
+                        worksheet.write_string_with_format(row_index, column_index, cell, &bold)?
+                    }
+                };
+            }
+        }
+        writer.write_all(&workbook.save_to_buffer()?)?;
+        Ok(())
+    }
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; font-size: 11pt; } \
+table { border-collapse: collapse; width: 100%; } \
+th, td { border: 1px solid #999; padding: 2px 6px; } \
+th { background: #eee; } \
+.right { text-align: right; } \
+.subtotal, .total { font-weight: bold; } \
+@media print { h1 { font-size: 14pt; } thead { display: table-header-group; } tr { page-break-inside: avoid; } }";
+
+fn html_cell(tag: &str, align: Align, content: &str) -> String {
+    match align {
+        Align::Left => format!("<{}>{}</{}>", tag, escape_html(content), tag),
+        Align::Right => format!(
+            "<{} class=\"right\">{}</{}>",
+            tag,
+            escape_html(content),
+            tag
+        ),
+    }
+}
+
+fn escape_html(content: &str) -> String {
+    content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(vec![Column::left("Month"), Column::right("Amount")]);
+        table.push(RowKind::Item, vec!["2024-01".into(), "10.00".into()]);
+        table.push(RowKind::Item, vec!["2024-02 | 2".into(), "5.50".into()]);
+        table.push(RowKind::Subtotal, vec!["Q1".into(), "15.50".into()]);
+        table.push(RowKind::Total, vec!["Total".into()]);
+        table
+    }
+
+    fn output(write: impl Fn(&Table, &mut dyn Write) -> anyhow::Result<()>) -> String {
+        let mut buffer = vec![];
+        write(&table(), &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn write_aligned_text() {
+        let expected = "\
+Month       | Amount
+------------+-------
+2024-01     |  10.00
+2024-02 | 2 |   5.50
+------------+-------
+Q1          |  15.50
+------------+-------
+Total       |
+";
+        assert_eq!(output(Table::write_text), expected);
+    }
+
+    #[test]
+    fn write_csv() {
+        let expected = "\
+Month,Amount
+2024-01,10.00
+2024-02 | 2,5.50
+Q1,15.50
+Total,
+";
+        assert_eq!(output(Table::write_csv), expected);
+    }
+
+    #[test]
+    fn write_markdown() {
+        let expected = "\
+| Month | Amount |
+| --- | ---: |
+| 2024-01 | 10.00 |
+| 2024-02 \\| 2 | 5.50 |
+| **Q1** | **15.50** |
+| **Total** |  |
+";
+        assert_eq!(output(Table::write_markdown), expected);
+    }
+
+    #[test]
+    fn write_html() {
+        let html = output(|table, writer| table.write_html("Incomes <2024>", writer));
+
+        assert!(html.contains("<title>Incomes &lt;2024&gt;</title>"));
+        assert!(html.contains("<tr><th>Month</th><th class=\"right\">Amount</th></tr>"));
+        assert!(html.contains("<tr><td>2024-01</td><td class=\"right\">10.00</td></tr>"));
+        assert!(
+            html.contains("<tr class=\"subtotal\"><td>Q1</td><td class=\"right\">15.50</td></tr>")
+        );
+    }
+
+    #[test]
+    fn write_pdf() {
+        let mut buffer = vec![];
+        table().write_pdf("Доходи", &mut buffer).unwrap();
+
+        assert!(buffer.starts_with(b"%PDF-"));
+        assert!(buffer.ends_with(b"%%EOF"));
+    }
+}
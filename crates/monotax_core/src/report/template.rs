@@ -0,0 +1,199 @@
+//! HTML pages of reports rendered from Tera templates.
+//!
+//! Templates get the JSON document of the report, the same one written by the JSON format,
+//! and the `title` of the page. Built-in templates are compiled into the application.
+//! A directory of user templates overrides built-in templates with the same file names,
+//! e.g. `balance.html`, and `base.html` there changes the layout and styles of every page.
+//!
+//! Besides the filters of Tera, templates can format amounts with two decimals
+//! with the `money` filter.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera, Value};
+
+const BUILTIN_TEMPLATES: [(&str, &str); 5] = [
+    ("base.html", include_str!("../../templates/base.html")),
+    ("balance.html", include_str!("../../templates/balance.html")),
+    (
+        "declaration.html",
+        include_str!("../../templates/declaration.html"),
+    ),
+    (
+        "income_book.html",
+        include_str!("../../templates/income_book.html"),
+    ),
+    (
+        "income_certificate.html",
+        include_str!("../../templates/income_certificate.html"),
+    ),
+];
+
+/// Location of user templates.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplatesConfiguration {
+    /// Directory with templates overriding the built-in ones.
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+}
+
+/// Templates of report pages.
+#[derive(Debug)]
+pub struct Templates {
+    tera: Tera,
+}
+
+impl Templates {
+    /// Built-in templates only.
+    pub fn builtin() -> Self {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(BUILTIN_TEMPLATES)
+            .expect("built-in templates are valid");
+        tera.register_filter("money", money);
+        Self { tera }
+    }
+
+    /// Built-in templates overridden by `*.html` files of the directory, if any.
+    pub fn new(dir: Option<&Path>) -> anyhow::Result<Self> {
+        let mut templates = Self::builtin();
+        let Some(dir) = dir else {
+            return Ok(templates);
+        };
+        let mut overrides = vec![];
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("failed to read templates from {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "html") {
+                continue;
+            }
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .with_context(|| format!("invalid template name {}", path.display()))?
+                .to_string();
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read template {}", path.display()))?;
+            overrides.push((name, content));
+        }
+        templates
+            .tera
+            .add_raw_templates(overrides)
+            .with_context(|| format!("invalid templates in {}", dir.display()))?;
+        Ok(templates)
+    }
+
+    /// Renders the template with the report document.
+    pub fn render(
+        &self,
+        name: &str,
+        title: &str,
+        document: &impl Serialize,
+        writer: &mut dyn std::io::Write,
+    ) -> anyhow::Result<()> {
+        let mut context = Context::from_serialize(document)?;
+        context.insert("title", title);
+        self.tera
+            .render_to(name, &context, writer)
+            .with_context(|| format!("failed to render template {}", name))?;
+        Ok(())
+    }
+}
+
+/// Formats a number with two decimals.
+fn money(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let amount = value
+        .as_f64()
+        .ok_or_else(|| tera::Error::msg(format!("money filter expects a number, got {}", value)))?;
+    Ok(Value::String(format!("{:.2}", amount)))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render(templates: &Templates, document: Value) -> String {
+        let mut buffer = vec![];
+        templates
+            .render("balance.html", "Balance <2024>", &document, &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn balance() -> Value {
+        json!({
+            "taxes": ["ЄП"],
+            "incomes": [{
+                "date": "2024-01-10",
+                "amount": 1000.0,
+                "obligations": [{"name": "ЄП", "amount": 50.0}],
+                "total_obligations": 50.0
+            }],
+            "months": [],
+            "quarters": [],
+            "total": {
+                "period": "Total",
+                "amount": 1000.0,
+                "obligations": [{"name": "ЄП", "amount": 50.0}],
+                "total_obligations": 50.0
+            }
+        })
+    }
+
+    #[test]
+    fn render_builtin_template() {
+        let html = render(&Templates::builtin(), balance());
+
+        assert!(html.contains("<title>Balance &lt;2024&gt;</title>"));
+        assert!(html.contains("<td>2024-01-10</td>"));
+        assert!(html.contains("<td class=\"right\">1000.00</td>"));
+        assert!(html.contains("<th class=\"right\">ЄП</th>"));
+    }
+
+    #[test]
+    fn override_builtin_templates_from_dir() {
+        let dir = std::env::temp_dir().join(format!("monotax-templates-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        fs::write(
+            dir.join("base.html"),
+            "<html>{% block content %}{% endblock content %}</html>",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "{% invalid").unwrap();
+
+        let templates = Templates::new(Some(&dir)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let html = render(&templates, balance());
+        assert!(html.starts_with("<html>"));
+        assert!(!html.contains("<title>"));
+        assert!(html.contains("<td>2024-01-10</td>"));
+    }
+
+    #[test]
+    fn reject_invalid_user_template() {
+        let dir = std::env::temp_dir().join(format!("monotax-templates-{}", uuid::Uuid::new_v4()));
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("balance.html"), "{% if %}").unwrap();
+
+        let result = Templates::new(Some(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn format_money() {
+        assert_eq!(
+            money(&json!(1234.5), &HashMap::new()).unwrap(),
+            json!("1234.50")
+        );
+        assert!(money(&json!("text"), &HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,410 @@
+//! Excel workbook of reports for accountants.
+//!
+//! The workbook has a sheet of incomes, a balance sheet with a column per tax and a summary
+//! of quarters. Dates and amounts are written as typed cells with currency formats, headers
+//! stay frozen while scrolling and totals are formulas, so sheets can be edited and
+//! recalculated. Formulas carry their results for readers that don't calculate them.
+
+use std::io::Write;
+
+use rust_xlsxwriter::{
+    cell_range, ColNum, Format, FormatBorder, Formula, RowNum, Workbook, Worksheet,
+};
+
+use crate::domain::model::income::{ForeignIncomesError, IncomeKind};
+use crate::domain::model::income_tax::IncomeTax;
+use crate::domain::{Currency, Income};
+
+use super::balance::{BalanceReport, IncomeTaxObligation};
+
+/// Incomes and their balance written to an Excel workbook.
+#[derive(Debug)]
+pub struct ReportWorkbook {
+    incomes: Vec<Income>,
+    balance: BalanceReport,
+}
+
+/// Formats shared by the sheets.
+struct Formats {
+    header: Format,
+    date: Format,
+    bold: Format,
+}
+
+impl ReportWorkbook {
+    /// Fails when some incomes are not in hryvnias.
+    pub fn new(
+        mut incomes: Vec<Income>,
+        income_taxes: Vec<IncomeTax>,
+    ) -> Result<Self, ForeignIncomesError> {
+        incomes.sort();
+        let balance = BalanceReport::new(incomes.clone(), income_taxes)?;
+        Ok(Self { incomes, balance })
+    }
+
+    pub fn incomes(&self) -> &[Income] {
+        &self.incomes
+    }
+
+    pub fn balance(&self) -> &BalanceReport {
+        &self.balance
+    }
+
+    pub fn write(&self, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let formats = Formats {
+            header: Format::new()
+                .set_bold()
+                .set_border_bottom(FormatBorder::Thin),
+            date: Format::new().set_num_format("yyyy-mm-dd"),
+            bold: Format::new().set_bold(),
+        };
+        let mut workbook = Workbook::new();
+        self.write_incomes(workbook.add_worksheet(), &formats)?;
+        self.write_balance(workbook.add_worksheet(), &formats)?;
+        self.write_quarters(workbook.add_worksheet(), &formats)?;
+        writer.write_all(&workbook.save_to_buffer()?)?;
+        Ok(())
+    }
+
+    /// Incomes followed by totals of every currency. Refunds reduce the totals.
+    fn write_incomes(&self, sheet: &mut Worksheet, formats: &Formats) -> anyhow::Result<()> {
+        sheet.set_name("Incomes")?;
+        write_header(
+            sheet,
+            &[
+                "Payment no",
+                "Date",
+                "Amount",
+                "Currency",
+                "Kind",
+                "Description",
+                "Source ref",
+            ],
+            formats,
+        )?;
+        for (index, income) in self.incomes.iter().enumerate() {
+            let row = index as RowNum + 1;
+            let currency = income.currency();
+            sheet.write_number(row, 0, income.income_no() as f64)?;
+            sheet.write_date_with_format(row, 1, income.date(), &formats.date)?;
+            sheet.write_number_with_format(row, 2, income.amount().amount(), &money(currency))?;
+            sheet.write_string(row, 3, currency.code())?;
+            sheet.write_string(row, 4, income.kind().as_str())?;
+            sheet.write_string(row, 5, income.comment().unwrap_or_default())?;
+            sheet.write_string(row, 6, income.source_ref().unwrap_or_default())?;
+        }
+        let last = self.incomes.len() as RowNum;
+        let mut currencies: Vec<Currency> = vec![];
+        for income in &self.incomes {
+            if !currencies.contains(&income.currency()) {
+                currencies.push(income.currency());
+            }
+        }
+        for (index, currency) in currencies.iter().enumerate() {
+            let row = last + 1 + index as RowNum;
+            let total = self
+                .incomes
+                .iter()
+                .filter(|income| income.currency() == *currency)
+                .map(|income| match income.kind() {
+                    IncomeKind::Refund => -income.amount().amount(),
+                    _ => income.amount().amount(),
+                })
+                .sum::<f64>();
+            let amounts = cell_range(1, 2, last, 2);
+            let currency_cells = cell_range(1, 3, last, 3);
+            let kinds = cell_range(1, 4, last, 4);
+            let code = currency.code();
+            let formula = format!(
+                "=SUMIFS({amounts},{currency_cells},\"{code}\",{kinds},\"<>refund\")\
+                -SUMIFS({amounts},{currency_cells},\"{code}\",{kinds},\"refund\")"
+            );
+            sheet.write_string_with_format(row, 0, "Total", &formats.bold)?;
+            sheet.write_formula_with_format(
+                row,
+                2,
+                Formula::new(formula).set_result(format_result(total)),
+                &money(*currency).set_bold(),
+            )?;
+            sheet.write_string_with_format(row, 3, code, &formats.bold)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+        Ok(())
+    }
+
+    /// Incomes with obligations of every tax and the total of every column.
+    fn write_balance(&self, sheet: &mut Worksheet, formats: &Formats) -> anyhow::Result<()> {
+        sheet.set_name("Balance")?;
+        self.write_amounts_header(sheet, "Date", formats)?;
+        let rows = self.balance.income_obligations();
+        for (index, income) in rows.iter().enumerate() {
+            let row = index as RowNum + 1;
+            sheet.write_date_with_format(row, 0, income.date(), &formats.date)?;
+            write_amounts(sheet, row, income.amount(), income.obligations())?;
+        }
+        self.write_totals(sheet, rows.len() as RowNum, formats)?;
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+        Ok(())
+    }
+
+    /// Totals of every quarter with incomes and the total of every column.
+    fn write_quarters(&self, sheet: &mut Worksheet, formats: &Formats) -> anyhow::Result<()> {
+        sheet.set_name("Quarters")?;
+        self.write_amounts_header(sheet, "Quarter", formats)?;
+        let quarters = self.balance.quarterly_totals();
+        for (index, quarter) in quarters.iter().enumerate() {
+            let row = index as RowNum + 1;
+            sheet.write_string(row, 0, quarter.period())?;
+            write_amounts(sheet, row, quarter.amount(), quarter.obligations())?;
+        }
+        self.write_totals(sheet, quarters.len() as RowNum, formats)?;
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofit();
+        Ok(())
+    }
+
+    fn write_amounts_header(
+        &self,
+        sheet: &mut Worksheet,
+        label: &str,
+        formats: &Formats,
+    ) -> anyhow::Result<()> {
+        let mut titles = vec![label, "Income"];
+        titles.extend(self.balance.taxes().iter().map(String::as_str));
+        titles.push("Taxes");
+        write_header(sheet, &titles, formats)
+    }
+
+    /// Writes the total row summing amount columns of rows after the header.
+    fn write_totals(
+        &self,
+        sheet: &mut Worksheet,
+        last: RowNum,
+        formats: &Formats,
+    ) -> anyhow::Result<()> {
+        let total = self.balance.total();
+        let row = last + 1;
+        sheet.write_string_with_format(row, 0, "Total", &formats.bold)?;
+        let mut results = vec![total.amount()];
+        results.extend(total.obligations().iter().map(|o| o.obligation()));
+        results.push(total.total_obligations());
+        for (index, result) in results.into_iter().enumerate() {
+            let col = index as ColNum + 1;
+            let range = (last > 0).then(|| cell_range(1, col, last, col));
+            write_sum(
+                sheet,
+                row,
+                col,
+                range,
+                result,
+                &money(Currency::UAH).set_bold(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_header(sheet: &mut Worksheet, titles: &[&str], formats: &Formats) -> anyhow::Result<()> {
+    for (col, title) in titles.iter().enumerate() {
+        sheet.write_string_with_format(0, col as ColNum, *title, &formats.header)?;
+    }
+    Ok(())
+}
+
+/// Writes the amount, obligations of every tax and their sum starting from the second column.
+fn write_amounts(
+    sheet: &mut Worksheet,
+    row: RowNum,
+    amount: f64,
+    obligations: &[IncomeTaxObligation],
+) -> anyhow::Result<()> {
+    let format = money(Currency::UAH);
+    sheet.write_number_with_format(row, 1, amount, &format)?;
+    for (index, obligation) in obligations.iter().enumerate() {
+        let col = index as ColNum + 2;
+        sheet.write_number_with_format(row, col, obligation.obligation(), &format)?;
+    }
+    let col = obligations.len() as ColNum + 2;
+    let range = (!obligations.is_empty()).then(|| cell_range(row, 2, row, col - 1));
+    let total = obligations
+        .iter()
+        .map(IncomeTaxObligation::obligation)
+        .sum();
+    write_sum(sheet, row, col, range, total, &format)
+}
+
+/// Writes the sum of the range as a formula with its result. Empty ranges are written as numbers.
+fn write_sum(
+    sheet: &mut Worksheet,
+    row: RowNum,
+    col: ColNum,
+    range: Option<String>,
+    result: f64,
+    format: &Format,
+) -> anyhow::Result<()> {
+    match range {
+        Some(range) => {
+            let formula =
+                Formula::new(format!("=SUM({})", range)).set_result(format_result(result));
+            sheet.write_formula_with_format(row, col, formula, format)?
+        }
+        None => sheet.write_number_with_format(row, col, result, format)?,
+    };
+    Ok(())
+}
+
+/// Number format showing the currency code after the amount.
+fn money(currency: Currency) -> Format {
+    Format::new().set_num_format(format!("#,##0.00 \"{}\"", currency.code()))
+}
+
+fn format_result(value: f64) -> String {
+    format!("{:.2}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use calamine::{Data, DataType, Reader, Xlsx};
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use crate::domain::model::income::Amount;
+    use crate::domain::model::income_tax::{IncomeTaxRate, TaxRate};
+
+    use super::*;
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    fn income(raw: &str, amount: f64) -> Income {
+        Income::from_date(date(raw), Amount::new(amount).unwrap())
+    }
+
+    fn tax(name: &str, rate: f64) -> IncomeTax {
+        IncomeTax::new(
+            Uuid::new_v4(),
+            name.to_string(),
+            vec![IncomeTaxRate::open(
+                date("2020-01-01"),
+                TaxRate::new(rate).unwrap(),
+            )],
+        )
+    }
+
+    fn workbook() -> Xlsx<Cursor<Vec<u8>>> {
+        let incomes = vec![
+            income("2024-05-10", 2000.0).with_no(3),
+            income("2024-01-15", 1000.0)
+                .with_comment("Invoice 1".to_string())
+                .with_no(1),
+            income("2024-02-01", 100.0)
+                .with_kind(IncomeKind::Refund)
+                .with_no(2),
+        ];
+        let taxes = vec![tax("Single tax", 0.05), tax("Military levy", 0.01)];
+        let mut buffer = vec![];
+        ReportWorkbook::new(incomes, taxes)
+            .unwrap()
+            .write(&mut buffer)
+            .unwrap();
+        Xlsx::new(Cursor::new(buffer)).unwrap()
+    }
+
+    fn float(data: Option<&Data>) -> f64 {
+        match data {
+            Some(Data::Float(value)) => *value,
+            other => panic!("expected a number, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_written_sheets() {
+        let mut workbook = workbook();
+
+        assert_eq!(workbook.sheet_names(), ["Incomes", "Balance", "Quarters"]);
+
+        let incomes = workbook.worksheet_range("Incomes").unwrap();
+        assert_eq!(
+            incomes.get((0, 2)),
+            Some(&Data::String("Amount".to_string()))
+        );
+        let first_date = incomes.get((1, 1)).and_then(Data::as_date);
+        assert_eq!(first_date, Some(date("2024-01-15")));
+        assert_eq!(float(incomes.get((1, 2))), 1000.0);
+        assert_eq!(
+            incomes.get((1, 5)),
+            Some(&Data::String("Invoice 1".to_string()))
+        );
+        assert_eq!(
+            incomes.get((2, 4)),
+            Some(&Data::String("refund".to_string()))
+        );
+        // the total follows incomes
+        assert_eq!(float(incomes.get((4, 2))), 2900.0);
+        assert_eq!(incomes.get((4, 3)), Some(&Data::String("UAH".to_string())));
+
+        let balance = workbook.worksheet_range("Balance").unwrap();
+        let header = (0..5)
+            .map(|col| balance.get((0, col)).unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            header,
+            ["Date", "Income", "Single tax", "Military levy", "Taxes"]
+        );
+        assert_eq!(float(balance.get((1, 2))), 50.0);
+        assert_eq!(float(balance.get((1, 4))), 60.0);
+        // refunds reduce the balance
+        assert_eq!(float(balance.get((2, 1))), -100.0);
+        assert_eq!(float(balance.get((2, 4))), -6.0);
+        assert_eq!(
+            balance.get((4, 0)),
+            Some(&Data::String("Total".to_string()))
+        );
+        assert_eq!(float(balance.get((4, 1))), 2900.0);
+        assert_eq!(float(balance.get((4, 4))), 174.0);
+
+        let quarters = workbook.worksheet_range("Quarters").unwrap();
+        assert_eq!(quarters.get((1, 0)).unwrap().to_string(), "Q1 2024");
+        assert_eq!(float(quarters.get((1, 1))), 900.0);
+        assert_eq!(float(quarters.get((2, 1))), 2000.0);
+        assert_eq!(float(quarters.get((3, 3))), 29.0);
+    }
+
+    #[test]
+    fn refuse_foreign_incomes() {
+        let usd = income("2024-02-03", 50.0).with_currency(Currency::USD);
+
+        let error = ReportWorkbook::new(vec![income("2024-01-15", 1000.0), usd.clone()], vec![])
+            .unwrap_err();
+
+        assert_eq!(error.incomes, vec![usd]);
+    }
+
+    #[test]
+    fn write_totals_as_formulas() {
+        let mut workbook = workbook();
+
+        let balance = workbook.worksheet_formula("Balance").unwrap();
+        assert_eq!(
+            balance.get_value((1, 4)).map(String::as_str),
+            Some("SUM(C2:D2)")
+        );
+        assert_eq!(
+            balance.get_value((4, 1)).map(String::as_str),
+            Some("SUM(B2:B4)")
+        );
+        let incomes = workbook.worksheet_formula("Incomes").unwrap();
+        let total = incomes.get_value((4, 2)).unwrap();
+        assert!(total.starts_with("SUMIFS(C2:C4,D2:D4,\"UAH\""), "{}", total);
+        let quarters = workbook.worksheet_formula("Quarters").unwrap();
+        assert_eq!(
+            quarters.get_value((3, 2)).map(String::as_str),
+            Some("SUM(C2:C3)")
+        );
+    }
+}
@@ -0,0 +1,3 @@
+//! Import of CSV statements described by user-defined profiles.
+pub mod profile;
+pub mod rule;
@@ -0,0 +1,356 @@
+//! User-defined CSV statement profiles.
+//!
+//! A profile describes the layout of CSV statements of a bank that has no dedicated reader.
+//! Profiles are defined in the configuration:
+//!
+//! ```toml
+//! [csv_profiles.wise]
+//! delimiter = ","
+//! encoding = "utf-8"
+//! header_row = 1
+//! date_format = "%d-%m-%Y"
+//! decimal_separator = "."
+//! include = "amount > 0"
+//!
+//! [csv_profiles.wise.columns]
+//! date = "Date"
+//! amount = "Amount"
+//! currency = "Currency"
+//! description = "Description"
+//! reference = "TransferWise ID"
+//! ```
+use std::collections::HashMap;
+
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+use crate::rule::{RowValues, Rule};
+
+/// Prefix of profile format names. Keeps profiles apart from built-in formats.
+pub const FORMAT_PREFIX: &str = "profile:";
+
+/// Layout of CSV statements.
+///
+/// - The `delimiter` separates fields. Comma by default.
+/// - The `encoding` is a label like `utf-8` or `windows-1251`.
+/// - The `header_row` is a line number of the header. Preceding lines are skipped.
+/// - The `date_format` is a chrono format of dates with or without time.
+/// - The `decimal_separator` is either `.` or `,`. The other one is a thousands separator.
+/// - The `include` rule picks rows that are incomes. All rows are included without it.
+/// - The `currency` is used when statements have no currency column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvProfile {
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+    #[serde(default = "default_header_row")]
+    pub header_row: usize,
+    pub columns: CsvColumns,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: char,
+    #[serde(default)]
+    pub include: Option<String>,
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// Names of statement columns in the header row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumns {
+    pub date: String,
+    pub amount: String,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+/// Statement reader for CSV files described by the profile.
+#[derive(Debug)]
+pub struct CsvProfileReader {
+    format: String,
+    profile: CsvProfile,
+    encoding: &'static Encoding,
+    rule: Option<Rule>,
+}
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("profile {profile}: unknown encoding {encoding}")]
+    UnknownEncoding { profile: String, encoding: String },
+    #[error("profile {profile}: delimiter must be a single ascii character")]
+    InvalidDelimiter { profile: String },
+    #[error("profile {profile}: decimal separator must be either '.' or ','")]
+    InvalidDecimalSeparator { profile: String },
+    #[error("profile {profile}: header row numbers start with 1")]
+    InvalidHeaderRow { profile: String },
+    #[error("profile {profile}: {source}")]
+    InvalidRule {
+        profile: String,
+        source: crate::rule::RuleError,
+    },
+    #[error("profile {profile}: column {column} not found in the header")]
+    MissingColumn { profile: String, column: String },
+}
+
+struct Row<'a> {
+    header: &'a HashMap<String, usize>,
+    fields: &'a csv::StringRecord,
+    amount: Option<f64>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+fn default_header_row() -> usize {
+    1
+}
+
+fn default_date_format() -> String {
+    "%d.%m.%Y".to_string()
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+impl CsvProfileReader {
+    /// Creates the reader validating the profile.
+    pub fn new(name: &str, profile: CsvProfile) -> Result<Self, ProfileError> {
+        let encoding = Encoding::for_label(profile.encoding.as_bytes()).ok_or_else(|| {
+            ProfileError::UnknownEncoding {
+                profile: name.to_string(),
+                encoding: profile.encoding.clone(),
+            }
+        })?;
+        if !profile.delimiter.is_ascii() {
+            return Err(ProfileError::InvalidDelimiter {
+                profile: name.to_string(),
+            });
+        }
+        if !['.', ','].contains(&profile.decimal_separator) {
+            return Err(ProfileError::InvalidDecimalSeparator {
+                profile: name.to_string(),
+            });
+        }
+        if profile.header_row == 0 {
+            return Err(ProfileError::InvalidHeaderRow {
+                profile: name.to_string(),
+            });
+        }
+        let rule = profile
+            .include
+            .as_deref()
+            .map(Rule::parse)
+            .transpose()
+            .map_err(|source| ProfileError::InvalidRule {
+                profile: name.to_string(),
+                source,
+            })?;
+        Ok(Self {
+            format: format!("{}{}", FORMAT_PREFIX, name),
+            profile,
+            encoding,
+            rule,
+        })
+    }
+
+    /// Name of the profile.
+    pub fn name(&self) -> &str {
+        &self.format[FORMAT_PREFIX.len()..]
+    }
+
+    fn csv_reader<'a>(&self, text: &'a str) -> csv::Reader<&'a [u8]> {
+        // lines before the header are not part of the table
+        let table_start = match self.profile.header_row - 1 {
+            0 => 0,
+            skipped => text
+                .match_indices('\n')
+                .nth(skipped - 1)
+                .map_or(text.len(), |(position, _)| position + 1),
+        };
+        csv::ReaderBuilder::new()
+            .delimiter(self.profile.delimiter as u8)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(&text.as_bytes()[table_start..])
+    }
+
+    fn decode<'a>(&self, content: &'a [u8]) -> std::borrow::Cow<'a, str> {
+        let (text, _, _) = self.encoding.decode(content);
+        text
+    }
+
+    fn header(&self, reader: &mut csv::Reader<&[u8]>) -> anyhow::Result<HashMap<String, usize>> {
+        let header = reader
+            .headers()
+            .context("failed to read the header")?
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.to_string(), index))
+            .collect::<HashMap<_, _>>();
+        for column in self.required_columns() {
+            if !header.contains_key(column) {
+                return Err(ProfileError::MissingColumn {
+                    profile: self.name().to_string(),
+                    column: column.to_string(),
+                }
+                .into());
+            }
+        }
+        Ok(header)
+    }
+
+    fn required_columns(&self) -> Vec<&str> {
+        let columns = &self.profile.columns;
+        [
+            Some(&columns.date),
+            Some(&columns.amount),
+            columns.currency.as_ref(),
+            columns.description.as_ref(),
+            columns.reference.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect()
+    }
+
+    fn parse_amount(&self, raw: &str) -> anyhow::Result<f64> {
+        let thousands_separator = if self.profile.decimal_separator == ',' {
+            '.'
+        } else {
+            ','
+        };
+        let normalized: String = raw
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != thousands_separator)
+            .map(|c| if c == ',' { '.' } else { c })
+            .collect();
+        normalized
+            .parse()
+            .with_context(|| format!("failed to parse amount {}", raw))
+    }
+
+    fn parse_date(&self, raw: &str) -> anyhow::Result<NaiveDateTime> {
+        let format = &self.profile.date_format;
+        NaiveDateTime::parse_from_str(raw, format)
+            .or_else(|_| {
+                NaiveDate::parse_from_str(raw, format).map(|date| date.and_time(NaiveTime::MIN))
+            })
+            .with_context(|| format!("failed to parse date {} as {}", raw, format))
+    }
+
+    fn income(&self, row: &Row) -> anyhow::Result<Income> {
+        let columns = &self.profile.columns;
+        let date = self.parse_date(row.get(&columns.date).unwrap_or_default())?;
+        let amount = row.amount.context("amount not found")?;
+        let mut income = Income::new(date, Amount::new(amount)?);
+        if let Some(description) = columns
+            .description
+            .as_deref()
+            .and_then(|column| row.get(column))
+        {
+            income = income.with_comment(description.to_string());
+        }
+        let currency = columns
+            .currency
+            .as_deref()
+            .and_then(|column| row.get(column))
+            .or(self.profile.currency.as_deref());
+        if let Some(currency) = currency {
+            income = income.with_currency(Currency::new(currency)?);
+        }
+        if let Some(reference) = columns
+            .reference
+            .as_deref()
+            .and_then(|column| row.get(column))
+        {
+            income = income.with_source_ref(reference.to_string());
+        }
+        Ok(income)
+    }
+}
+
+impl RowValues for Row<'_> {
+    fn value(&self, column: &str) -> Option<&str> {
+        self.header
+            .get(column)
+            .and_then(|index| self.fields.get(*index))
+    }
+
+    fn amount(&self) -> Option<f64> {
+        self.amount
+    }
+}
+
+impl Row<'_> {
+    fn get(&self, column: &str) -> Option<&str> {
+        self.value(column).filter(|value| !value.is_empty())
+    }
+}
+
+impl StatementReader for CsvProfileReader {
+    fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// The statement is recognized when its header has all columns of the profile.
+    fn detect(&self, content: &[u8]) -> bool {
+        let text = self.decode(content);
+        let mut reader = self.csv_reader(&text);
+        self.header(&mut reader).is_ok()
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        let text = self.decode(content);
+        let mut reader = self.csv_reader(&text);
+        let header = self.header(&mut reader)?;
+        let amount_column = header[&self.profile.columns.amount];
+        let mut incomes = vec![];
+        for (index, record) in reader.records().enumerate() {
+            let line = self.profile.header_row + index + 1;
+            let record = record.with_context(|| format!("failed to read line {}", line))?;
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            let amount = record
+                .get(amount_column)
+                .filter(|amount| !amount.is_empty())
+                .map(|amount| self.parse_amount(amount))
+                .transpose()
+                .with_context(|| format!("line {}", line))?;
+            let row = Row {
+                header: &header,
+                fields: &record,
+                amount,
+            };
+            if self.rule.as_ref().is_some_and(|rule| !rule.matches(&row)) {
+                continue;
+            }
+            let income = self
+                .income(&row)
+                .with_context(|| format!("line {}", line))?;
+            incomes.push(income);
+        }
+        incomes.sort();
+        Ok(incomes)
+    }
+}
@@ -0,0 +1,269 @@
+//! Row inclusion rules of CSV profiles.
+//!
+//! A rule is one or more comparisons joined with `and`:
+//!
+//! ```text
+//! amount > 0
+//! type == "credit" and amount >= 100
+//! `Тип операції` != "Дебет"
+//! ```
+//!
+//! The left side is a column name. Names with spaces are quoted with backticks.
+//! The special `amount` name refers to the parsed amount of the row.
+//! The right side is a number or a double-quoted string.
+use std::cmp::Ordering;
+
+use thiserror::Error;
+
+/// A parsed inclusion rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    comparisons: Vec<Comparison>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    column: String,
+    operator: Operator,
+    value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Error, PartialEq)]
+#[error("invalid rule {rule:?}: {message}")]
+pub struct RuleError {
+    pub rule: String,
+    pub message: String,
+}
+
+/// Source of column values for rule evaluation.
+pub trait RowValues {
+    /// Provides the raw value of the column.
+    fn value(&self, column: &str) -> Option<&str>;
+
+    /// Provides the parsed amount of the row.
+    fn amount(&self) -> Option<f64>;
+}
+
+const AMOUNT: &str = "amount";
+
+impl Rule {
+    pub fn parse(rule: &str) -> Result<Self, RuleError> {
+        let error = |message: &str| RuleError {
+            rule: rule.to_string(),
+            message: message.to_string(),
+        };
+        let tokens = tokenize(rule).map_err(|message| error(&message))?;
+        let mut comparisons = vec![];
+        let mut tokens = tokens.into_iter();
+        loop {
+            let column = match tokens.next() {
+                Some(Token::Name(name)) => name,
+                _ => return Err(error("expected a column name")),
+            };
+            let operator = match tokens.next() {
+                Some(Token::Operator(operator)) => operator,
+                _ => return Err(error("expected a comparison operator")),
+            };
+            let value = match tokens.next() {
+                Some(Token::Value(value)) => value,
+                _ => return Err(error("expected a number or a quoted string")),
+            };
+            comparisons.push(Comparison {
+                column,
+                operator,
+                value,
+            });
+            match tokens.next() {
+                None => break,
+                Some(Token::And) => continue,
+                Some(_) => return Err(error("expected `and`")),
+            }
+        }
+        Ok(Self { comparisons })
+    }
+
+    /// Checks whether the row satisfies all comparisons.
+    pub fn matches(&self, row: &impl RowValues) -> bool {
+        self.comparisons
+            .iter()
+            .all(|comparison| comparison.matches(row))
+    }
+}
+
+impl Comparison {
+    fn matches(&self, row: &impl RowValues) -> bool {
+        let ordering = match &self.value {
+            Value::Number(expected) => {
+                let actual = if self.column == AMOUNT {
+                    row.amount()
+                } else {
+                    row.value(&self.column)
+                        .and_then(|value| value.trim().replace(',', ".").parse().ok())
+                };
+                actual.and_then(|actual: f64| actual.partial_cmp(expected))
+            }
+            Value::Text(expected) => row
+                .value(&self.column)
+                .map(|actual| actual.trim().cmp(expected.as_str())),
+        };
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self.operator {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Name(String),
+    Operator(Operator),
+    Value(Value),
+    And,
+}
+
+fn tokenize(rule: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = rule.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '`' || c == '"' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some(end) if end == c => break,
+                    Some(other) => text.push(other),
+                    None => return Err(format!("unterminated {}", c)),
+                }
+            }
+            tokens.push(if c == '`' {
+                Token::Name(text)
+            } else {
+                Token::Value(Value::Text(text))
+            });
+        } else if "=!<>".contains(c) {
+            let mut operator = String::new();
+            while let Some(&c) = chars.peek().filter(|c| "=!<>".contains(**c)) {
+                operator.push(c);
+                chars.next();
+            }
+            let operator = match operator.as_str() {
+                "==" | "=" => Operator::Eq,
+                "!=" => Operator::Ne,
+                ">" => Operator::Gt,
+                ">=" => Operator::Ge,
+                "<" => Operator::Lt,
+                "<=" => Operator::Le,
+                other => return Err(format!("unknown operator {}", other)),
+            };
+            tokens.push(Token::Operator(operator));
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars
+                .peek()
+                .filter(|c| !c.is_whitespace() && !"=!<>`\"".contains(**c))
+            {
+                word.push(c);
+                chars.next();
+            }
+            let token = if word == "and" {
+                Token::And
+            } else if let Ok(number) = word.parse::<f64>() {
+                Token::Value(Value::Number(number))
+            } else {
+                Token::Name(word)
+            };
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    struct Row {
+        values: HashMap<&'static str, &'static str>,
+        amount: Option<f64>,
+    }
+
+    impl RowValues for Row {
+        fn value(&self, column: &str) -> Option<&str> {
+            self.values.get(column).copied()
+        }
+
+        fn amount(&self) -> Option<f64> {
+            self.amount
+        }
+    }
+
+    fn row(values: &[(&'static str, &'static str)], amount: f64) -> Row {
+        Row {
+            values: values.iter().copied().collect(),
+            amount: Some(amount),
+        }
+    }
+
+    #[test]
+    fn compare_amounts() {
+        let rule = Rule::parse("amount > 0").unwrap();
+
+        assert!(rule.matches(&row(&[], 10.0)));
+        assert!(!rule.matches(&row(&[], -10.0)));
+        assert!(!rule.matches(&row(&[], 0.0)));
+    }
+
+    #[test]
+    fn compare_text_columns() {
+        let rule = Rule::parse(r#"type == "credit""#).unwrap();
+
+        assert!(rule.matches(&row(&[("type", "credit")], 1.0)));
+        assert!(!rule.matches(&row(&[("type", "debit")], 1.0)));
+        assert!(!rule.matches(&row(&[], 1.0)), "missing columns never match");
+    }
+
+    #[test]
+    fn combine_comparisons() {
+        let rule = Rule::parse(r#"`Тип операції` != "Дебет" and amount >= 100"#).unwrap();
+
+        assert!(rule.matches(&row(&[("Тип операції", "Кредит")], 100.0)));
+        assert!(!rule.matches(&row(&[("Тип операції", "Дебет")], 100.0)));
+        assert!(!rule.matches(&row(&[("Тип операції", "Кредит")], 99.0)));
+    }
+
+    #[test]
+    fn reject_invalid_rules() {
+        assert!(Rule::parse("").is_err());
+        assert!(Rule::parse("amount >").is_err());
+        assert!(Rule::parse("amount => 1").is_err());
+        assert!(Rule::parse(r#"type == "credit"#).is_err());
+        assert!(Rule::parse("amount > 1 or amount < 0").is_err());
+    }
+}
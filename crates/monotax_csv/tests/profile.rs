@@ -0,0 +1,133 @@
+// Test import incomes with user-defined CSV profiles.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_csv::profile::{CsvProfile, CsvProfileReader, ProfileError};
+
+const PROFILES: &str = r#"
+[wise]
+date_format = "%d-%m-%Y"
+include = "amount > 0"
+
+[wise.columns]
+date = "Date"
+amount = "Amount"
+currency = "Currency"
+description = "Description"
+reference = "TransferWise ID"
+
+[local]
+delimiter = ";"
+encoding = "windows-1251"
+header_row = 2
+date_format = "%d.%m.%Y %H:%M"
+decimal_separator = ","
+include = 'Тип == "Кредит"'
+currency = "UAH"
+
+[local.columns]
+date = "Дата"
+amount = "Сума"
+description = "Опис"
+reference = "Документ"
+"#;
+
+fn profiles() -> HashMap<String, CsvProfile> {
+    toml::from_str(PROFILES).unwrap()
+}
+
+fn reader(name: &str) -> CsvProfileReader {
+    CsvProfileReader::new(name, profiles().remove(name).unwrap()).unwrap()
+}
+
+fn income(date: &str, amount: f64) -> Income {
+    let income_date = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+    Income::new(income_date, Amount::new(amount).unwrap())
+}
+
+fn read_file(name: &str) -> Vec<u8> {
+    std::fs::read(format!("tests/test_files/{}", name)).unwrap()
+}
+
+#[test]
+fn import_rows_matching_amount_rule() {
+    let incomes = reader("wise").read_incomes(&read_file("wise.csv")).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![
+            income("2024-03-05 00:00", 1500.0),
+            income("2024-03-21 00:00", 730.25)
+        ]
+    );
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].source_ref(), Some("TRANSFER-1001"));
+    assert_eq!(incomes[0].comment(), Some("Received money from Acme Corp"));
+}
+
+#[test]
+fn import_rows_matching_column_rule() {
+    let incomes = reader("local")
+        .read_incomes(&read_file("bank.csv"))
+        .unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![
+            income("2024-03-05 10:30", 1500.0),
+            income("2024-03-21 09:15", 730.25)
+        ]
+    );
+    assert_eq!(incomes[0].currency(), Currency::UAH);
+    assert_eq!(incomes[0].source_ref(), Some("D-1"));
+    assert_eq!(incomes[0].comment(), Some("Оплата за послуги"));
+}
+
+#[test]
+fn detect_statements_by_profile_columns() {
+    assert!(reader("wise").detect(&read_file("wise.csv")));
+    assert!(!reader("wise").detect(&read_file("bank.csv")));
+    assert!(reader("local").detect(&read_file("bank.csv")));
+    assert_eq!(reader("local").format(), "profile:local");
+}
+
+#[test]
+fn reject_invalid_profiles() {
+    let mut profile = profiles().remove("wise").unwrap();
+    profile.include = Some("amount >".to_string());
+    assert!(matches!(
+        CsvProfileReader::new("wise", profile.clone()),
+        Err(ProfileError::InvalidRule { .. })
+    ));
+
+    profile.include = None;
+    profile.encoding = "klingon".to_string();
+    assert!(matches!(
+        CsvProfileReader::new("wise", profile.clone()),
+        Err(ProfileError::UnknownEncoding { .. })
+    ));
+
+    profile.encoding = "utf-8".to_string();
+    profile.decimal_separator = ';';
+    assert!(matches!(
+        CsvProfileReader::new("wise", profile),
+        Err(ProfileError::InvalidDecimalSeparator { .. })
+    ));
+}
+
+#[test]
+fn report_missing_columns() {
+    let error = reader("wise")
+        .read_incomes(&read_file("bank.csv"))
+        .unwrap_err();
+
+    assert!(
+        error.to_string().contains("column Date not found"),
+        "unexpected error {}",
+        error
+    );
+}
@@ -9,13 +9,19 @@ use csv::StringRecord;
 use encoding_rs::WINDOWS_1251;
 use encoding_rs_rw::DecodingReader;
 
+use monotax_core::domain::filter::income::IncomeCriteria;
 use monotax_core::domain::model::income::Amount;
 use monotax_core::domain::Income;
 use monotax_core::filter::IncomePredicate;
+use monotax_core::integration::statement::StatementReader;
 
 const DATE_COLUMN: usize = 4;
 const AMOUNT_COLUMN: usize = 14;
 const DESCRIPTION_COLUMN: usize = 15;
+const DATE_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
+
+/// Statement reader for DBOsoft-compatible CSV files.
+pub struct DboStatementReader;
 
 /// Reads incomes from DBOsoft-compatible CSV files.
 /// The filter allows to pick incomes for particular date range.
@@ -23,11 +29,7 @@ pub fn read_incomes<R>(reader: R, filter: impl IncomePredicate) -> anyhow::Resul
 where
     R: Read,
 {
-    let mut reader = DecodingReader::new(BufReader::new(reader), WINDOWS_1251.new_decoder());
-    let mut csv_reader = csv::ReaderBuilder::new()
-        .delimiter(b';')
-        .flexible(true)
-        .from_reader(&mut reader);
+    let mut csv_reader = csv_reader(reader);
     let mut incomes = Vec::new();
     for result in csv_reader.records() {
         let record = result.context("failed to read record")?;
@@ -40,6 +42,17 @@ where
     Ok(incomes)
 }
 
+fn csv_reader<R>(reader: R) -> csv::Reader<DecodingReader<BufReader<R>>>
+where
+    R: Read,
+{
+    let reader = DecodingReader::new(BufReader::new(reader), WINDOWS_1251.new_decoder());
+    csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .flexible(true)
+        .from_reader(reader)
+}
+
 fn income_from_csv(record: &StringRecord) -> anyhow::Result<Income> {
     let date = record
         .get(DATE_COLUMN)
@@ -50,9 +63,30 @@ fn income_from_csv(record: &StringRecord) -> anyhow::Result<Income> {
     let comment = record
         .get(DESCRIPTION_COLUMN)
         .ok_or_else(|| anyhow::anyhow!("comment not found"))?;
-    let date =
-        NaiveDateTime::parse_from_str(date, "%d.%m.%Y %H:%M:%S").context("failed to parse date")?;
+    let date = NaiveDateTime::parse_from_str(date, DATE_FORMAT).context("failed to parse date")?;
     let amount = amount.parse().context("failed to parse amount")?;
     let amount = Amount::new(amount)?;
     Ok(Income::new(date, amount).with_comment(comment.to_string()))
 }
+
+impl StatementReader for DboStatementReader {
+    fn format(&self) -> &str {
+        "dbo"
+    }
+
+    /// The statement is recognized by the date in the expected column of the first record.
+    fn detect(&self, content: &[u8]) -> bool {
+        let mut csv_reader = csv_reader(content);
+        let Some(Ok(record)) = csv_reader.records().next() else {
+            return false;
+        };
+        record.len() > DESCRIPTION_COLUMN
+            && record
+                .get(DATE_COLUMN)
+                .is_some_and(|date| NaiveDateTime::parse_from_str(date, DATE_FORMAT).is_ok())
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        read_incomes(content, IncomeCriteria::new(&[]))
+    }
+}
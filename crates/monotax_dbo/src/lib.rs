@@ -1,2 +1,2 @@
 pub mod dbo;
-
+pub mod payment;
@@ -0,0 +1,57 @@
+//! Export format for payment orders imported into DBOsoft banking.
+//!
+//! Orders are rows of a semicolon separated file with the header, encoded in windows-1251
+//! like statements of the bank. Amounts have two decimals separated by a dot.
+use std::io::Write;
+
+use anyhow::Context;
+use encoding_rs::WINDOWS_1251;
+
+use monotax_core::integration::payment::PaymentOrder;
+
+const HEADER: [&str; 11] = [
+    "Номер документа",
+    "Дата документа",
+    "Сума",
+    "Валюта",
+    "Рахунок платника",
+    "Код платника",
+    "Назва платника",
+    "Рахунок отримувача",
+    "Код отримувача",
+    "Назва отримувача",
+    "Призначення платежу",
+];
+const CURRENCY: &str = "UAH";
+const DATE_FORMAT: &str = "%d.%m.%Y";
+
+/// Writes payment orders as a DBOsoft import file.
+pub fn write_payment_orders(orders: &[PaymentOrder], mut writer: impl Write) -> anyhow::Result<()> {
+    let mut csv_writer = csv::WriterBuilder::new()
+        .delimiter(b';')
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(vec![]);
+    csv_writer.write_record(HEADER)?;
+    for order in orders {
+        csv_writer.write_record([
+            order.number.to_string(),
+            order.date.format(DATE_FORMAT).to_string(),
+            order.formatted_amount(),
+            CURRENCY.to_string(),
+            order.payer.iban.clone(),
+            order.payer.code.clone(),
+            order.payer.name.clone(),
+            order.recipient.iban.clone(),
+            order.recipient.code.clone(),
+            order.recipient.name.clone(),
+            order.purpose.clone(),
+        ])?;
+    }
+    let content = csv_writer
+        .into_inner()
+        .context("failed to write payment orders")?;
+    let content = String::from_utf8(content)?;
+    let (encoded, _, _) = WINDOWS_1251.encode(&content);
+    writer.write_all(&encoded)?;
+    Ok(())
+}
@@ -7,7 +7,8 @@ use monotax_core::domain::filter::income::{IncomeCriteria, IncomeCriterion, Quar
 use monotax_core::domain::model::income::Amount;
 use monotax_core::domain::Income;
 use monotax_core::domain::Quarter;
-use monotax_dbo::dbo;
+use monotax_core::integration::statement::StatementReader;
+use monotax_dbo::dbo::{self, DboStatementReader};
 
 fn income(date: &str, amount: f64) -> Income {
     let income_date = NaiveDateTime::parse_from_str(date, "%d.%m.%Y %H:%M:%S").unwrap();
@@ -49,3 +50,21 @@ fn import_one_quarter_from_csv() {
         ]
     );
 }
+
+#[test]
+fn detect_dbo_statement() {
+    let content = std::fs::read("tests/test_files/balance.csv").unwrap();
+
+    assert!(DboStatementReader.detect(&content));
+    assert!(!DboStatementReader.detect(b"date,amount\n2024-01-01,100.00\n"));
+    assert!(!DboStatementReader.detect(b""));
+}
+
+#[test]
+fn read_statement_with_reader() {
+    let content = std::fs::read("tests/test_files/balance.csv").unwrap();
+
+    let incomes = DboStatementReader.read_incomes(&content).unwrap();
+
+    assert_eq!(4, incomes.len());
+}
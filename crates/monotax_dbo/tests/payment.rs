@@ -0,0 +1,60 @@
+// Test export of payment orders to the DBOsoft import format.
+
+use chrono::NaiveDate;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::integration::payment::{PaymentOrder, PaymentParty};
+use monotax_dbo::payment;
+
+fn order(number: u32, amount: f64, iban: &str, purpose: &str) -> PaymentOrder {
+    PaymentOrder {
+        number,
+        date: NaiveDate::from_ymd_opt(2024, 7, 19).unwrap(),
+        amount: Amount::new(amount).unwrap(),
+        payer: PaymentParty {
+            name: "Шевченко Тарас Григорович".to_string(),
+            code: "1234567890".to_string(),
+            iban: "UA213223130000026007233566001".to_string(),
+        },
+        recipient: PaymentParty {
+            name: "ГУК у м.Києві/Шевченк.р-н/18050400".to_string(),
+            code: "37993783".to_string(),
+            iban: iban.to_string(),
+        },
+        purpose: purpose.to_string(),
+    }
+}
+
+#[test]
+fn write_payment_orders() {
+    let orders = vec![
+        order(
+            1,
+            1250.03,
+            "UA258999980334179879000026001",
+            "*;101;1234567890;єдиний податок за 2 квартал 2024 року",
+        ),
+        order(
+            2,
+            250.0,
+            "UA468999980314151939000026001",
+            "*;101;1234567890;військовий збір за 2 квартал 2024 року",
+        ),
+    ];
+    let mut buffer = vec![];
+
+    payment::write_payment_orders(&orders, &mut buffer).unwrap();
+
+    let expected = std::fs::read("tests/test_files/payment_orders.csv").unwrap();
+    assert_eq!(buffer, expected);
+}
+
+#[test]
+fn write_header_without_orders() {
+    let mut buffer = vec![];
+
+    payment::write_payment_orders(&[], &mut buffer).unwrap();
+
+    let (content, _, _) = encoding_rs::WINDOWS_1251.decode(&buffer);
+    assert!(content.starts_with("Номер документа;Дата документа;Сума;"));
+    assert_eq!(content.lines().count(), 1);
+}
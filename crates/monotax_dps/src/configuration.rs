@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_SINGLE_TAX: &str = "ЄП";
+const DEFAULT_PAYMENT_CODE: &str = "101";
+
+/// Taxpayer details for DPS reports.
+///
+/// - The `tin` is the taxpayer identification number (РНОКПП).
+/// - The `name` is the full name of the taxpayer. It also signs reports.
+/// - The `address` is the registered address.
+/// - The `phone` and `email` are optional contacts.
+/// - The `region` and `district` are codes of the tax office reports are filed to.
+/// - The `tax_office` is the name of the tax office.
+/// - The `single_tax` and `military_levy` are names of income taxes in the database.
+///   Reports have no military levy lines if it's not set.
+/// - The `account` is the IBAN taxes are paid from.
+/// - The `recipients` are requisites of budget accounts by names of income taxes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpsConfiguration {
+    #[serde(default)]
+    pub tin: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub region: u8,
+    #[serde(default)]
+    pub district: u8,
+    #[serde(default)]
+    pub tax_office: String,
+    #[serde(default = "default_single_tax")]
+    pub single_tax: String,
+    #[serde(default)]
+    pub military_levy: Option<String>,
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    pub recipients: BTreeMap<String, TaxRecipient>,
+}
+
+/// Budget account receiving payments of an income tax.
+///
+/// - The `name` and the `edrpou` code are of the treasury office holding the account.
+/// - The `iban` is the budget account of the tax office of the taxpayer.
+/// - The `payment_code` is the code of the payment kind, `101` is the payment of the tax.
+/// - The `purpose` describes the tax in purposes of payments, e.g. `єдиний податок`.
+///   The name of the tax is used if it's not set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRecipient {
+    pub name: String,
+    pub edrpou: String,
+    pub iban: String,
+    #[serde(default = "default_payment_code")]
+    pub payment_code: String,
+    #[serde(default)]
+    pub purpose: String,
+}
+
+fn default_single_tax() -> String {
+    DEFAULT_SINGLE_TAX.to_string()
+}
+
+fn default_payment_code() -> String {
+    DEFAULT_PAYMENT_CODE.to_string()
+}
+
+impl Default for DpsConfiguration {
+    fn default() -> Self {
+        Self {
+            tin: String::new(),
+            name: String::new(),
+            address: String::new(),
+            phone: None,
+            email: None,
+            region: 0,
+            district: 0,
+            tax_office: String::new(),
+            single_tax: default_single_tax(),
+            military_levy: None,
+            account: String::new(),
+            recipients: BTreeMap::new(),
+        }
+    }
+}
+
+impl DpsConfiguration {
+    /// Code of the tax office combining the region and the district.
+    pub fn tax_office_code(&self) -> u16 {
+        self.region as u16 * 100 + self.district as u16
+    }
+}
@@ -0,0 +1,303 @@
+//! Declaration of the single tax payer of the third group, form F0103309.
+//!
+//! The declaration is cumulative: it has the income from the start of the year to the end of
+//! the declared quarter, the tax due on it, the tax declared for previous periods and the
+//! difference to pay. The figures come from the [`DeclarationReport`].
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use thiserror::Error;
+
+use monotax_core::domain::model::income::{Amount, ForeignIncomesError};
+use monotax_core::domain::Quarter;
+use monotax_core::report::declaration::{DeclarationReport, DeclaredTax};
+
+use crate::configuration::DpsConfiguration;
+
+/// Code of the form with its version.
+pub const FORM: &str = "F0103309";
+const DOC: &str = "F01";
+const DOC_SUB: &str = "033";
+const DOC_VERSION: u8 = 9;
+const SOFTWARE: &str = "monotax";
+const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// Kind of the declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum DeclarationKind {
+    /// Regular declaration of the period.
+    #[default]
+    Regular,
+    /// Regular declaration replacing the one filed for the period before the deadline.
+    New,
+    /// Correction of the declaration filed for the period.
+    Correction,
+}
+
+#[derive(Debug, Error)]
+pub enum DeclarationError {
+    #[error("taxpayer {0} is not configured. set it in the [dps] section")]
+    MissingDetail(&'static str),
+    #[error("taxpayer identification number {0} must have 8 to 10 digits")]
+    InvalidTin(String),
+    #[error("income tax {0} is not found")]
+    TaxNotFound(String),
+    #[error(transparent)]
+    ForeignIncomes(#[from] ForeignIncomesError),
+}
+
+/// Declaration ready to be written as DPS XML.
+/// The XML is not validated against the official schema of the form.
+#[derive(Debug)]
+pub struct SingleTaxDeclaration<'a> {
+    report: &'a DeclarationReport,
+    config: &'a DpsConfiguration,
+    single_tax: &'a DeclaredTax,
+    military_levy: Option<&'a DeclaredTax>,
+    kind: DeclarationKind,
+    filled: NaiveDate,
+}
+
+impl<'a> SingleTaxDeclaration<'a> {
+    /// Creates the declaration filled on the date.
+    ///
+    /// Fails if taxpayer details are missing, configured taxes are not in the report
+    /// or some incomes of the report are not in hryvnias.
+    pub fn new(
+        report: &'a DeclarationReport,
+        config: &'a DpsConfiguration,
+        filled: NaiveDate,
+    ) -> Result<Self, DeclarationError> {
+        report.ensure_uah()?;
+        let required = [
+            ("tin", &config.tin),
+            ("name", &config.name),
+            ("address", &config.address),
+            ("tax_office", &config.tax_office),
+        ];
+        for (detail, value) in required {
+            if value.trim().is_empty() {
+                return Err(DeclarationError::MissingDetail(detail));
+            }
+        }
+        if config.region == 0 {
+            return Err(DeclarationError::MissingDetail("region"));
+        }
+        let tin_length = config.tin.len();
+        if !(8..=10).contains(&tin_length) || !config.tin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(DeclarationError::InvalidTin(config.tin.clone()));
+        }
+        let find_tax = |name: &str| {
+            report
+                .taxes()
+                .iter()
+                .find(|tax| tax.name() == name)
+                .ok_or_else(|| DeclarationError::TaxNotFound(name.to_string()))
+        };
+        let single_tax = find_tax(&config.single_tax)?;
+        let military_levy = config.military_levy.as_deref().map(find_tax).transpose()?;
+        Ok(Self {
+            report,
+            config,
+            single_tax,
+            military_levy,
+            kind: DeclarationKind::default(),
+            filled,
+        })
+    }
+
+    pub fn with_kind(self, kind: DeclarationKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    /// Name of the file expected by the DPS electronic cabinet.
+    pub fn file_name(&self) -> String {
+        let (period_type, period_month, _) = period(self.report.quarter());
+        format!(
+            "{:02}{:02}{:0>10}{}{}{:02}{}{:02}{:07}{}{:02}{}{:04}.xml",
+            self.config.region,
+            self.config.district,
+            self.config.tin,
+            DOC,
+            DOC_SUB,
+            DOC_VERSION,
+            self.kind.state(),
+            0,
+            1,
+            period_type,
+            period_month,
+            self.report.year(),
+            self.config.tax_office_code(),
+        )
+    }
+
+    /// Writes the declaration as XML. The text declares the windows-1251 encoding,
+    /// so it must be saved with [`SingleTaxDeclaration::to_bytes`].
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"windows-1251\"?>\n");
+        xml.push_str(&format!(
+            "<DECLAR xmlns:xsi=\"{}\" xsi:noNamespaceSchemaLocation=\"{}.xsd\">\n",
+            XSI_NAMESPACE, FORM
+        ));
+        xml.push_str("  <DECLARHEAD>\n");
+        for (tag, value) in self.head() {
+            match value {
+                Some(value) => push_element(&mut xml, tag, &value),
+                None => xml.push_str(&format!("    <{} xsi:nil=\"true\"/>\n", tag)),
+            }
+        }
+        xml.push_str("  </DECLARHEAD>\n");
+        xml.push_str("  <DECLARBODY>\n");
+        for (tag, value) in self.body() {
+            push_element(&mut xml, tag, &value);
+        }
+        xml.push_str("  </DECLARBODY>\n");
+        xml.push_str("</DECLAR>\n");
+        xml
+    }
+
+    /// Encodes the declaration in windows-1251.
+    /// Characters missing from the encoding are written as character references.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let xml = self.to_xml();
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(&xml);
+        bytes.into_owned()
+    }
+
+    fn head(&self) -> Vec<(&'static str, Option<String>)> {
+        let (period_type, period_month, _) = period(self.report.quarter());
+        vec![
+            ("TIN", Some(self.config.tin.clone())),
+            ("C_DOC", Some(DOC.to_string())),
+            ("C_DOC_SUB", Some(DOC_SUB.to_string())),
+            ("C_DOC_VER", Some(DOC_VERSION.to_string())),
+            ("C_DOC_TYPE", Some("0".to_string())),
+            ("C_DOC_CNT", Some("1".to_string())),
+            ("C_REG", Some(self.config.region.to_string())),
+            ("C_RAJ", Some(self.config.district.to_string())),
+            ("PERIOD_MONTH", Some(period_month.to_string())),
+            ("PERIOD_TYPE", Some(period_type.to_string())),
+            ("PERIOD_YEAR", Some(self.report.year().to_string())),
+            (
+                "C_STI_ORIG",
+                Some(self.config.tax_office_code().to_string()),
+            ),
+            ("C_DOC_STAN", Some(self.kind.state().to_string())),
+            ("LINKED_DOCS", None),
+            ("D_FILL", Some(format_date(self.filled))),
+            ("SOFTWARE", Some(SOFTWARE.to_string())),
+        ]
+    }
+
+    fn body(&self) -> Vec<(&'static str, String)> {
+        let (_, _, period_tag) = period(self.report.quarter());
+        let checked = || "1".to_string();
+        let mut fields = vec![
+            (self.kind.tag(), checked()),
+            ("HZY", self.report.year().to_string()),
+            (period_tag, checked()),
+            ("HNAME", self.config.name.clone()),
+            ("HTIN", self.config.tin.clone()),
+            ("HLOC", self.config.address.clone()),
+        ];
+        if let Some(phone) = &self.config.phone {
+            fields.push(("HTEL", phone.clone()));
+        }
+        if let Some(email) = &self.config.email {
+            fields.push(("HEMAIL", email.clone()));
+        }
+        fields.push(("HSTI", self.config.tax_office.clone()));
+        fields.push(("R01G3", format_amount(self.report.ytd_income())));
+        fields.extend(tax_fields(["R02G3", "R03G3", "R04G3"], self.single_tax));
+        if let Some(military_levy) = self.military_levy {
+            fields.extend(tax_fields(["R05G3", "R06G3", "R07G3"], military_levy));
+        }
+        fields.push(("HFILL", format_date(self.filled)));
+        fields.push(("HBOS", self.config.name.clone()));
+        fields
+    }
+}
+
+impl DeclarationKind {
+    /// Value of the `C_DOC_STAN` field.
+    fn state(&self) -> u8 {
+        match self {
+            DeclarationKind::Regular => 1,
+            DeclarationKind::New => 2,
+            DeclarationKind::Correction => 3,
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            DeclarationKind::Regular => "HZ",
+            DeclarationKind::New => "HZN",
+            DeclarationKind::Correction => "HZU",
+        }
+    }
+}
+
+/// Type, last month and body field of the cumulative period ending with the quarter.
+fn period(quarter: Quarter) -> (u8, u8, &'static str) {
+    match quarter {
+        Quarter::Q1 => (2, 3, "H1KV"),
+        Quarter::Q2 => (3, 6, "HHY"),
+        Quarter::Q3 => (4, 9, "H3KV"),
+        Quarter::Q4 => (5, 12, "HY"),
+    }
+}
+
+/// Lines are rounded before subtraction, so the amount to pay matches the declared lines.
+fn tax_fields(tags: [&'static str; 3], tax: &DeclaredTax) -> [(&'static str, String); 3] {
+    let due = kopecks(tax.due());
+    let declared = kopecks(tax.declared());
+    let to_pay = (due - declared).max(0);
+    [
+        (tags[0], format_kopecks(due)),
+        (tags[1], format_kopecks(declared)),
+        (tags[2], format_kopecks(to_pay)),
+    ]
+}
+
+fn push_element(xml: &mut String, tag: &str, value: &str) {
+    xml.push_str(&format!("    <{}>{}</{}>\n", tag, escape(value), tag));
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Rounds the amount to kopecks, halves are rounded up.
+fn kopecks(amount: Amount) -> i64 {
+    (amount.amount() * 100.0).round() as i64
+}
+
+fn format_kopecks(kopecks: i64) -> String {
+    format!("{}.{:02}", kopecks / 100, kopecks % 100)
+}
+
+fn format_amount(amount: Amount) -> String {
+    format_kopecks(kopecks(amount))
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%d%m%Y").to_string()
+}
+
+impl Display for DeclarationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DeclarationKind::Regular => "regular",
+            DeclarationKind::New => "new regular",
+            DeclarationKind::Correction => "correction",
+        };
+        write!(f, "{}", name)
+    }
+}
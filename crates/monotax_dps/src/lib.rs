@@ -0,0 +1,7 @@
+//! Reports for the State Tax Service of Ukraine (DPS).
+//!
+//! The electronic cabinet accepts reports as XML files of official schemas.
+//! Files are encoded in windows-1251 and named after the taxpayer, the form and the period.
+pub mod configuration;
+pub mod declaration;
+pub mod payment;
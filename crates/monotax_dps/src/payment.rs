@@ -0,0 +1,111 @@
+//! Payment orders of taxes declared for the quarter.
+//!
+//! Taxes are paid to budget accounts of the tax office. The purpose of the payment starts
+//! with the code of the payment kind and the tax number of the payer separated by semicolons,
+//! e.g. `*;101;1234567890;єдиний податок за 2 квартал 2024 року`, so the treasury can
+//! credit the payment to the taxpayer.
+//!
+//! Taxes of a quarter are paid after the quarter ends, so payments of a tax made after the
+//! quarter and up to the date of the orders reduce the amount left to pay.
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::payment::TaxPayment;
+use monotax_core::integration::payment::{PaymentOrder, PaymentParty};
+use monotax_core::report::declaration::{DeclarationReport, DeclaredTax};
+
+use crate::configuration::{DpsConfiguration, TaxRecipient};
+
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    #[error("taxpayer {0} is not configured. set it in the [dps] section")]
+    MissingDetail(&'static str),
+    #[error("recipient of {0} is not configured. set it in the [dps.recipients] section")]
+    MissingRecipient(String),
+}
+
+/// Creates orders paying taxes of the report that are left to pay on the date.
+/// Taxes fully paid by the payments are skipped.
+///
+/// Fails if the payer details or recipients of the taxes to pay are not configured.
+pub fn payment_orders(
+    report: &DeclarationReport,
+    payments: &[TaxPayment],
+    config: &DpsConfiguration,
+    date: NaiveDate,
+) -> Result<Vec<PaymentOrder>, PaymentError> {
+    let quarter_end = report.quarter().last_day(report.year());
+    let taxes = report
+        .taxes()
+        .iter()
+        .map(|tax| {
+            let paid = payments
+                .iter()
+                .filter(|payment| payment.tax() == Some(tax.name()))
+                .filter(|payment| payment.date() > quarter_end && payment.date() <= date)
+                .map(|payment| payment.amount().amount())
+                .sum::<f64>();
+            (tax, tax.to_pay().amount() - paid)
+        })
+        .filter(|(_, left)| *left >= 0.005)
+        .collect::<Vec<_>>();
+    if taxes.is_empty() {
+        return Ok(vec![]);
+    }
+    let required = [
+        ("tin", &config.tin),
+        ("name", &config.name),
+        ("account", &config.account),
+    ];
+    for (detail, value) in required {
+        if value.trim().is_empty() {
+            return Err(PaymentError::MissingDetail(detail));
+        }
+    }
+    let payer = PaymentParty {
+        name: config.name.clone(),
+        code: config.tin.clone(),
+        iban: config.account.clone(),
+    };
+    let mut orders = vec![];
+    for (index, (tax, left)) in taxes.into_iter().enumerate() {
+        let recipient = config
+            .recipients
+            .get(tax.name())
+            .ok_or_else(|| PaymentError::MissingRecipient(tax.name().to_string()))?;
+        orders.push(PaymentOrder {
+            number: index as u32 + 1,
+            date,
+            amount: Amount::new(left).unwrap(),
+            payer: payer.clone(),
+            recipient: PaymentParty {
+                name: recipient.name.clone(),
+                code: recipient.edrpou.clone(),
+                iban: recipient.iban.clone(),
+            },
+            purpose: purpose(report, tax, recipient, &config.tin),
+        });
+    }
+    Ok(orders)
+}
+
+fn purpose(
+    report: &DeclarationReport,
+    tax: &DeclaredTax,
+    recipient: &TaxRecipient,
+    tin: &str,
+) -> String {
+    let description = match recipient.purpose.trim() {
+        "" => tax.name(),
+        purpose => purpose,
+    };
+    format!(
+        "*;{};{};{} за {} квартал {} року",
+        recipient.payment_code,
+        tin,
+        description,
+        report.quarter().index(),
+        report.year()
+    )
+}
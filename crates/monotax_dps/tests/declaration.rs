@@ -0,0 +1,142 @@
+use std::fs;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::income_tax::{IncomeTax, IncomeTaxRate, TaxRate};
+use monotax_core::domain::{Currency, Income, Quarter};
+use monotax_core::report::declaration::DeclarationReport;
+use monotax_dps::configuration::DpsConfiguration;
+use monotax_dps::declaration::{DeclarationError, DeclarationKind, SingleTaxDeclaration};
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+fn tax(name: &str, rate: f64) -> IncomeTax {
+    let rate = IncomeTaxRate::open(date("2020-01-01"), TaxRate::new(rate).unwrap());
+    IncomeTax::new(Uuid::new_v4(), name.to_string(), vec![rate])
+}
+
+fn report() -> DeclarationReport {
+    let income =
+        |raw: &str, amount: f64| Income::from_date(date(raw), Amount::new(amount).unwrap());
+    DeclarationReport::new(
+        2024,
+        Quarter::Q2,
+        vec![income("2024-02-10", 10000.0), income("2024-05-15", 25000.5)],
+        vec![tax("ЄП", 0.05), tax("ВЗ", 0.01)],
+    )
+}
+
+fn config() -> DpsConfiguration {
+    DpsConfiguration {
+        tin: "1234567890".to_string(),
+        name: "Шевченко Тарас Григорович".to_string(),
+        address: "м. Київ, вул. Хрещатик, 1, кв. 2".to_string(),
+        phone: Some("+380441234567".to_string()),
+        email: None,
+        region: 26,
+        district: 59,
+        tax_office: "ГУ ДПС у м. Києві, ДПІ у Шевченківському районі".to_string(),
+        military_levy: Some("ВЗ".to_string()),
+        ..DpsConfiguration::default()
+    }
+}
+
+#[test]
+fn write_declaration() {
+    let report = report();
+    let config = config();
+    let declaration = SingleTaxDeclaration::new(&report, &config, date("2024-07-15")).unwrap();
+
+    let expected = fs::read_to_string("tests/test_files/declaration.xml").unwrap();
+    assert_eq!(declaration.to_xml(), expected);
+    assert_eq!(
+        declaration.file_name(),
+        "26591234567890F0103309100000000130620242659.xml"
+    );
+}
+
+#[test]
+fn encode_in_windows_1251() {
+    let report = report();
+    let config = config();
+    let declaration = SingleTaxDeclaration::new(&report, &config, date("2024-07-15")).unwrap();
+
+    let bytes = declaration.to_bytes();
+
+    let (text, _, had_errors) = encoding_rs::WINDOWS_1251.decode(&bytes);
+    assert!(!had_errors);
+    assert_eq!(text, declaration.to_xml());
+}
+
+#[test]
+fn write_correction_without_military_levy() {
+    let report = report();
+    let config = DpsConfiguration {
+        military_levy: None,
+        ..config()
+    };
+    let declaration = SingleTaxDeclaration::new(&report, &config, date("2024-08-01"))
+        .unwrap()
+        .with_kind(DeclarationKind::Correction);
+
+    let xml = declaration.to_xml();
+
+    assert!(xml.contains("<HZU>1</HZU>"));
+    assert!(xml.contains("<C_DOC_STAN>3</C_DOC_STAN>"));
+    assert!(!xml.contains("R05G3"));
+}
+
+#[test]
+fn reject_incomplete_taxpayer_details() {
+    let report = report();
+    let missing_address = DpsConfiguration {
+        address: String::new(),
+        ..config()
+    };
+    let invalid_tin = DpsConfiguration {
+        tin: "12-34".to_string(),
+        ..config()
+    };
+    let unknown_tax = DpsConfiguration {
+        single_tax: "Single tax".to_string(),
+        ..config()
+    };
+    let filled = date("2024-07-15");
+
+    assert!(matches!(
+        SingleTaxDeclaration::new(&report, &missing_address, filled),
+        Err(DeclarationError::MissingDetail("address"))
+    ));
+    assert!(matches!(
+        SingleTaxDeclaration::new(&report, &invalid_tin, filled),
+        Err(DeclarationError::InvalidTin(_))
+    ));
+    assert!(matches!(
+        SingleTaxDeclaration::new(&report, &unknown_tax, filled),
+        Err(DeclarationError::TaxNotFound(_))
+    ));
+}
+
+#[test]
+fn reject_foreign_incomes() {
+    let income = Income::from_date(date("2024-05-20"), Amount::new(500.0).unwrap())
+        .with_currency(Currency::USD);
+    let report = DeclarationReport::new(
+        2024,
+        Quarter::Q2,
+        vec![income],
+        vec![tax("ЄП", 0.05), tax("ВЗ", 0.01)],
+    );
+    let config = config();
+
+    let result = SingleTaxDeclaration::new(&report, &config, date("2024-07-15"));
+
+    let Err(DeclarationError::ForeignIncomes(error)) = result else {
+        panic!("expected foreign incomes to be rejected");
+    };
+    assert_eq!(error.incomes.len(), 1);
+}
@@ -0,0 +1,153 @@
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::income_tax::{IncomeTax, IncomeTaxRate, TaxRate};
+use monotax_core::domain::model::payment::TaxPayment;
+use monotax_core::domain::{Income, Quarter};
+use monotax_core::report::declaration::DeclarationReport;
+use monotax_dps::configuration::{DpsConfiguration, TaxRecipient};
+use monotax_dps::payment::{self, PaymentError};
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+fn tax(name: &str, rate: f64) -> IncomeTax {
+    let rate = IncomeTaxRate::open(date("2020-01-01"), TaxRate::new(rate).unwrap());
+    IncomeTax::new(Uuid::new_v4(), name.to_string(), vec![rate])
+}
+
+fn report(incomes: Vec<(&str, f64)>) -> DeclarationReport {
+    let incomes = incomes
+        .into_iter()
+        .map(|(raw, amount)| Income::from_date(date(raw), Amount::new(amount).unwrap()))
+        .collect();
+    DeclarationReport::new(
+        2024,
+        Quarter::Q2,
+        incomes,
+        vec![tax("ЄП", 0.05), tax("ВЗ", 0.01)],
+    )
+}
+
+fn recipient(iban: &str, purpose: &str) -> TaxRecipient {
+    TaxRecipient {
+        name: "ГУК у м.Києві/Шевченк.р-н/18050400".to_string(),
+        edrpou: "37993783".to_string(),
+        iban: iban.to_string(),
+        payment_code: "101".to_string(),
+        purpose: purpose.to_string(),
+    }
+}
+
+fn config() -> DpsConfiguration {
+    let mut config = DpsConfiguration {
+        tin: "1234567890".to_string(),
+        name: "Шевченко Тарас Григорович".to_string(),
+        account: "UA213223130000026007233566001".to_string(),
+        ..DpsConfiguration::default()
+    };
+    config.recipients.insert(
+        "ЄП".to_string(),
+        recipient("UA258999980334179879000026001", "єдиний податок"),
+    );
+    config.recipients.insert(
+        "ВЗ".to_string(),
+        recipient("UA468999980314151939000026001", ""),
+    );
+    config
+}
+
+#[test]
+fn create_orders_of_taxes_to_pay() {
+    let report = report(vec![("2024-02-10", 10000.0), ("2024-05-15", 25000.5)]);
+
+    let orders = payment::payment_orders(&report, &[], &config(), date("2024-07-19")).unwrap();
+
+    assert_eq!(orders.len(), 2);
+    let single_tax = &orders[0];
+    assert_eq!(single_tax.number, 1);
+    assert_eq!(single_tax.date, date("2024-07-19"));
+    assert_eq!(single_tax.formatted_amount(), "1250.03");
+    assert_eq!(single_tax.payer.code, "1234567890");
+    assert_eq!(single_tax.payer.iban, "UA213223130000026007233566001");
+    assert_eq!(single_tax.recipient.code, "37993783");
+    assert_eq!(single_tax.recipient.iban, "UA258999980334179879000026001");
+    assert_eq!(
+        single_tax.purpose,
+        "*;101;1234567890;єдиний податок за 2 квартал 2024 року"
+    );
+    let military_levy = &orders[1];
+    assert_eq!(military_levy.number, 2);
+    assert_eq!(military_levy.formatted_amount(), "250.01");
+    assert_eq!(
+        military_levy.purpose,
+        "*;101;1234567890;ВЗ за 2 квартал 2024 року"
+    );
+}
+
+fn payment(raw: &str, amount: f64, tax: &str) -> TaxPayment {
+    TaxPayment::new(date(raw), Amount::new(amount).unwrap()).with_tax(tax.to_string())
+}
+
+#[test]
+fn subtract_payments_made_after_the_quarter() {
+    let report = report(vec![("2024-02-10", 10000.0), ("2024-05-15", 25000.5)]);
+    let payments = vec![
+        // tax of the first quarter
+        payment("2024-05-10", 500.0, "ЄП"),
+        payment("2024-07-10", 1000.0, "ЄП"),
+        payment("2024-07-12", 250.01, "ВЗ"),
+        // made after the date of the orders
+        payment("2024-07-25", 100.0, "ЄП"),
+    ];
+
+    let orders =
+        payment::payment_orders(&report, &payments, &config(), date("2024-07-19")).unwrap();
+
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].number, 1);
+    assert_eq!(orders[0].formatted_amount(), "250.03");
+    assert_eq!(orders[0].recipient.iban, "UA258999980334179879000026001");
+}
+
+#[test]
+fn skip_orders_without_taxes_to_pay() {
+    let report = report(vec![]);
+
+    let orders = payment::payment_orders(
+        &report,
+        &[],
+        &DpsConfiguration::default(),
+        date("2024-07-19"),
+    )
+    .unwrap();
+
+    assert!(orders.is_empty());
+}
+
+#[test]
+fn reject_missing_requisites() {
+    let report = report(vec![("2024-05-15", 1000.0)]);
+    let without_account = DpsConfiguration {
+        account: String::new(),
+        ..config()
+    };
+    let mut without_recipient = config();
+    without_recipient.recipients.remove("ВЗ");
+
+    let missing_account =
+        payment::payment_orders(&report, &[], &without_account, date("2024-07-19"));
+    let missing_recipient =
+        payment::payment_orders(&report, &[], &without_recipient, date("2024-07-19"));
+
+    assert!(matches!(
+        missing_account,
+        Err(PaymentError::MissingDetail("account"))
+    ));
+    assert!(matches!(
+        missing_recipient,
+        Err(PaymentError::MissingRecipient(name)) if name == "ВЗ"
+    ));
+}
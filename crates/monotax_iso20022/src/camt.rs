@@ -0,0 +1,262 @@
+//! Import format for ISO 20022 bank to customer statements.
+//!
+//! Supports account statements (camt.053) and debit/credit notifications (camt.054).
+//! Both messages list booked entries, credit entries (`CdtDbtInd` is `CRDT`) are incomes.
+//! Elements are matched by their local names, so any version of the schema is accepted.
+//! Statements with the `FrToDt` period cover their account for the period.
+use std::fmt::Display;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use roxmltree::{Document, Node};
+use thiserror::Error;
+
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+const STATEMENT_ROOT: &str = "BkToCstmrStmt";
+const NOTIFICATION_ROOT: &str = "BkToCstmrDbtCdtNtfctn";
+const CREDIT: &str = "CRDT";
+const BOOKED: &str = "BOOK";
+const NOT_PROVIDED: &str = "NOTPROVIDED";
+
+/// Statement reader for camt.053 and camt.054 XML files.
+pub struct CamtStatementReader;
+
+/// Position of an element in the XML document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum CamtError {
+    #[error("malformed xml: {0}")]
+    MalformedXml(#[from] roxmltree::Error),
+    #[error("the document is neither camt.053 nor camt.054 message")]
+    UnsupportedMessage,
+    #[error("{message} at {location}")]
+    InvalidEntry { message: String, location: Location },
+}
+
+/// Reads incomes from credit entries of the camt message.
+pub fn read_incomes(content: &[u8]) -> Result<Vec<Income>, CamtError> {
+    let text = String::from_utf8_lossy(content);
+    let document = Document::parse(&text)?;
+    let root = message_root(&document).ok_or(CamtError::UnsupportedMessage)?;
+    let mut incomes = vec![];
+    for entry in root.descendants().filter(|node| is_element(node, "Ntry")) {
+        if let Some(income) = income_from_entry(&document, entry)? {
+            incomes.push(income);
+        }
+    }
+    incomes.sort();
+    Ok(incomes)
+}
+
+/// Reads periods of accounts covered by statements of the camt message.
+/// Notifications and statements without the period cover nothing.
+pub fn read_coverage(content: &[u8]) -> Result<Vec<StatementCoverage>, CamtError> {
+    let text = String::from_utf8_lossy(content);
+    let document = Document::parse(&text)?;
+    let root = message_root(&document).ok_or(CamtError::UnsupportedMessage)?;
+    let mut coverages = vec![];
+    for statement in root.children().filter(|node| is_element(node, "Stmt")) {
+        let Some(period) = child(statement, "FrToDt") else {
+            continue;
+        };
+        let account = required_child(&document, statement, "Acct")?;
+        let account = descendant_text(account, "IBAN")
+            .or_else(|| {
+                account
+                    .descendants()
+                    .find(|node| is_element(node, "Othr"))
+                    .and_then(|other| child_text(other, "Id"))
+            })
+            .ok_or_else(|| invalid(&document, account, "account identifier not found"))?;
+        let start = parse_period_bound(&document, period, "FrDtTm")?;
+        let end = parse_period_bound(&document, period, "ToDtTm")?;
+        coverages.push(StatementCoverage::new(account.to_string(), start, end));
+    }
+    Ok(coverages)
+}
+
+fn parse_period_bound(
+    document: &Document,
+    period: Node,
+    name: &str,
+) -> Result<NaiveDate, CamtError> {
+    let date_time = required_text(document, period, name)?;
+    date_time
+        .get(..10)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .ok_or_else(|| {
+            invalid(
+                document,
+                period,
+                format!("invalid date time {:?}", date_time),
+            )
+        })
+}
+
+fn message_root<'a>(document: &'a Document) -> Option<Node<'a, 'a>> {
+    document
+        .root_element()
+        .children()
+        .find(|node| is_element(node, STATEMENT_ROOT) || is_element(node, NOTIFICATION_ROOT))
+}
+
+fn income_from_entry(document: &Document, entry: Node) -> Result<Option<Income>, CamtError> {
+    let indicator = required_text(document, entry, "CdtDbtInd")?;
+    if indicator != CREDIT || is_reversal(entry) || !is_booked(entry) {
+        return Ok(None);
+    }
+    let amount_node = required_child(document, entry, "Amt")?;
+    let amount = parse_amount(document, amount_node)?;
+    let currency = amount_node
+        .attribute("Ccy")
+        .map(Currency::new)
+        .transpose()
+        .map_err(|e| invalid(document, amount_node, e))?
+        .unwrap_or_default();
+    let booking_date = required_child(document, entry, "BookgDt")?;
+    let date = parse_date(document, booking_date)?;
+
+    let details = entry.descendants().find(|node| is_element(node, "TxDtls"));
+    let end_to_end_id = details
+        .and_then(|details| descendant_text(details, "EndToEndId"))
+        .filter(|id| *id != NOT_PROVIDED)
+        .or_else(|| child_text(entry, "AcctSvcrRef"));
+    let remittance = details
+        .map(|details| {
+            details
+                .descendants()
+                .filter(|node| is_element(node, "Ustrd"))
+                .filter_map(|node| node.text())
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|remittance| !remittance.is_empty())
+        .or_else(|| child_text(entry, "AddtlNtryInf").map(str::to_string));
+
+    let mut income = Income::new(date, amount).with_currency(currency);
+    if let Some(remittance) = remittance {
+        income = income.with_comment(remittance);
+    }
+    if let Some(end_to_end_id) = end_to_end_id {
+        income = income.with_source_ref(end_to_end_id.to_string());
+    }
+    Ok(Some(income))
+}
+
+/// Pending entries are not incomes yet. Older schemas keep the status as text,
+/// newer ones wrap it into the `Cd` element.
+fn is_booked(entry: Node) -> bool {
+    let status = child(entry, "Sts")
+        .and_then(|status| child_text(status, "Cd").or(status.text().map(str::trim)));
+    status.is_none_or(|status| status.is_empty() || status == BOOKED)
+}
+
+fn is_reversal(entry: Node) -> bool {
+    child_text(entry, "RvslInd").is_some_and(|reversal| reversal == "true")
+}
+
+fn parse_amount(document: &Document, node: Node) -> Result<Amount, CamtError> {
+    let raw = node.text().unwrap_or_default().trim();
+    let amount: f64 = raw
+        .parse()
+        .map_err(|_| invalid(document, node, format!("invalid amount {:?}", raw)))?;
+    Amount::new(amount).map_err(|e| invalid(document, node, e))
+}
+
+/// Parses the date from `Dt` or `DtTm` element.
+fn parse_date(document: &Document, node: Node) -> Result<NaiveDateTime, CamtError> {
+    if let Some(date) = child_text(node, "Dt") {
+        return NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(|date| date.and_time(NaiveTime::MIN))
+            .map_err(|_| invalid(document, node, format!("invalid date {:?}", date)));
+    }
+    if let Some(date_time) = child_text(node, "DtTm") {
+        // the time may have an offset or fractional seconds, only the local part is used
+        let local_part = date_time.get(..19).unwrap_or(date_time);
+        return NaiveDateTime::parse_from_str(local_part, "%Y-%m-%dT%H:%M:%S")
+            .map_err(|_| invalid(document, node, format!("invalid date time {:?}", date_time)));
+    }
+    Err(invalid(document, node, "date not found"))
+}
+
+fn is_element(node: &Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_element(child, name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+fn descendant_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.descendants()
+        .find(|child| is_element(child, name))
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+fn required_child<'a, 'input>(
+    document: &Document,
+    node: Node<'a, 'input>,
+    name: &str,
+) -> Result<Node<'a, 'input>, CamtError> {
+    child(node, name).ok_or_else(|| invalid(document, node, format!("{} not found", name)))
+}
+
+fn required_text<'a>(
+    document: &Document,
+    node: Node<'a, '_>,
+    name: &str,
+) -> Result<&'a str, CamtError> {
+    child_text(node, name).ok_or_else(|| invalid(document, node, format!("{} not found", name)))
+}
+
+fn invalid(document: &Document, node: Node, message: impl Display) -> CamtError {
+    let position = document.text_pos_at(node.range().start);
+    CamtError::InvalidEntry {
+        message: message.to_string(),
+        location: Location {
+            line: position.row,
+            column: position.col,
+        },
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl StatementReader for CamtStatementReader {
+    fn format(&self) -> &str {
+        "camt"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(content);
+        Document::parse(&text).is_ok_and(|document| message_root(&document).is_some())
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        Ok(read_incomes(content)?)
+    }
+
+    fn read_coverage(&self, content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        Ok(read_coverage(content)?)
+    }
+}
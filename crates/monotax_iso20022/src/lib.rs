@@ -0,0 +1,3 @@
+//! Integration with banks via ISO 20022 messages.
+pub mod camt;
+pub mod pain;
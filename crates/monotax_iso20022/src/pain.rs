@@ -0,0 +1,199 @@
+//! Export format for ISO 20022 customer credit transfer initiations (pain.001.001.03).
+//!
+//! Orders of the same payer and date are a payment information block executed on the date.
+//! Payers are identified by their tax numbers and recipients by their EDRPOU codes.
+//! Purposes are unstructured remittance information limited to 140 characters.
+use chrono::{NaiveDate, NaiveDateTime};
+use thiserror::Error;
+
+use monotax_core::integration::payment::PaymentOrder;
+
+const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03";
+const CURRENCY: &str = "UAH";
+const NOT_PROVIDED: &str = "NOTPROVIDED";
+const MAX_TEXT: usize = 140;
+
+#[derive(Debug, Error)]
+pub enum PainError {
+    #[error("no payment orders to transfer")]
+    NoOrders,
+    #[error("{field} of the payment order {number} is longer than {MAX_TEXT} characters")]
+    TooLong { field: &'static str, number: u32 },
+}
+
+/// Writes payment orders as a pain.001 message created at the time.
+pub fn write_credit_transfers(
+    orders: &[PaymentOrder],
+    created: NaiveDateTime,
+) -> Result<String, PainError> {
+    if orders.is_empty() {
+        return Err(PainError::NoOrders);
+    }
+    for order in orders {
+        let texts = [
+            ("purpose", &order.purpose),
+            ("payer name", &order.payer.name),
+            ("recipient name", &order.recipient.name),
+        ];
+        for (field, text) in texts {
+            if text.chars().count() > MAX_TEXT {
+                return Err(PainError::TooLong {
+                    field,
+                    number: order.number,
+                });
+            }
+        }
+    }
+    let message_id = format!("MONOTAX-{}", created.format("%Y%m%d%H%M%S"));
+    let mut xml = Xml::default();
+    xml.text
+        .push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.text
+        .push_str(&format!("<Document xmlns=\"{}\">\n", NAMESPACE));
+    xml.depth = 1;
+    xml.open("CstmrCdtTrfInitn");
+    xml.open("GrpHdr");
+    xml.element("MsgId", &message_id);
+    xml.element("CreDtTm", &created.format("%Y-%m-%dT%H:%M:%S").to_string());
+    xml.element("NbOfTxs", &orders.len().to_string());
+    xml.element("CtrlSum", &control_sum(orders.iter()));
+    xml.open("InitgPty");
+    xml.element("Nm", &orders[0].payer.name);
+    xml.close("InitgPty");
+    xml.close("GrpHdr");
+    for (index, ((date, _), batch)) in batches(orders).into_iter().enumerate() {
+        write_payment_information(
+            &mut xml,
+            &format!("{}-{}", message_id, index + 1),
+            date,
+            &batch,
+        );
+    }
+    xml.close("CstmrCdtTrfInitn");
+    xml.text.push_str("</Document>\n");
+    Ok(xml.text)
+}
+
+fn write_payment_information(xml: &mut Xml, id: &str, date: NaiveDate, orders: &[&PaymentOrder]) {
+    let payer = &orders[0].payer;
+    xml.open("PmtInf");
+    xml.element("PmtInfId", id);
+    xml.element("PmtMtd", "TRF");
+    xml.element("NbOfTxs", &orders.len().to_string());
+    xml.element("CtrlSum", &control_sum(orders.iter().copied()));
+    xml.element("ReqdExctnDt", &date.format("%Y-%m-%d").to_string());
+    xml.open("Dbtr");
+    xml.element("Nm", &payer.name);
+    xml.open("Id");
+    xml.open("PrvtId");
+    xml.open("Othr");
+    xml.element("Id", &payer.code);
+    xml.close("Othr");
+    xml.close("PrvtId");
+    xml.close("Id");
+    xml.close("Dbtr");
+    write_account(xml, "DbtrAcct", &payer.iban, true);
+    xml.open("DbtrAgt");
+    xml.open("FinInstnId");
+    xml.open("Othr");
+    xml.element("Id", NOT_PROVIDED);
+    xml.close("Othr");
+    xml.close("FinInstnId");
+    xml.close("DbtrAgt");
+    for order in orders {
+        xml.open("CdtTrfTxInf");
+        xml.open("PmtId");
+        xml.element("EndToEndId", &format!("{}-{}", id, order.number));
+        xml.close("PmtId");
+        xml.open("Amt");
+        xml.line(&format!(
+            "<InstdAmt Ccy=\"{}\">{}</InstdAmt>",
+            CURRENCY,
+            order.formatted_amount()
+        ));
+        xml.close("Amt");
+        xml.open("Cdtr");
+        xml.element("Nm", &order.recipient.name);
+        xml.open("Id");
+        xml.open("OrgId");
+        xml.open("Othr");
+        xml.element("Id", &order.recipient.code);
+        xml.close("Othr");
+        xml.close("OrgId");
+        xml.close("Id");
+        xml.close("Cdtr");
+        write_account(xml, "CdtrAcct", &order.recipient.iban, false);
+        xml.open("RmtInf");
+        xml.element("Ustrd", &order.purpose);
+        xml.close("RmtInf");
+        xml.close("CdtTrfTxInf");
+    }
+    xml.close("PmtInf");
+}
+
+fn write_account(xml: &mut Xml, tag: &str, iban: &str, with_currency: bool) {
+    xml.open(tag);
+    xml.open("Id");
+    xml.element("IBAN", iban);
+    xml.close("Id");
+    if with_currency {
+        xml.element("Ccy", CURRENCY);
+    }
+    xml.close(tag);
+}
+
+/// Orders grouped by the date and the account of the payer in the order of appearance.
+fn batches(orders: &[PaymentOrder]) -> Vec<((NaiveDate, &str), Vec<&PaymentOrder>)> {
+    let mut batches: Vec<((NaiveDate, &str), Vec<&PaymentOrder>)> = vec![];
+    for order in orders {
+        let key = (order.date, order.payer.iban.as_str());
+        match batches.iter_mut().find(|(batch, _)| *batch == key) {
+            Some((_, batch)) => batch.push(order),
+            None => batches.push((key, vec![order])),
+        }
+    }
+    batches
+}
+
+fn control_sum<'a>(orders: impl Iterator<Item = &'a PaymentOrder>) -> String {
+    let kopecks: i64 = orders.map(PaymentOrder::kopecks).sum();
+    format!("{}.{:02}", kopecks / 100, kopecks % 100)
+}
+
+/// Indented XML text.
+#[derive(Default)]
+struct Xml {
+    text: String,
+    depth: usize,
+}
+
+impl Xml {
+    fn line(&mut self, line: &str) {
+        self.text.push_str(&"  ".repeat(self.depth));
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.line(&format!("<{}>", tag));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{}>", tag));
+    }
+
+    fn element(&mut self, tag: &str, value: &str) {
+        self.line(&format!("<{}>{}</{}>", tag, escape(value), tag));
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
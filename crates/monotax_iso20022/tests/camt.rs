@@ -0,0 +1,113 @@
+// Test import incomes from ISO 20022 camt.053 and camt.054 messages.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_iso20022::camt::{self, CamtError, CamtStatementReader, Location};
+
+fn income(date: &str, amount: f64) -> Income {
+    let income_date = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+    Income::new(income_date, Amount::new(amount).unwrap())
+}
+
+fn read_file(name: &str) -> Vec<u8> {
+    std::fs::read(format!("tests/test_files/{}", name)).unwrap()
+}
+
+#[test]
+fn import_credit_entries_from_statement() {
+    let incomes = camt::read_incomes(&read_file("camt053.xml")).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![
+            income("2024-03-05 00:00:00", 2500.0),
+            income("2024-03-20 14:30:00", 1200.5),
+        ]
+    );
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].source_ref(), Some("INV-2024-017"));
+    assert_eq!(
+        incomes[0].comment(),
+        Some("Software development services invoice 17")
+    );
+    assert_eq!(incomes[1].source_ref(), Some("BANKREF-003"));
+    assert_eq!(incomes[1].comment(), Some("Payment from ACME Corp"));
+}
+
+#[test]
+fn import_credit_entries_from_notification() {
+    let incomes = camt::read_incomes(&read_file("camt054.xml")).unwrap();
+
+    assert_eq!(incomes, vec![income("2024-05-10 00:00:00", 980.0)]);
+    assert_eq!(incomes[0].currency(), Currency::EUR);
+    assert_eq!(incomes[0].source_ref(), Some("E2E-77"));
+    assert_eq!(incomes[0].comment(), Some("Consulting April"));
+}
+
+#[test]
+fn detect_camt_messages() {
+    assert!(CamtStatementReader.detect(&read_file("camt053.xml")));
+    assert!(CamtStatementReader.detect(&read_file("camt054.xml")));
+    assert!(!CamtStatementReader.detect(b"<Document><Other/></Document>"));
+    assert!(!CamtStatementReader.detect(b"date;amount"));
+}
+
+#[test]
+fn report_location_of_malformed_xml() {
+    let content = b"<Document>\n  <BkToCstmrStmt>\n    <Stmt>\n  </BkToCstmrStmt>\n</Document>";
+
+    let error = camt::read_incomes(content).unwrap_err();
+
+    assert!(matches!(error, CamtError::MalformedXml(_)));
+    assert!(
+        error.to_string().contains("4:3"),
+        "unexpected error {}",
+        error
+    );
+}
+
+#[test]
+fn report_location_of_invalid_entry() {
+    let content = br#"<Document>
+  <BkToCstmrStmt>
+    <Stmt>
+      <Ntry>
+        <Amt Ccy="UAH">abc</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><Dt>2024-01-01</Dt></BookgDt>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+
+    let error = camt::read_incomes(content).unwrap_err();
+
+    match error {
+        CamtError::InvalidEntry { location, .. } => {
+            assert_eq!(location, Location { line: 5, column: 9 })
+        }
+        other => panic!("unexpected error {}", other),
+    }
+}
+
+#[test]
+fn read_statement_period() {
+    let coverages = camt::read_coverage(&read_file("camt053.xml")).unwrap();
+
+    let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+    assert_eq!(
+        coverages,
+        vec![StatementCoverage::new(
+            "UA213223130000026007233566001".to_string(),
+            date("2024-03-01"),
+            date("2024-03-31")
+        )]
+    );
+    assert_eq!(
+        camt::read_coverage(&read_file("camt054.xml")).unwrap(),
+        vec![]
+    );
+}
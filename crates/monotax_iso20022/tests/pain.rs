@@ -0,0 +1,80 @@
+// Test export of payment orders as ISO 20022 pain.001 messages.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use monotax_core::domain::model::income::Amount;
+use monotax_core::integration::payment::{PaymentOrder, PaymentParty};
+use monotax_iso20022::pain::{self, PainError};
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+fn created() -> NaiveDateTime {
+    date("2024-07-19").and_hms_opt(10, 30, 0).unwrap()
+}
+
+fn order(number: u32, date: NaiveDate, amount: f64, purpose: &str) -> PaymentOrder {
+    PaymentOrder {
+        number,
+        date,
+        amount: Amount::new(amount).unwrap(),
+        payer: PaymentParty {
+            name: "Шевченко Тарас Григорович".to_string(),
+            code: "1234567890".to_string(),
+            iban: "UA213223130000026007233566001".to_string(),
+        },
+        recipient: PaymentParty {
+            name: "ГУК у м.Києві/Шевченк.р-н/18050400".to_string(),
+            code: "37993783".to_string(),
+            iban: "UA258999980334179879000026001".to_string(),
+        },
+        purpose: purpose.to_string(),
+    }
+}
+
+#[test]
+fn write_credit_transfers() {
+    let orders = vec![
+        order(
+            1,
+            date("2024-07-19"),
+            1250.03,
+            "*;101;1234567890;єдиний податок за 2 квартал 2024 року",
+        ),
+        order(
+            2,
+            date("2024-07-19"),
+            250.0,
+            "*;101;1234567890;військовий збір за 2 квартал 2024 року",
+        ),
+        order(1, date("2024-07-22"), 10.5, "*;140;1234567890;пеня & штраф"),
+    ];
+
+    let xml = pain::write_credit_transfers(&orders, created()).unwrap();
+
+    let expected = std::fs::read_to_string("tests/test_files/pain001.xml").unwrap();
+    assert_eq!(xml, expected);
+    assert!(roxmltree::Document::parse(&xml).is_ok());
+}
+
+#[test]
+fn reject_long_purpose() {
+    let orders = vec![order(3, date("2024-07-19"), 100.0, &"п".repeat(141))];
+
+    let result = pain::write_credit_transfers(&orders, created());
+
+    assert!(matches!(
+        result,
+        Err(PainError::TooLong {
+            field: "purpose",
+            number: 3
+        })
+    ));
+}
+
+#[test]
+fn reject_empty_message() {
+    let result = pain::write_credit_transfers(&[], created());
+
+    assert!(matches!(result, Err(PainError::NoOrders)));
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use monotax_core::domain::Currency;
+use serde::{Deserialize, Serialize};
+
+/// Accounts of journal transactions.
+///
+/// - The `bank` account receives incomes and pays taxes. Its subaccount is named after the
+///   currency code, like `Assets:Bank:UAH`.
+/// - The `income` account is credited with incomes.
+/// - The `tax_expenses` account is debited with accrued taxes.
+/// - The `taxes` are liability accounts of income taxes by their names in the database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerConfiguration {
+    #[serde(default = "default_bank")]
+    pub bank: String,
+    #[serde(default = "default_income")]
+    pub income: String,
+    #[serde(default = "default_tax_expenses")]
+    pub tax_expenses: String,
+    #[serde(default = "default_taxes")]
+    pub taxes: BTreeMap<String, String>,
+}
+
+fn default_bank() -> String {
+    "Assets:Bank".to_string()
+}
+
+fn default_income() -> String {
+    "Income:FOP".to_string()
+}
+
+fn default_tax_expenses() -> String {
+    "Expenses:Taxes".to_string()
+}
+
+fn default_taxes() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("ЄП".to_string(), "Liabilities:Tax:SingleTax".to_string()),
+        ("ВЗ".to_string(), "Liabilities:Tax:MilitaryLevy".to_string()),
+    ])
+}
+
+impl Default for LedgerConfiguration {
+    fn default() -> Self {
+        Self {
+            bank: default_bank(),
+            income: default_income(),
+            tax_expenses: default_tax_expenses(),
+            taxes: default_taxes(),
+        }
+    }
+}
+
+impl LedgerConfiguration {
+    /// Bank account of the currency.
+    pub fn bank_account(&self, currency: Currency) -> String {
+        format!("{}:{}", self.bank, currency.code())
+    }
+
+    /// Liability account of the income tax.
+    pub fn tax_account(&self, tax: &str) -> Option<&str> {
+        self.taxes.get(tax).map(String::as_str)
+    }
+}
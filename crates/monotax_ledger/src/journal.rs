@@ -0,0 +1,431 @@
+//! Double-entry journal of incomes and taxes.
+//!
+//! Every income is a transaction moving its amount from the income account to the bank and
+//! accruing its taxes as expenses against tax liabilities. Refunds reverse both. Recorded tax
+//! payments settle liabilities of their taxes from the bank. Liabilities without payments
+//! stay open. Taxes are accrued and paid in hryvnias, so incomes must be in UAH.
+//!
+//! Amounts are rounded to hundredths before posting, so every transaction balances exactly.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::Write;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use thiserror::Error;
+
+use monotax_core::domain::model::income::{ensure_uah, ForeignIncomesError, IncomeKind};
+use monotax_core::domain::model::income_tax::IncomeTax;
+use monotax_core::domain::model::payment::{PaymentID, TaxPayment};
+use monotax_core::domain::{Currency, Income};
+
+use crate::configuration::LedgerConfiguration;
+
+const OPERATING_CURRENCY: &str = "UAH";
+const BEANCOUNT_ROOTS: [&str; 5] = ["Assets", "Liabilities", "Equity", "Income", "Expenses"];
+
+/// Plain-text accounting tool reading the journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum JournalFormat {
+    #[default]
+    Beancount,
+    Hledger,
+    Ledger,
+}
+
+#[derive(Debug, Error)]
+pub enum JournalError {
+    #[error("no account is set for income tax {0}. add it to the [ledger.taxes] section")]
+    MissingTaxAccount(String),
+    #[error("payment {0} has no tax. delete it and add it again with `payments add --tax`")]
+    UnassignedPayment(PaymentID),
+    #[error(transparent)]
+    ForeignIncomes(#[from] ForeignIncomesError),
+    #[error("account {account} is not valid in {format} journals")]
+    InvalidAccount {
+        account: String,
+        format: JournalFormat,
+    },
+}
+
+/// Transactions of incomes and taxes.
+#[derive(Debug)]
+pub struct Journal {
+    transactions: Vec<Transaction>,
+}
+
+#[derive(Debug)]
+pub struct Transaction {
+    date: NaiveDate,
+    narration: String,
+    metadata: Vec<(&'static str, String)>,
+    postings: Vec<Posting>,
+}
+
+#[derive(Debug)]
+pub struct Posting {
+    account: String,
+    /// Amount in hundredths of the currency.
+    cents: i64,
+    currency: Currency,
+}
+
+impl Journal {
+    /// Creates transactions of incomes and payments of their taxes.
+    /// Every income tax and every paid tax must have its liability account configured.
+    pub fn new(
+        mut incomes: Vec<Income>,
+        payments: &[TaxPayment],
+        income_taxes: &[IncomeTax],
+        config: &LedgerConfiguration,
+    ) -> Result<Self, JournalError> {
+        ensure_uah(&incomes)?;
+        let tax_accounts = income_taxes
+            .iter()
+            .map(|tax| {
+                config
+                    .tax_account(tax.name())
+                    .map(str::to_string)
+                    .ok_or_else(|| JournalError::MissingTaxAccount(tax.name().to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        incomes.sort();
+        let mut transactions = vec![];
+        for income in &incomes {
+            let sign = match income.kind() {
+                IncomeKind::Refund => -1,
+                _ => 1,
+            };
+            let currency = income.currency();
+            let amount = sign * cents(income.amount().amount());
+            let taxes = income_taxes
+                .iter()
+                .map(|tax| {
+                    let obligation = tax.calculate_obligation(income.amount(), income.date());
+                    sign * cents(obligation.amount())
+                })
+                .collect::<Vec<_>>();
+            let mut postings = vec![
+                Posting::new(config.bank_account(currency), amount, currency),
+                Posting::new(config.income.clone(), -amount, currency),
+            ];
+            let accrued = taxes.iter().sum::<i64>();
+            if accrued != 0 {
+                postings.push(Posting::new(config.tax_expenses.clone(), accrued, currency));
+                postings.extend(tax_postings(&tax_accounts, &taxes, -1, currency));
+            }
+            let mut metadata = vec![("payment_no", income.income_no().to_string())];
+            if let Some(source_ref) = income.source_ref() {
+                metadata.push(("source_ref", source_ref.to_string()));
+            }
+            if income.kind() != IncomeKind::Income {
+                metadata.push(("kind", income.kind().to_string()));
+            }
+            let narration = match (income.comment(), income.kind()) {
+                (Some(comment), _) => comment.to_string(),
+                (None, IncomeKind::Refund) => "Refund".to_string(),
+                (None, _) => "Income".to_string(),
+            };
+            transactions.push(Transaction {
+                date: income.date(),
+                narration,
+                metadata,
+                postings,
+            });
+        }
+        for payment in payments {
+            let tax = payment
+                .tax()
+                .ok_or(JournalError::UnassignedPayment(payment.id()))?;
+            let account = config
+                .tax_account(tax)
+                .ok_or_else(|| JournalError::MissingTaxAccount(tax.to_string()))?;
+            let amount = cents(payment.amount().amount());
+            transactions.push(Transaction {
+                date: payment.date(),
+                narration: format!("{} payment", tax),
+                metadata: vec![("payment_id", payment.id().to_string())],
+                postings: vec![
+                    Posting::new(account.to_string(), amount, Currency::UAH),
+                    Posting::new(config.bank_account(Currency::UAH), -amount, Currency::UAH),
+                ],
+            });
+        }
+        // payments follow incomes of the same day
+        transactions.sort_by_key(|transaction| transaction.date);
+        Ok(Self { transactions })
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Accounts of all postings in alphabetical order.
+    pub fn accounts(&self) -> BTreeSet<&str> {
+        self.transactions
+            .iter()
+            .flat_map(|transaction| &transaction.postings)
+            .map(|posting| posting.account.as_str())
+            .collect()
+    }
+
+    /// Writes the journal with declarations of all accounts.
+    /// Fails if an account name is not valid in the format.
+    pub fn write(&self, format: JournalFormat, writer: &mut dyn Write) -> anyhow::Result<()> {
+        let accounts = self.accounts();
+        if let Some(account) = accounts
+            .iter()
+            .find(|account| !is_valid_account(account, format))
+        {
+            return Err(JournalError::InvalidAccount {
+                account: account.to_string(),
+                format,
+            }
+            .into());
+        }
+        writeln!(writer, "; incomes and taxes exported by monotax")?;
+        match format {
+            JournalFormat::Beancount => {
+                writeln!(
+                    writer,
+                    "option \"operating_currency\" \"{}\"",
+                    OPERATING_CURRENCY
+                )?;
+                if let Some(first) = self.transactions.first() {
+                    writeln!(writer)?;
+                    for account in &accounts {
+                        writeln!(writer, "{} open {}", first.date, account)?;
+                    }
+                }
+            }
+            JournalFormat::Hledger | JournalFormat::Ledger => {
+                writeln!(writer)?;
+                for account in &accounts {
+                    writeln!(writer, "account {}", account)?;
+                }
+            }
+        }
+        let width = accounts
+            .iter()
+            .map(|account| account.chars().count())
+            .max()
+            .unwrap_or_default();
+        for transaction in &self.transactions {
+            writeln!(writer)?;
+            transaction.write(format, width, writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Transaction {
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn narration(&self) -> &str {
+        &self.narration
+    }
+
+    pub fn postings(&self) -> &[Posting] {
+        &self.postings
+    }
+
+    fn write(
+        &self,
+        format: JournalFormat,
+        width: usize,
+        writer: &mut dyn Write,
+    ) -> anyhow::Result<()> {
+        let indent = match format {
+            JournalFormat::Beancount => "  ",
+            JournalFormat::Hledger | JournalFormat::Ledger => "    ",
+        };
+        match format {
+            JournalFormat::Beancount => {
+                writeln!(
+                    writer,
+                    "{} * \"{}\"",
+                    self.date,
+                    escape_string(&self.narration)
+                )?;
+                for (key, value) in &self.metadata {
+                    writeln!(writer, "{}{}: \"{}\"", indent, key, escape_string(value))?;
+                }
+            }
+            JournalFormat::Hledger | JournalFormat::Ledger => {
+                let date = match format {
+                    JournalFormat::Ledger => self.date.format("%Y/%m/%d").to_string(),
+                    _ => self.date.to_string(),
+                };
+                writeln!(writer, "{} * {}", date, escape_text(&self.narration))?;
+                for (key, value) in &self.metadata {
+                    writeln!(writer, "{}; {}: {}", indent, key, escape_text(value))?;
+                }
+            }
+        }
+        for posting in &self.postings {
+            writeln!(
+                writer,
+                "{}{:<width$}  {:>12} {}",
+                indent,
+                posting.account,
+                format_cents(posting.cents),
+                posting.currency
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Posting {
+    fn new(account: String, cents: i64, currency: Currency) -> Self {
+        Self {
+            account,
+            cents,
+            currency,
+        }
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.cents as f64 / 100.0
+    }
+
+    /// Amount in hundredths of the currency.
+    pub fn cents(&self) -> i64 {
+        self.cents
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+}
+
+impl Display for JournalFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            JournalFormat::Beancount => "beancount",
+            JournalFormat::Hledger => "hledger",
+            JournalFormat::Ledger => "ledger",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Postings of nonzero taxes to their liability accounts.
+fn tax_postings(accounts: &[String], taxes: &[i64], sign: i64, currency: Currency) -> Vec<Posting> {
+    accounts
+        .iter()
+        .zip(taxes)
+        .filter(|(_, tax)| **tax != 0)
+        .map(|(account, tax)| Posting::new(account.clone(), sign * tax, currency))
+        .collect()
+}
+
+fn cents(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.abs() / 100, cents.abs() % 100)
+}
+
+/// Checks the account against the syntax of the format.
+///
+/// Beancount accounts start with one of five root accounts and every component starts
+/// with a capital letter or a digit. Hledger and ledger accept any names, but two spaces
+/// end the account name and brackets make postings virtual.
+fn is_valid_account(account: &str, format: JournalFormat) -> bool {
+    match format {
+        JournalFormat::Beancount => {
+            let mut components = account.split(':');
+            let root = components.next().unwrap_or_default();
+            let component_is_valid = |component: &str| {
+                let mut chars = component.chars();
+                chars
+                    .next()
+                    .is_some_and(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || !c.is_ascii())
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || !c.is_ascii())
+            };
+            BEANCOUNT_ROOTS.contains(&root)
+                && account.contains(':')
+                && components.all(component_is_valid)
+        }
+        JournalFormat::Hledger | JournalFormat::Ledger => {
+            !account.is_empty()
+                && account.trim() == account
+                && !account.contains("  ")
+                && !account.starts_with(['(', '['])
+                && !account.chars().any(|c| c.is_control() || c == ';')
+        }
+    }
+}
+
+/// Escapes the text for a beancount string.
+fn escape_string(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+/// Keeps the text on a line of hledger and ledger journals.
+/// Semicolons start comments and commas end tag values, so they are replaced.
+fn escape_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ';' | ',' => ' ',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_account_syntax() {
+        let beancount = JournalFormat::Beancount;
+        assert!(is_valid_account("Assets:Bank:UAH", beancount));
+        assert!(is_valid_account("Liabilities:Tax:ЄП", beancount));
+        assert!(is_valid_account("Expenses:Taxes:2024", beancount));
+        assert!(!is_valid_account("Assets", beancount));
+        assert!(!is_valid_account("Bank:UAH", beancount));
+        assert!(!is_valid_account("Assets:bank", beancount));
+        assert!(!is_valid_account("Assets:Bank Account", beancount));
+
+        let hledger = JournalFormat::Hledger;
+        assert!(is_valid_account("assets:bank account:uah", hledger));
+        assert!(!is_valid_account("assets:bank  account", hledger));
+        assert!(!is_valid_account("(assets:bank)", hledger));
+        assert!(!is_valid_account(" assets", hledger));
+    }
+
+    #[test]
+    fn format_amounts() {
+        assert_eq!(format_cents(100050), "1000.50");
+        assert_eq!(format_cents(-5), "-0.05");
+        assert_eq!(format_cents(0), "0.00");
+    }
+
+    #[test]
+    fn escape_descriptions() {
+        assert_eq!(escape_string("say \"hi\"\n"), "say \\\"hi\\\" ");
+        assert_eq!(
+            escape_text("invoice 1; paid, thanks\n"),
+            "invoice 1  paid  thanks"
+        );
+    }
+}
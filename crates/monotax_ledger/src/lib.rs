@@ -0,0 +1,6 @@
+//! Journals of plain-text accounting tools: beancount, hledger and ledger.
+//!
+//! Incomes, tax accruals and tax payments are written as double-entry transactions,
+//! so books kept in these tools can be checked against monotax.
+pub mod configuration;
+pub mod journal;
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use monotax_core::domain::model::income::{Amount, IncomeKind};
+use monotax_core::domain::model::income_tax::{IncomeTax, IncomeTaxRate, TaxRate};
+use monotax_core::domain::model::payment::TaxPayment;
+use monotax_core::domain::{Currency, Income};
+use monotax_ledger::configuration::LedgerConfiguration;
+use monotax_ledger::journal::{Journal, JournalFormat};
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+fn tax(name: &str, rate: f64) -> IncomeTax {
+    let rate = IncomeTaxRate::open(date("2020-01-01"), TaxRate::new(rate).unwrap());
+    IncomeTax::new(Uuid::new_v4(), name.to_string(), vec![rate])
+}
+
+fn income(raw: &str, amount: f64) -> Income {
+    Income::from_date(date(raw), Amount::new(amount).unwrap())
+}
+
+fn incomes() -> Vec<Income> {
+    vec![
+        income("2024-04-10", 2000.0).with_no(3),
+        income("2024-01-15", 1000.0)
+            .with_comment("Invoice \"1\"; January".to_string())
+            .with_no(1)
+            .with_source_ref("REF-1".to_string()),
+        income("2024-02-01", 100.0)
+            .with_kind(IncomeKind::Refund)
+            .with_no(2),
+    ]
+}
+
+fn payment(raw: &str, amount: f64, tax: &str) -> TaxPayment {
+    TaxPayment::new(date(raw), Amount::new(amount).unwrap()).with_tax(tax.to_string())
+}
+
+/// Taxes of the first quarter are paid, taxes of the second quarter are not.
+fn payments() -> Vec<TaxPayment> {
+    vec![
+        payment("2024-04-19", 45.0, "ЄП").with_id(1),
+        payment("2024-04-19", 9.0, "ВЗ").with_id(2),
+    ]
+}
+
+fn journal() -> Journal {
+    let taxes = vec![tax("ЄП", 0.05), tax("ВЗ", 0.01)];
+    Journal::new(
+        incomes(),
+        &payments(),
+        &taxes,
+        &LedgerConfiguration::default(),
+    )
+    .unwrap()
+}
+
+fn write(journal: &Journal, format: JournalFormat) -> String {
+    let mut buffer = vec![];
+    journal.write(format, &mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn write_beancount() {
+    let expected = fs::read_to_string("tests/test_files/journal.beancount").unwrap();
+    assert_eq!(write(&journal(), JournalFormat::Beancount), expected);
+}
+
+#[test]
+fn write_hledger() {
+    let expected = fs::read_to_string("tests/test_files/journal.hledger").unwrap();
+    assert_eq!(write(&journal(), JournalFormat::Hledger), expected);
+}
+
+#[test]
+fn write_ledger_dates_with_slashes() {
+    let ledger = write(&journal(), JournalFormat::Ledger);
+    let hledger = write(&journal(), JournalFormat::Hledger);
+
+    assert!(ledger.contains("\n2024/01/15 * Invoice \"1\"  January\n"));
+    assert_eq!(ledger.replace('/', "-"), hledger);
+}
+
+#[test]
+fn balance_transactions_and_paid_taxes() {
+    let journal = journal();
+
+    let mut balances = HashMap::new();
+    for transaction in journal.transactions() {
+        let sum = transaction
+            .postings()
+            .iter()
+            .map(|posting| posting.cents())
+            .sum::<i64>();
+        assert_eq!(sum, 0, "{} must balance", transaction.narration());
+        for posting in transaction.postings() {
+            *balances.entry(posting.account().to_string()).or_insert(0) += posting.cents();
+        }
+    }
+    // taxes of the paid first quarter are settled, the second quarter is not paid yet
+    assert_eq!(balances["Liabilities:Tax:SingleTax"], -10000);
+    assert_eq!(balances["Liabilities:Tax:MilitaryLevy"], -2000);
+    assert_eq!(balances["Income:FOP"], -290000);
+    assert_eq!(balances["Expenses:Taxes"], 17400);
+    assert_eq!(balances["Assets:Bank:UAH"], 284600);
+}
+
+#[test]
+fn leave_liabilities_open_without_payments() {
+    let taxes = vec![tax("ЄП", 0.05)];
+
+    let journal = Journal::new(incomes(), &[], &taxes, &LedgerConfiguration::default()).unwrap();
+
+    assert_eq!(journal.transactions().len(), 3);
+    assert!(journal
+        .transactions()
+        .iter()
+        .all(|transaction| transaction.narration() != "ЄП payment"));
+}
+
+#[test]
+fn reject_foreign_incomes() {
+    let mut incomes = incomes();
+    incomes.push(
+        income("2024-05-01", 100.0)
+            .with_no(4)
+            .with_currency(Currency::USD),
+    );
+
+    let error = Journal::new(incomes, &[], &[], &LedgerConfiguration::default()).unwrap_err();
+
+    assert!(error.to_string().contains("not in UAH"), "{}", error);
+}
+
+#[test]
+fn reject_payments_without_tax() {
+    let payments = [TaxPayment::new(date("2024-04-19"), Amount::new(45.0).unwrap()).with_id(7)];
+
+    let error =
+        Journal::new(incomes(), &payments, &[], &LedgerConfiguration::default()).unwrap_err();
+
+    assert!(
+        error.to_string().contains("payment 7 has no tax"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn require_tax_accounts() {
+    let taxes = vec![tax("Local tax", 0.02)];
+
+    let error = Journal::new(incomes(), &[], &taxes, &LedgerConfiguration::default()).unwrap_err();
+
+    assert!(error.to_string().contains("Local tax"), "{}", error);
+}
+
+#[test]
+fn reject_accounts_invalid_in_format() {
+    let config = LedgerConfiguration {
+        bank: "assets:bank".to_string(),
+        ..LedgerConfiguration::default()
+    };
+    let journal = Journal::new(incomes(), &[], &[], &config).unwrap();
+
+    let mut buffer = vec![];
+    let error = journal
+        .write(JournalFormat::Beancount, &mut buffer)
+        .unwrap_err();
+    assert!(error.to_string().contains("assets:bank:UAH"), "{}", error);
+    assert!(journal.write(JournalFormat::Hledger, &mut buffer).is_ok());
+}
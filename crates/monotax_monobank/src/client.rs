@@ -0,0 +1,118 @@
+//! Client of the Monobank personal API.
+//!
+//! The API allows one statement request per 60 seconds,
+//! and a single request covers at most 31 days.
+//! The client waits between requests to respect the limit.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use thiserror::Error;
+use tokio::time::{sleep_until, Instant};
+
+use crate::statement::StatementItem;
+
+/// Minimal interval between statement requests allowed by the API.
+pub const REQUEST_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The longest period a single statement request can cover.
+pub const MAX_WINDOW: chrono::Duration = chrono::Duration::days(31);
+
+/// The API returns at most this number of operations per request.
+/// The rest of the period must be requested separately.
+pub const MAX_ITEMS: usize = 500;
+
+pub struct MonobankClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+    request_interval: Duration,
+    next_request: Option<Instant>,
+}
+
+#[derive(Debug, Error)]
+pub enum MonobankError {
+    #[error("statement period from {from} to {to} exceeds 31 days")]
+    WindowTooLong {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    #[error("monobank api responded with {status}: {body}")]
+    Api {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+}
+
+impl MonobankClient {
+    pub fn new(base_url: &str, token: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            request_interval: REQUEST_INTERVAL,
+            next_request: None,
+        }
+    }
+
+    /// Changes the interval between requests. Useful for testing against a mock server.
+    pub fn with_request_interval(self, request_interval: Duration) -> Self {
+        Self {
+            request_interval,
+            ..self
+        }
+    }
+
+    /// Fetches the account statement for the period.
+    ///
+    /// The API returns operations in reverse chronological order.
+    pub async fn statement(
+        &mut self,
+        account: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<StatementItem>> {
+        if to - from > MAX_WINDOW {
+            return Err(MonobankError::WindowTooLong { from, to }.into());
+        }
+        self.wait_for_rate_limit().await;
+        let url = format!(
+            "{}/personal/statement/{}/{}/{}",
+            self.base_url,
+            account,
+            from.timestamp(),
+            to.timestamp()
+        );
+        debug!("requesting statement {}", url);
+        let response = self
+            .http
+            .get(&url)
+            .header("X-Token", &self.token)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(MonobankError::Api { status, body }.into());
+        }
+        let items = response.json::<Vec<StatementItem>>().await?;
+        info!(
+            "received {} operations from {} to {}",
+            items.len(),
+            from,
+            to
+        );
+        Ok(items)
+    }
+
+    async fn wait_for_rate_limit(&mut self) {
+        if let Some(next_request) = self.next_request {
+            if next_request > Instant::now() {
+                info!("waiting for the monobank api rate limit");
+                sleep_until(next_request).await;
+            }
+        }
+        self.next_request = Some(Instant::now() + self.request_interval);
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BASE_URL: &str = "https://api.monobank.ua";
+
+/// Configuration of the Monobank integration.
+///
+/// - The `own_accounts` are IBANs of person's accounts. Transfers from them are not incomes.
+/// - The `token` is the personal API token from <https://api.monobank.ua/>.
+/// - The `accounts` are identifiers of accounts to synchronize.
+/// - The `base_url` is the API address. It's only changed for testing.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonobankConfiguration {
+    #[serde(default)]
+    pub own_accounts: Vec<String>,
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+}
+
+fn default_base_url() -> String {
+    DEFAULT_BASE_URL.to_string()
+}
+
+impl Default for MonobankConfiguration {
+    fn default() -> Self {
+        Self {
+            own_accounts: vec![],
+            token: String::new(),
+            accounts: vec![],
+            base_url: default_base_url(),
+        }
+    }
+}
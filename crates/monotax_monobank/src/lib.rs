@@ -0,0 +1,5 @@
+//! Integration with the Monobank personal API.
+pub mod client;
+pub mod configuration;
+pub mod statement;
+pub mod sync;
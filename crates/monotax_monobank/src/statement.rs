@@ -0,0 +1,120 @@
+//! Import format for Monobank personal API statements.
+//!
+//! The personal API returns statements as a JSON array of operations.
+//! Amounts are in minor units of the currency and currencies are ISO 4217 numeric codes.
+//! Incomes are in the currency of the operation, which may differ from the account currency.
+//! Operation times are dated in the Kyiv time zone, whatever the time zone of the computer.
+//! See [API documentation](https://api.monobank.ua/docs/) for the details.
+use anyhow::Context;
+use chrono::DateTime;
+use chrono_tz::Europe::Kyiv;
+use serde::{Deserialize, Serialize};
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+/// Number of minor units in a major currency unit.
+const MINOR_UNITS: f64 = 100.0;
+
+/// A single operation of the Monobank statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementItem {
+    pub id: String,
+    /// Unix timestamp of the operation.
+    pub time: i64,
+    pub description: String,
+    pub mcc: u16,
+    /// Amount in minor units of the account currency. Debits are negative.
+    pub amount: i64,
+    /// Amount in minor units of the operation currency. Debits are negative.
+    pub operation_amount: i64,
+    /// ISO 4217 numeric code of the operation currency.
+    pub currency_code: u16,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub counter_iban: Option<String>,
+    #[serde(default)]
+    pub counter_name: Option<String>,
+}
+
+/// Statement reader for Monobank personal API JSON files.
+///
+/// Only credits coming from other parties are incomes.
+/// Debits and transfers between own accounts are skipped.
+#[derive(Debug, Default)]
+pub struct MonobankStatementReader {
+    own_accounts: Vec<String>,
+}
+
+impl MonobankStatementReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treats credits from the listed IBANs as transfers between own accounts.
+    pub fn with_own_accounts(own_accounts: Vec<String>) -> Self {
+        Self { own_accounts }
+    }
+
+    fn is_income(&self, item: &StatementItem) -> bool {
+        item.amount > 0 && !self.is_own_transfer(item)
+    }
+
+    fn is_own_transfer(&self, item: &StatementItem) -> bool {
+        item.counter_iban
+            .as_ref()
+            .is_some_and(|iban| self.own_accounts.iter().any(|own| own == iban))
+    }
+}
+
+/// Parses statement items from the JSON content.
+pub fn read_items(content: &[u8]) -> anyhow::Result<Vec<StatementItem>> {
+    serde_json::from_slice(content).context("failed to parse monobank statement")
+}
+
+/// Converts the statement items to incomes.
+pub fn incomes_from_items(
+    items: &[StatementItem],
+    reader: &MonobankStatementReader,
+) -> anyhow::Result<Vec<Income>> {
+    let mut incomes = items
+        .iter()
+        .filter(|item| reader.is_income(item))
+        .map(income_from_item)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    incomes.sort();
+    Ok(incomes)
+}
+
+fn income_from_item(item: &StatementItem) -> anyhow::Result<Income> {
+    let date = DateTime::from_timestamp(item.time, 0)
+        .with_context(|| format!("invalid operation time {}", item.time))?
+        .with_timezone(&Kyiv)
+        .naive_local();
+    let amount = Amount::new(item.operation_amount as f64 / MINOR_UNITS)?;
+    let currency = Currency::from_numeric(item.currency_code)?;
+    let comment = item.comment.as_deref().unwrap_or(&item.description);
+    Ok(Income::new(date, amount)
+        .with_comment(comment.to_string())
+        .with_currency(currency)
+        .with_source_ref(item.id.clone()))
+}
+
+impl StatementReader for MonobankStatementReader {
+    fn format(&self) -> &str {
+        "monobank"
+    }
+
+    /// The statement is a JSON array of operations with Monobank-specific fields.
+    fn detect(&self, content: &[u8]) -> bool {
+        read_items(content).is_ok_and(|items| !items.is_empty())
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        let items = read_items(content)?;
+        incomes_from_items(&items, self)
+    }
+}
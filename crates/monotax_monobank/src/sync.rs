@@ -0,0 +1,101 @@
+//! Synchronize incomes with the Monobank account statement.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+
+use monotax_core::app::income::import_incomes;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::repository::sync::SyncStateRepository;
+use monotax_core::domain::repository::IncomeRepository;
+
+use crate::client::{MonobankClient, MAX_ITEMS, MAX_WINDOW};
+use crate::statement::{incomes_from_items, MonobankStatementReader, StatementItem};
+
+/// Incomes imported by the synchronization of the account.
+#[derive(Debug)]
+pub struct AccountSync {
+    pub imported: usize,
+    /// Days of the statement fetched by this synchronization.
+    /// Nothing is fetched when the account is already synchronized.
+    pub coverage: Option<StatementCoverage>,
+}
+
+/// Imports incomes of the account received since the last synchronization.
+///
+/// The period is split into windows allowed by the API.
+/// The progress is saved after every window, so an interrupted sync continues from there.
+/// The `since` moment is used when the account was never synchronized.
+pub async fn sync_account(
+    client: &mut MonobankClient,
+    reader: &MonobankStatementReader,
+    account: &str,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    income_repo: &mut impl IncomeRepository,
+    sync_repo: &impl SyncStateRepository,
+) -> anyhow::Result<AccountSync> {
+    let start = sync_repo.last_synced(account).await?.unwrap_or(since);
+    let mut from = start;
+    let mut imported = 0;
+    while from < until {
+        let to = (from + MAX_WINDOW).min(until);
+        let items = fetch_window(client, account, from, to).await?;
+        let incomes = incomes_from_items(&items, reader)?;
+        imported += import_incomes(incomes, income_repo).await?;
+        sync_repo.save_last_synced(account, to).await?;
+        from = to;
+    }
+    info!("synchronized account {} until {}", account, until);
+    let coverage = (start < until).then(|| {
+        StatementCoverage::new(account.to_string(), start.date_naive(), until.date_naive())
+    });
+    Ok(AccountSync { imported, coverage })
+}
+
+/// Fetches all operations of the window.
+///
+/// A response with the maximum number of operations means that older operations
+/// didn't fit, so they are requested up to the oldest received one. When the whole
+/// response is of one second, the window moves before that second and the operations of
+/// the second that didn't fit are skipped with a warning.
+async fn fetch_window(
+    client: &mut MonobankClient,
+    account: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> anyhow::Result<Vec<StatementItem>> {
+    let mut items = vec![];
+    let mut to = to;
+    loop {
+        let batch = client.statement(account, from, to).await?;
+        let batch_size = batch.len();
+        let oldest = batch.iter().map(|item| item.time).min();
+        items.extend(batch);
+        match oldest {
+            Some(oldest) if batch_size >= MAX_ITEMS => {
+                let Some(oldest) = DateTime::from_timestamp(oldest, 0) else {
+                    break;
+                };
+                if oldest <= from {
+                    break;
+                }
+                to = if oldest < to {
+                    oldest
+                } else {
+                    warn!(
+                        "more than {} operations of account {} at {}, some of them are skipped",
+                        MAX_ITEMS, account, oldest
+                    );
+                    oldest - chrono::Duration::seconds(1)
+                };
+            }
+            _ => break,
+        }
+    }
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.id.clone()));
+    items.sort_by_key(|item| item.time);
+    Ok(items)
+}
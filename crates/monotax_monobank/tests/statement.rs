@@ -0,0 +1,77 @@
+// Test import incomes from the Monobank personal API statement format.
+
+use chrono::NaiveDate;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::Currency;
+use monotax_core::integration::statement::StatementReader;
+use monotax_monobank::statement::MonobankStatementReader;
+
+const OWN_ACCOUNT: &str = "UA903052992990004149123456789";
+
+fn statement() -> Vec<u8> {
+    std::fs::read("tests/test_files/statement.json").unwrap()
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn import_credits_from_statement() {
+    let reader = MonobankStatementReader::with_own_accounts(vec![OWN_ACCOUNT.to_string()]);
+
+    let incomes = reader.read_incomes(&statement()).unwrap();
+
+    assert_eq!(incomes.len(), 2);
+    assert_eq!(incomes[0].date(), date(2024, 2, 5));
+    assert_eq!(incomes[0].amount(), Amount::new(26565.40).unwrap());
+    assert_eq!(incomes[0].currency(), Currency::UAH);
+    assert_eq!(incomes[0].source_ref(), Some("ZuHWzqkKGVo="));
+    assert_eq!(
+        incomes[0].comment(),
+        Some("Оплата за послуги згідно договору №12")
+    );
+    assert_eq!(incomes[1].date(), date(2024, 4, 5));
+    assert_eq!(incomes[1].amount(), Amount::new(2500.0).unwrap());
+    assert_eq!(incomes[1].currency(), Currency::USD);
+    assert_eq!(incomes[1].comment(), Some("Від: Acme Inc"));
+}
+
+#[test]
+fn keep_transfers_from_unknown_accounts() {
+    let reader = MonobankStatementReader::new();
+
+    let incomes = reader.read_incomes(&statement()).unwrap();
+
+    assert_eq!(incomes.len(), 3);
+    assert_eq!(incomes[1].source_ref(), Some("pQrWzqkKGVq="));
+}
+
+#[test]
+fn detect_monobank_statement() {
+    let reader = MonobankStatementReader::new();
+
+    assert!(reader.detect(&statement()));
+    assert!(!reader.detect(b"[]"));
+    assert!(!reader.detect(br#"{"id": "abc"}"#));
+    assert!(!reader.detect(b"date;amount\n"));
+}
+
+#[test]
+fn reject_unknown_currency() {
+    let reader = MonobankStatementReader::new();
+    let content = br#"[{"id": "a", "time": 1707134400, "description": "x", "mcc": 4829, "amount": 100, "operationAmount": 100, "currencyCode": 1}]"#;
+
+    assert!(reader.read_incomes(content).is_err());
+}
+
+#[test]
+fn date_operations_in_kyiv_time() {
+    let reader = MonobankStatementReader::new();
+    // 2024-03-31 21:30 UTC is 00:30 of the next day in Kyiv
+    let content = br#"[{"id": "a", "time": 1711920600, "description": "x", "mcc": 4829, "amount": 100, "operationAmount": 100, "currencyCode": 980}]"#;
+
+    let incomes = reader.read_incomes(content).unwrap();
+
+    assert_eq!(incomes[0].date(), date(2024, 4, 1));
+}
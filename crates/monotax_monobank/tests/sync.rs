@@ -0,0 +1,413 @@
+// Test synchronization with the Monobank personal API running on a mock server.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use monotax_core::domain::filter::income::IncomeCriteria;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::repository::sync::SyncStateRepository;
+use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Income;
+use monotax_monobank::client::{MonobankClient, MAX_ITEMS};
+use monotax_monobank::statement::MonobankStatementReader;
+use monotax_monobank::sync::sync_account;
+use serde_json::json;
+use wiremock::matchers::{header, method, path, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ACCOUNT: &str = "acc1";
+const TOKEN: &str = "secret";
+
+#[derive(Default)]
+struct InMemoryIncomeRepository {
+    incomes: Vec<Income>,
+}
+
+#[async_trait]
+impl IncomeRepository for InMemoryIncomeRepository {
+    async fn save_all(&mut self, incomes: &[Income]) -> anyhow::Result<usize> {
+        self.incomes.extend_from_slice(incomes);
+        Ok(incomes.len())
+    }
+
+    async fn find_all(&mut self) -> anyhow::Result<Vec<Income>> {
+        Ok(self.incomes.clone())
+    }
+
+    async fn restore_all(&mut self, incomes: &[Income]) -> anyhow::Result<usize> {
+        self.save_all(incomes).await
+    }
+
+    async fn find_by(&mut self, _criteria: IncomeCriteria) -> anyhow::Result<Vec<Income>> {
+        Ok(self.incomes.clone())
+    }
+
+    async fn find_by_payment_no(&mut self, _payment_no: i64) -> anyhow::Result<Option<Income>> {
+        Ok(None)
+    }
+
+    async fn add(&mut self, income: &Income) -> anyhow::Result<Income> {
+        self.incomes.push(income.clone());
+        Ok(income.clone())
+    }
+
+    async fn update(&mut self, _income: &Income) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn delete(&mut self, _payment_no: i64) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+}
+
+#[derive(Default)]
+struct InMemorySyncStateRepository {
+    synced: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+#[async_trait]
+impl SyncStateRepository for InMemorySyncStateRepository {
+    async fn last_synced(&self, account: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        Ok(self.synced.lock().unwrap().get(account).copied())
+    }
+
+    async fn save_last_synced(
+        &self,
+        account: &str,
+        synced_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.synced
+            .lock()
+            .unwrap()
+            .insert(account.to_string(), synced_at);
+        Ok(())
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<(String, DateTime<Utc>)>> {
+        let synced = self.synced.lock().unwrap();
+        Ok(synced
+            .iter()
+            .map(|(account, synced_at)| (account.clone(), *synced_at))
+            .collect())
+    }
+}
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+fn timestamp(raw: &str) -> DateTime<Utc> {
+    raw.parse().unwrap()
+}
+
+fn operation(id: &str, time: &str, amount: i64) -> serde_json::Value {
+    json!({
+        "id": id,
+        "time": timestamp(time).timestamp(),
+        "description": "operation",
+        "mcc": 4829,
+        "amount": amount,
+        "operationAmount": amount,
+        "currencyCode": 980
+    })
+}
+
+fn statement_path(from: &str, to: &str) -> String {
+    format!(
+        "/personal/statement/{}/{}/{}",
+        ACCOUNT,
+        timestamp(from).timestamp(),
+        timestamp(to).timestamp()
+    )
+}
+
+fn client(server: &MockServer) -> MonobankClient {
+    MonobankClient::new(&server.uri(), TOKEN).with_request_interval(Duration::ZERO)
+}
+
+#[tokio::test]
+async fn request_statement_with_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-01-01T00:00:00Z",
+            "2024-01-31T00:00:00Z",
+        )))
+        .and(header("X-Token", TOKEN))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([operation(
+            "op1",
+            "2024-01-10T12:00:00Z",
+            10000
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let items = client(&server)
+        .statement(
+            ACCOUNT,
+            timestamp("2024-01-01T00:00:00Z"),
+            timestamp("2024-01-31T00:00:00Z"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "op1");
+}
+
+#[tokio::test]
+async fn reject_windows_longer_than_api_allows() {
+    let server = MockServer::start().await;
+
+    let result = client(&server)
+        .statement(
+            ACCOUNT,
+            timestamp("2024-01-01T00:00:00Z"),
+            timestamp("2024-03-01T00:00:00Z"),
+        )
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn report_api_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .set_body_json(json!({"errorDescription": "Too many requests"})),
+        )
+        .mount(&server)
+        .await;
+
+    let result = client(&server)
+        .statement(
+            ACCOUNT,
+            timestamp("2024-01-01T00:00:00Z"),
+            timestamp("2024-01-02T00:00:00Z"),
+        )
+        .await;
+
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("429"), "unexpected error {}", message);
+}
+
+#[tokio::test]
+async fn sync_period_in_windows() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-01-01T00:00:00Z",
+            "2024-02-01T00:00:00Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            operation("op2", "2024-01-20T12:00:00Z", -5000),
+            operation("op1", "2024-01-10T12:00:00Z", 10000),
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-02-01T00:00:00Z",
+            "2024-02-15T00:00:00Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([operation(
+            "op3",
+            "2024-02-10T12:00:00Z",
+            20000
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let mut income_repo = InMemoryIncomeRepository::default();
+    let sync_repo = InMemorySyncStateRepository::default();
+
+    let synced = sync_account(
+        &mut client(&server),
+        &MonobankStatementReader::new(),
+        ACCOUNT,
+        timestamp("2024-01-01T00:00:00Z"),
+        timestamp("2024-02-15T00:00:00Z"),
+        &mut income_repo,
+        &sync_repo,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(synced.imported, 2);
+    assert_eq!(
+        synced.coverage,
+        Some(StatementCoverage::new(
+            ACCOUNT.to_string(),
+            date("2024-01-01"),
+            date("2024-02-15")
+        ))
+    );
+    assert_eq!(income_repo.incomes[0].source_ref(), Some("op1"));
+    assert_eq!(income_repo.incomes[1].source_ref(), Some("op3"));
+    assert_eq!(
+        sync_repo.last_synced(ACCOUNT).await.unwrap(),
+        Some(timestamp("2024-02-15T00:00:00Z"))
+    );
+}
+
+#[tokio::test]
+async fn move_window_past_full_second() {
+    let server = MockServer::start().await;
+    let operations = (0..MAX_ITEMS)
+        .map(|index| operation(&format!("op{}", index), "2024-01-01T12:00:00Z", 100))
+        .collect::<Vec<_>>();
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-01-01T00:00:00Z",
+            "2024-01-02T00:00:00Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&operations))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T12:00:00Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&operations))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-01-01T00:00:00Z",
+            "2024-01-01T11:59:59Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([operation(
+            "older",
+            "2024-01-01T08:00:00Z",
+            100
+        )])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let mut income_repo = InMemoryIncomeRepository::default();
+    let sync_repo = InMemorySyncStateRepository::default();
+
+    let synced = sync_account(
+        &mut client(&server),
+        &MonobankStatementReader::new(),
+        ACCOUNT,
+        timestamp("2024-01-01T00:00:00Z"),
+        timestamp("2024-01-02T00:00:00Z"),
+        &mut income_repo,
+        &sync_repo,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(synced.imported, MAX_ITEMS + 1);
+    assert_eq!(income_repo.incomes[0].source_ref(), Some("older"));
+}
+
+#[tokio::test]
+async fn continue_from_last_synced() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(statement_path(
+            "2024-02-01T00:00:00Z",
+            "2024-02-15T00:00:00Z",
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex("^/personal/statement/.*"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let mut income_repo = InMemoryIncomeRepository::default();
+    let sync_repo = InMemorySyncStateRepository::default();
+    sync_repo
+        .save_last_synced(ACCOUNT, timestamp("2024-02-01T00:00:00Z"))
+        .await
+        .unwrap();
+
+    let synced = sync_account(
+        &mut client(&server),
+        &MonobankStatementReader::new(),
+        ACCOUNT,
+        timestamp("2024-01-01T00:00:00Z"),
+        timestamp("2024-02-15T00:00:00Z"),
+        &mut income_repo,
+        &sync_repo,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(synced.imported, 0);
+    // the period before the last sync is not fetched again
+    assert_eq!(
+        synced.coverage,
+        Some(StatementCoverage::new(
+            ACCOUNT.to_string(),
+            date("2024-02-01"),
+            date("2024-02-15")
+        ))
+    );
+}
+
+#[tokio::test]
+async fn cover_nothing_when_already_synced() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let mut income_repo = InMemoryIncomeRepository::default();
+    let sync_repo = InMemorySyncStateRepository::default();
+    sync_repo
+        .save_last_synced(ACCOUNT, timestamp("2024-02-15T00:00:00Z"))
+        .await
+        .unwrap();
+
+    let synced = sync_account(
+        &mut client(&server),
+        &MonobankStatementReader::new(),
+        ACCOUNT,
+        timestamp("2024-01-01T00:00:00Z"),
+        timestamp("2024-02-15T00:00:00Z"),
+        &mut income_repo,
+        &sync_repo,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(synced.imported, 0);
+    assert_eq!(synced.coverage, None);
+}
+
+#[tokio::test]
+async fn wait_between_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+    let interval = Duration::from_millis(200);
+    let mut client = MonobankClient::new(&server.uri(), TOKEN).with_request_interval(interval);
+    let from = timestamp("2024-01-01T00:00:00Z");
+    let to = timestamp("2024-01-02T00:00:00Z");
+
+    let started = std::time::Instant::now();
+    client.statement(ACCOUNT, from, to).await.unwrap();
+    client.statement(ACCOUNT, from, to).await.unwrap();
+
+    assert!(started.elapsed() >= interval);
+}
@@ -0,0 +1,12 @@
+use monotax_core::domain::Currency;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of QIF statements.
+///
+/// - The `currency` is the currency of the account. QIF files don't state it,
+///   so they are not imported without it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct QifConfiguration {
+    #[serde(default)]
+    pub currency: Option<Currency>,
+}
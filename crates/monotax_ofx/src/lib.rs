@@ -0,0 +1,6 @@
+//! Import formats of personal finance software: OFX and QIF.
+//!
+//! Fintech services like Payoneer or Wise export statements in these formats.
+pub mod configuration;
+pub mod ofx;
+pub mod qif;
@@ -0,0 +1,225 @@
+//! Import format for Open Financial Exchange (OFX) statements.
+//!
+//! OFX 1.x files are SGML where leaf elements have no closing tags,
+//! OFX 2.x files are XML. The reader tolerates both, it only looks at elements
+//! of statement transactions and the default currency of the statement.
+//! Amounts are in the default currency unless the transaction has its own `CURRENCY`.
+//! The `ORIGCURRENCY` of a transaction is the currency amounts were converted from,
+//! so it's ignored.
+//! The financial institution transaction ID (`FITID`) is the source reference of incomes.
+//! The statement covers its account (`ACCTID`) from `DTSTART` to `DTEND` of the transaction list.
+use std::collections::HashMap;
+
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+const TRANSACTION: &str = "STMTTRN";
+const TRANSACTION_LIST: &str = "BANKTRANLIST";
+const CURRENCY: &str = "CURRENCY";
+const ORIGINAL_CURRENCY: &str = "ORIGCURRENCY";
+
+/// Statement reader for OFX files in SGML and XML variants.
+pub struct OfxStatementReader;
+
+/// A transaction with its elements.
+#[derive(Debug, Default)]
+struct Transaction {
+    elements: HashMap<String, String>,
+    currency: Option<String>,
+}
+
+/// Reads incomes from credit transactions of the OFX statement.
+pub fn read_incomes(content: &[u8]) -> anyhow::Result<Vec<Income>> {
+    let text = String::from_utf8_lossy(content);
+    let mut incomes = vec![];
+    for transaction in transactions(&text)? {
+        if let Some(income) = transaction.income()? {
+            incomes.push(income);
+        }
+    }
+    incomes.sort();
+    Ok(incomes)
+}
+
+/// Reads the account and the period of the transaction list.
+pub fn read_coverage(content: &[u8]) -> anyhow::Result<Option<StatementCoverage>> {
+    let text = String::from_utf8_lossy(content);
+    let (Some(account), Some(start), Some(end)) = (
+        element_value(&text, "ACCTID"),
+        element_value(&text, "DTSTART"),
+        element_value(&text, "DTEND"),
+    ) else {
+        return Ok(None);
+    };
+    let start = parse_date(&start)?.date();
+    let end = parse_date(&end)?.date();
+    Ok(Some(StatementCoverage::new(account, start, end)))
+}
+
+/// Finds the text of the first element with the name.
+fn element_value(text: &str, name: &str) -> Option<String> {
+    let tag = format!("<{}>", name);
+    let start = text.find(&tag)? + tag.len();
+    let end = text[start..]
+        .find('<')
+        .map_or(text.len(), |end| start + end);
+    Some(decode_entities(text[start..end].trim())).filter(|value| !value.is_empty())
+}
+
+/// Collects transactions walking through the tags of the document.
+///
+/// An opening tag followed by text is a leaf element, whether it's closed or not.
+fn transactions(text: &str) -> anyhow::Result<Vec<Transaction>> {
+    let start = text
+        .find("<OFX>")
+        .or_else(|| text.find("<ofx>"))
+        .context("OFX element not found")?;
+    let mut rest = &text[start..];
+    let mut default_currency: Option<String> = None;
+    let mut current: Option<Transaction> = None;
+    let mut currency_aggregate: Option<String> = None;
+    let mut transactions = vec![];
+    while let Some(open) = rest.find('<') {
+        let close = rest[open..]
+            .find('>')
+            .map(|close| open + close)
+            .context("unterminated tag")?;
+        let tag = rest[open + 1..close].trim().to_ascii_uppercase();
+        rest = &rest[close + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            // SGML files may omit the closing tag of the last transaction in the list
+            if name == TRANSACTION || name == TRANSACTION_LIST {
+                if let Some(transaction) = current.take() {
+                    transactions.push(transaction);
+                }
+            }
+            if name == CURRENCY || name == ORIGINAL_CURRENCY {
+                currency_aggregate = None;
+            }
+            continue;
+        }
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if tag == TRANSACTION {
+            current = Some(Transaction {
+                currency: default_currency.clone(),
+                ..Transaction::default()
+            });
+            continue;
+        }
+        if tag == CURRENCY || tag == ORIGINAL_CURRENCY {
+            currency_aggregate = Some(tag);
+            continue;
+        }
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let value = decode_entities(rest[..text_end].trim());
+        if value.is_empty() {
+            continue;
+        }
+        match current.as_mut() {
+            Some(transaction)
+                if tag == "CURSYM" && currency_aggregate.as_deref() == Some(CURRENCY) =>
+            {
+                transaction.currency = Some(value);
+            }
+            // the original currency of converted transactions
+            Some(_) if tag == "CURSYM" => {}
+            Some(transaction) => {
+                transaction.elements.insert(tag, value);
+            }
+            None if tag == "CURDEF" => default_currency = Some(value),
+            None => {}
+        }
+    }
+    if let Some(transaction) = current {
+        transactions.push(transaction);
+    }
+    Ok(transactions)
+}
+
+fn decode_entities(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+impl Transaction {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.elements.get(name).map(String::as_str)
+    }
+
+    /// Converts credit transactions to incomes. Debits and zero amounts are skipped.
+    fn income(&self) -> anyhow::Result<Option<Income>> {
+        let amount = self.get("TRNAMT").context("TRNAMT not found")?;
+        let amount: f64 = amount
+            .replace(',', ".")
+            .parse()
+            .with_context(|| format!("failed to parse amount {}", amount))?;
+        if amount <= 0.0 {
+            return Ok(None);
+        }
+        let date = self.get("DTPOSTED").context("DTPOSTED not found")?;
+        let date = parse_date(date)?;
+        let mut income = Income::new(date, Amount::new(amount)?);
+        let comment = [self.get("NAME"), self.get("MEMO")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !comment.is_empty() {
+            income = income.with_comment(comment);
+        }
+        let currency = self.currency.as_deref().map(Currency::new).transpose()?;
+        if let Some(currency) = currency {
+            income = income.with_currency(currency);
+        }
+        if let Some(fitid) = self.get("FITID") {
+            income = income.with_source_ref(fitid.to_string());
+        }
+        Ok(Some(income))
+    }
+}
+
+/// Parses `YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]` dates. The time zone is ignored.
+fn parse_date(raw: &str) -> anyhow::Result<NaiveDateTime> {
+    let digits: String = raw.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let date = digits
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .with_context(|| format!("failed to parse date {}", raw))?;
+    let time = digits
+        .get(8..14)
+        .and_then(|time| NaiveTime::parse_from_str(time, "%H%M%S").ok())
+        .unwrap_or(NaiveTime::MIN);
+    Ok(date.and_time(time))
+}
+
+impl StatementReader for OfxStatementReader {
+    fn format(&self) -> &str {
+        "ofx"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(content);
+        let head = text.trim_start();
+        head.starts_with("OFXHEADER:")
+            || (head.starts_with("<?xml") && text.contains("<?OFX"))
+            || head.starts_with("<OFX>")
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        read_incomes(content)
+    }
+
+    fn read_coverage(&self, content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        Ok(read_coverage(content)?.into_iter().collect())
+    }
+}
@@ -0,0 +1,188 @@
+//! Import format for Quicken Interchange Format (QIF) statements.
+//!
+//! Every line of the file starts with a field code, `^` ends a transaction:
+//!
+//! - `D` - the date.
+//! - `T` or `U` - the amount. Positive amounts are credits.
+//! - `P` - the payee.
+//! - `M` - the memo.
+//! - `N` - the reference number.
+//!
+//! Dates are written either month-first or day-first depending on the exporting software.
+//! The order is detected from all dates of the file. The byte order mark is skipped.
+//!
+//! QIF has no currency field, so the currency of the account is configured.
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveTime};
+
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+const BOM: char = '\u{feff}';
+
+/// Statement reader for QIF files of bank and cash accounts.
+/// Files are read only when the currency of the account is known.
+#[derive(Debug, Default)]
+pub struct QifStatementReader {
+    currency: Option<Currency>,
+}
+
+#[derive(Debug, Default)]
+struct Record {
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    number: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+/// Reads incomes in the currency from credit transactions of the QIF file.
+pub fn read_incomes(content: &[u8], currency: Currency) -> anyhow::Result<Vec<Income>> {
+    let text = String::from_utf8_lossy(content);
+    let records = records(&text);
+    let order = date_order(&records);
+    let mut incomes = vec![];
+    for (index, record) in records.iter().enumerate() {
+        let income = record
+            .income(order, currency)
+            .with_context(|| format!("failed to read transaction {}", index + 1))?;
+        if let Some(income) = income {
+            incomes.push(income);
+        }
+    }
+    incomes.sort();
+    Ok(incomes)
+}
+
+fn records(text: &str) -> Vec<Record> {
+    let mut records = vec![];
+    let mut record = Record::default();
+    for line in text.trim_start_matches(BOM).lines() {
+        let line = line.trim_end();
+        let Some(code) = line.chars().next() else {
+            continue;
+        };
+        match code {
+            '!' => continue,
+            '^' => {
+                records.push(std::mem::take(&mut record));
+                continue;
+            }
+            _ => {}
+        }
+        let value = Some(line[code.len_utf8()..].trim().to_string());
+        match code {
+            'D' => record.date = value,
+            'T' | 'U' => record.amount = value,
+            'P' => record.payee = value,
+            'M' => record.memo = value,
+            'N' => record.number = value,
+            _ => {}
+        }
+    }
+    if record.date.is_some() {
+        records.push(record);
+    }
+    records
+}
+
+/// Dates are day-first if any date has the first component that can't be a month.
+fn date_order(records: &[Record]) -> DateOrder {
+    let day_first = records
+        .iter()
+        .filter_map(|record| record.date.as_deref())
+        .filter_map(|date| date_parts(date).map(|parts| parts[0]))
+        .any(|first| first > 12);
+    if day_first {
+        DateOrder::DayFirst
+    } else {
+        DateOrder::MonthFirst
+    }
+}
+
+/// Splits dates like `12/31/2024`, `12/31'24` or `31.12.2024` into numeric parts.
+fn date_parts(raw: &str) -> Option<[u32; 3]> {
+    let parts = raw
+        .split(['/', '\'', '.', '-'])
+        .map(|part| part.trim().parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts.as_slice() {
+        [first, second, year] => Some([*first, *second, *year]),
+        _ => None,
+    }
+}
+
+fn parse_date(raw: &str, order: DateOrder) -> anyhow::Result<NaiveDate> {
+    let [first, second, year] =
+        date_parts(raw).with_context(|| format!("failed to parse date {}", raw))?;
+    let (month, day) = match order {
+        DateOrder::MonthFirst => (first, second),
+        DateOrder::DayFirst => (second, first),
+    };
+    let year = if year < 100 { 2000 + year } else { year };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+        .with_context(|| format!("failed to parse date {}", raw))
+}
+
+impl Record {
+    fn income(&self, order: DateOrder, currency: Currency) -> anyhow::Result<Option<Income>> {
+        let amount = self.amount.as_deref().context("amount not found")?;
+        let amount: f64 = amount
+            .replace(',', "")
+            .parse()
+            .with_context(|| format!("failed to parse amount {}", amount))?;
+        if amount <= 0.0 {
+            return Ok(None);
+        }
+        let date = self.date.as_deref().context("date not found")?;
+        let date = parse_date(date, order)?.and_time(NaiveTime::MIN);
+        let mut income = Income::new(date, Amount::new(amount)?).with_currency(currency);
+        let comment = [self.payee.as_deref(), self.memo.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !comment.is_empty() {
+            income = income.with_comment(comment);
+        }
+        if let Some(number) = self.number.as_deref().filter(|n| !n.is_empty()) {
+            income = income.with_source_ref(number.to_string());
+        }
+        Ok(Some(income))
+    }
+}
+
+impl QifStatementReader {
+    /// Creates the reader of statements of accounts in the currency.
+    /// Without the currency, files are detected but not read.
+    pub fn new(currency: Option<Currency>) -> Self {
+        Self { currency }
+    }
+}
+
+impl StatementReader for QifStatementReader {
+    fn format(&self) -> &str {
+        "qif"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(content);
+        let head = text.trim_start_matches(BOM).trim_start();
+        head.starts_with("!Type:") || head.starts_with("!Account")
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        let currency = self.currency.context(
+            "QIF statements have no currency. set the currency of the account in the [qif] section",
+        )?;
+        read_incomes(content, currency)
+    }
+}
@@ -0,0 +1,109 @@
+// Test import incomes from OFX statements.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_ofx::ofx::{self, OfxStatementReader};
+
+fn income(date: &str, amount: f64) -> Income {
+    let income_date = NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+    Income::new(income_date, Amount::new(amount).unwrap())
+}
+
+fn read_file(name: &str) -> Vec<u8> {
+    std::fs::read(format!("tests/test_files/{}", name)).unwrap()
+}
+
+#[test]
+fn import_credits_from_sgml() {
+    let incomes = OfxStatementReader
+        .read_incomes(&read_file("payoneer.ofx"))
+        .unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![
+            income("2024-03-05 10:30:00", 1500.0),
+            income("2024-03-21 00:00:00", 730.25),
+        ]
+    );
+    assert_eq!(incomes[0].source_ref(), Some("PYN-0001"));
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].comment(), Some("Acme Corp Invoice 12 & 13"));
+    assert_eq!(incomes[1].source_ref(), Some("PYN-0003"));
+}
+
+#[test]
+fn import_credits_from_xml() {
+    let incomes = OfxStatementReader
+        .read_incomes(&read_file("wise.ofx"))
+        .unwrap();
+
+    assert_eq!(incomes, vec![income("2024-04-10 14:30:00", 980.0)]);
+    assert_eq!(incomes[0].source_ref(), Some("TRANSFER-123456"));
+    assert_eq!(incomes[0].currency(), Currency::EUR);
+    assert_eq!(incomes[0].comment(), Some("Initech GmbH Consulting April"));
+}
+
+#[test]
+fn detect_ofx_statements() {
+    assert!(OfxStatementReader.detect(&read_file("payoneer.ofx")));
+    assert!(OfxStatementReader.detect(&read_file("wise.ofx")));
+    assert!(!OfxStatementReader.detect(&read_file("statement.qif")));
+    assert!(!OfxStatementReader.detect(b"<?xml version=\"1.0\"?><Document/>"));
+}
+
+#[test]
+fn reject_transactions_without_amount() {
+    let content = b"OFXHEADER:100\n<OFX><STMTTRN><DTPOSTED>20240101<FITID>1</STMTTRN></OFX>";
+
+    assert!(OfxStatementReader.read_incomes(content).is_err());
+}
+
+#[test]
+fn read_transaction_list_period() {
+    let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+
+    let coverage = ofx::read_coverage(&read_file("payoneer.ofx")).unwrap();
+    assert_eq!(
+        coverage,
+        Some(StatementCoverage::new(
+            "12345678".to_string(),
+            date("2024-03-01"),
+            date("2024-03-31")
+        ))
+    );
+    let coverage = ofx::read_coverage(&read_file("wise.ofx")).unwrap().unwrap();
+    assert_eq!(coverage.account(), "87654321");
+    assert_eq!(coverage.end(), date("2024-04-30"));
+}
+
+fn transaction_with(currency: &str) -> String {
+    format!(
+        "OFXHEADER:100\n<OFX><STMTRS><CURDEF>USD<BANKTRANLIST>\
+         <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20240301<TRNAMT>100.00<FITID>1{}\
+         </STMTTRN></BANKTRANLIST></STMTRS></OFX>",
+        currency
+    )
+}
+
+#[test]
+fn take_currency_of_transaction() {
+    let content = transaction_with("<CURRENCY><CURRATE>1.08<CURSYM>EUR</CURRENCY>");
+
+    let incomes = OfxStatementReader.read_incomes(content.as_bytes()).unwrap();
+
+    assert_eq!(incomes[0].currency(), Currency::EUR);
+}
+
+#[test]
+fn keep_default_currency_of_converted_transaction() {
+    let content = transaction_with("<ORIGCURRENCY><CURRATE>1.08<CURSYM>EUR</ORIGCURRENCY>");
+
+    let incomes = OfxStatementReader.read_incomes(content.as_bytes()).unwrap();
+
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].amount(), Amount::new(100.0).unwrap());
+}
@@ -0,0 +1,77 @@
+// Test import incomes from QIF statements.
+
+use chrono::NaiveDate;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_ofx::qif::QifStatementReader;
+
+fn income(year: i32, month: u32, day: u32, amount: f64) -> Income {
+    let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    Income::from_date(date, Amount::new(amount).unwrap()).with_currency(Currency::USD)
+}
+
+fn reader() -> QifStatementReader {
+    QifStatementReader::new(Some(Currency::USD))
+}
+
+#[test]
+fn import_credits_with_day_first_dates() {
+    let content = std::fs::read("tests/test_files/statement.qif").unwrap();
+
+    let incomes = reader().read_incomes(&content).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![income(2024, 3, 5, 1500.0), income(2024, 3, 21, 730.25)]
+    );
+    assert_eq!(incomes[0].source_ref(), Some("1001"));
+    assert_eq!(incomes[0].comment(), Some("Acme Corp Invoice 12"));
+    assert_eq!(incomes[1].source_ref(), None);
+}
+
+#[test]
+fn import_credits_with_month_first_dates() {
+    let content = b"!Type:Bank\nD03/05/2024\nT100.00\nPAcme\n^\nD12/31'23\nT50.00\n^\n";
+
+    let incomes = reader().read_incomes(content).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![income(2023, 12, 31, 50.0), income(2024, 3, 5, 100.0)]
+    );
+}
+
+#[test]
+fn detect_qif_statement() {
+    assert!(reader().detect(b"!Type:Bank\nD03/05/2024\nT100.00\n^\n"));
+    assert!(!reader().detect(b"OFXHEADER:100\n"));
+}
+
+#[test]
+fn require_currency() {
+    let content = b"!Type:Bank\nD03/05/2024\nT100.00\n^\n";
+    let reader = QifStatementReader::default();
+
+    assert!(reader.detect(content));
+    let error = reader.read_incomes(content).unwrap_err();
+    assert!(error.to_string().contains("no currency"), "{}", error);
+}
+
+#[test]
+fn reject_invalid_dates() {
+    let content = b"!Type:Bank\nD2024\nT100.00\n^\n";
+
+    assert!(reader().read_incomes(content).is_err());
+}
+
+#[test]
+fn read_file_with_byte_order_mark() {
+    let content = "\u{feff}!Type:Bank\nD03/05/2024\nT100.00\nPПриватна особа\n^\nЖ\n";
+
+    assert!(reader().detect(content.as_bytes()));
+    let incomes = reader().read_incomes(content.as_bytes()).unwrap();
+
+    assert_eq!(incomes, vec![income(2024, 3, 5, 100.0)]);
+    assert_eq!(incomes[0].comment(), Some("Приватна особа"));
+}
@@ -0,0 +1,2 @@
+//! Import formats for PrivatBank business statements.
+pub mod statement;
@@ -0,0 +1,312 @@
+//! Import format for PrivatBank business statements.
+//!
+//! Privat24 for business exports statements as CSV and XLSX files with the same table.
+//! The table may be preceded by a title with the account and the statement period,
+//! so columns are located by their names in the header row.
+//! Debits and credits are split into separate columns, only credits are incomes.
+use std::io::Cursor;
+
+use anyhow::Context;
+use calamine::{Data, Reader, Xlsx};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use encoding_rs::{UTF_8, WINDOWS_1251};
+
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+const DATE_HEADER: &str = "Дата операції";
+const TIME_HEADER: &str = "Час операції";
+const DEBIT_HEADER: &str = "Дебет";
+const CREDIT_HEADER: &str = "Кредит";
+const CURRENCY_HEADER: &str = "Валюта";
+const PURPOSE_HEADER: &str = "Призначення платежу";
+const REFERENCE_HEADER: &str = "Референс";
+const COUNTERPARTY_HEADER: &str = "Контрагент";
+
+const ACCOUNT_TITLE: &str = "Виписка за рахунком";
+const PERIOD_TITLE: &str = "Період:";
+
+const DATE_TIME_FORMATS: [&str; 2] = ["%d.%m.%Y %H:%M:%S", "%d.%m.%Y %H:%M"];
+const DATE_FORMAT: &str = "%d.%m.%Y";
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+/// Signature of zip archives. XLSX files are zip archives.
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+
+/// Statement reader for PrivatBank business CSV exports.
+pub struct PrivatBankCsvReader;
+
+/// Statement reader for PrivatBank business XLSX exports.
+pub struct PrivatBankXlsxReader;
+
+/// Positions of the statement columns in the table.
+struct Columns {
+    date: usize,
+    time: Option<usize>,
+    debit: Option<usize>,
+    credit: usize,
+    currency: Option<usize>,
+    purpose: Option<usize>,
+    reference: Option<usize>,
+    counterparty: Option<usize>,
+}
+
+/// Reads incomes from the CSV statement.
+pub fn read_csv(content: &[u8]) -> anyhow::Result<Vec<Income>> {
+    incomes_from_rows(csv_rows(content)?)
+}
+
+/// Reads incomes from the first sheet of the XLSX statement.
+pub fn read_xlsx(content: &[u8]) -> anyhow::Result<Vec<Income>> {
+    incomes_from_rows(xlsx_rows(content)?)
+}
+
+/// Reads the account and the period stated in the title of the CSV statement.
+pub fn read_csv_coverage(content: &[u8]) -> anyhow::Result<Option<StatementCoverage>> {
+    coverage_from_rows(&csv_rows(content)?)
+}
+
+/// Reads the account and the period stated in the title of the XLSX statement.
+pub fn read_xlsx_coverage(content: &[u8]) -> anyhow::Result<Option<StatementCoverage>> {
+    coverage_from_rows(&xlsx_rows(content)?)
+}
+
+fn csv_rows(content: &[u8]) -> anyhow::Result<Vec<Vec<String>>> {
+    let text = decode(content);
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut rows = vec![];
+    for record in csv_reader.records() {
+        let record = record.context("failed to read record")?;
+        rows.push(
+            record
+                .iter()
+                .map(|field| field.trim().to_string())
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+/// Decodes the statement text. Exports are either UTF-8 or Windows-1251 encoded.
+fn decode(content: &[u8]) -> String {
+    let (text, _, had_errors) = UTF_8.decode(content);
+    if !had_errors {
+        return text.into_owned();
+    }
+    let (text, _, _) = WINDOWS_1251.decode(content);
+    text.into_owned()
+}
+
+fn xlsx_rows(content: &[u8]) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut workbook =
+        Xlsx::new(Cursor::new(content)).context("failed to open the xlsx statement")?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .context("the xlsx statement has no sheets")?
+        .context("failed to read the statement sheet")?;
+    let rows = range
+        .rows()
+        .map(|row| row.iter().map(cell_to_string).collect())
+        .collect();
+    Ok(rows)
+}
+
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(date) => date
+            .as_datetime()
+            .map(|date| date.format(DATE_TIME_FORMATS[0]).to_string())
+            .unwrap_or_default(),
+        Data::Empty => String::new(),
+        other => other.to_string().trim().to_string(),
+    }
+}
+
+fn incomes_from_rows(rows: Vec<Vec<String>>) -> anyhow::Result<Vec<Income>> {
+    let (header_index, columns) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(index, row)| Columns::from_header(row).map(|columns| (index, columns)))
+        .context("statement header not found")?;
+    let mut incomes = vec![];
+    for (index, row) in rows.iter().enumerate().skip(header_index + 1) {
+        if row.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        let income = columns
+            .income(row)
+            .with_context(|| format!("failed to read statement row {}", index + 1))?;
+        if let Some(income) = income {
+            incomes.push(income);
+        }
+    }
+    incomes.sort();
+    Ok(incomes)
+}
+
+/// Finds the account and the period in the title rows above the header.
+/// Statements without the title cover nothing.
+fn coverage_from_rows(rows: &[Vec<String>]) -> anyhow::Result<Option<StatementCoverage>> {
+    let title = rows
+        .iter()
+        .take_while(|row| Columns::from_header(row).is_none())
+        .flatten();
+    let mut account = None;
+    let mut period = None;
+    for field in title {
+        if let Some(rest) = field.strip_prefix(ACCOUNT_TITLE) {
+            account = Some(rest.trim().to_string());
+        } else if let Some(rest) = field.strip_prefix(PERIOD_TITLE) {
+            period = Some(parse_period(rest.trim())?);
+        }
+    }
+    Ok(account
+        .zip(period)
+        .map(|(account, (start, end))| StatementCoverage::new(account, start, end)))
+}
+
+/// Parses periods like `01.01.2024 - 30.04.2024`.
+fn parse_period(raw: &str) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+    let (start, end) = raw
+        .split_once('-')
+        .with_context(|| format!("failed to parse period {}", raw))?;
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date.trim(), DATE_FORMAT)
+            .with_context(|| format!("failed to parse period {}", raw))
+    };
+    Ok((parse(start)?, parse(end)?))
+}
+
+impl Columns {
+    fn from_header(row: &[String]) -> Option<Self> {
+        let find = |name: &str| {
+            row.iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+        };
+        Some(Self {
+            date: find(DATE_HEADER)?,
+            time: find(TIME_HEADER),
+            debit: find(DEBIT_HEADER),
+            credit: find(CREDIT_HEADER)?,
+            currency: find(CURRENCY_HEADER),
+            purpose: find(PURPOSE_HEADER),
+            reference: find(REFERENCE_HEADER),
+            counterparty: find(COUNTERPARTY_HEADER),
+        })
+    }
+
+    /// Reads the income from the row. Rows without a credit amount are not incomes.
+    fn income(&self, row: &[String]) -> anyhow::Result<Option<Income>> {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| row.get(column))
+                .map(String::as_str)
+                .filter(|value| !value.is_empty())
+        };
+        // rows without the date are totals
+        let Some(date) = field(Some(self.date)) else {
+            return Ok(None);
+        };
+        let Some(credit) = field(Some(self.credit)) else {
+            return Ok(None);
+        };
+        let credit = parse_amount(credit)?;
+        let debit = field(self.debit).map(parse_amount).transpose()?;
+        if credit == 0.0 || debit.is_some_and(|debit| debit != 0.0) {
+            return Ok(None);
+        }
+        let date = parse_date(date, field(self.time))?;
+        let mut income = Income::new(date, Amount::new(credit)?);
+        if let Some(purpose) = field(self.purpose).or(field(self.counterparty)) {
+            income = income.with_comment(purpose.to_string());
+        }
+        if let Some(currency) = field(self.currency) {
+            income = income.with_currency(Currency::new(currency)?);
+        }
+        if let Some(reference) = field(self.reference) {
+            income = income.with_source_ref(reference.to_string());
+        }
+        Ok(Some(income))
+    }
+}
+
+/// Parses amounts like `1 234,56` or `1234.56`.
+fn parse_amount(raw: &str) -> anyhow::Result<f64> {
+    let normalized: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    normalized
+        .parse()
+        .with_context(|| format!("failed to parse amount {}", raw))
+}
+
+fn parse_date(date: &str, time: Option<&str>) -> anyhow::Result<NaiveDateTime> {
+    let date_time = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, DATE_FORMAT)
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .with_context(|| format!("failed to parse date {}", date))?;
+    let time = time.and_then(|time| {
+        TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+    });
+    match time {
+        Some(time) if date_time.time() == NaiveTime::MIN => Ok(date_time.date().and_time(time)),
+        _ => Ok(date_time),
+    }
+}
+
+fn has_statement_header(rows: &[Vec<String>]) -> bool {
+    rows.iter().any(|row| Columns::from_header(row).is_some())
+}
+
+impl StatementReader for PrivatBankCsvReader {
+    fn format(&self) -> &str {
+        "privatbank-csv"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        !content.starts_with(ZIP_SIGNATURE)
+            && csv_rows(content).is_ok_and(|rows| has_statement_header(&rows))
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        read_csv(content)
+    }
+
+    fn read_coverage(&self, content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        Ok(read_csv_coverage(content)?.into_iter().collect())
+    }
+}
+
+impl StatementReader for PrivatBankXlsxReader {
+    fn format(&self) -> &str {
+        "privatbank-xlsx"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        content.starts_with(ZIP_SIGNATURE)
+            && xlsx_rows(content).is_ok_and(|rows| has_statement_header(&rows))
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        read_xlsx(content)
+    }
+
+    fn read_coverage(&self, content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        Ok(read_xlsx_coverage(content)?.into_iter().collect())
+    }
+}
@@ -0,0 +1,108 @@
+// Test import incomes from PrivatBank business statements.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_privatbank::statement::{self, PrivatBankCsvReader, PrivatBankXlsxReader};
+
+fn income(date: &str, amount: f64) -> Income {
+    let income_date = NaiveDateTime::parse_from_str(date, "%d.%m.%Y %H:%M").unwrap();
+    Income::new(income_date, Amount::new(amount).unwrap())
+}
+
+fn expected_incomes() -> Vec<Income> {
+    vec![
+        income("15.01.2024 10:15", 12500.0),
+        income("20.02.2024 09:30", 3000.5),
+        income("05.04.2024 14:20", 8000.0),
+    ]
+}
+
+fn read_file(name: &str) -> Vec<u8> {
+    std::fs::read(format!("tests/test_files/{}", name)).unwrap()
+}
+
+#[test]
+fn import_credits_from_csv() {
+    let incomes = PrivatBankCsvReader
+        .read_incomes(&read_file("statement.csv"))
+        .unwrap();
+
+    assert_eq!(incomes, expected_incomes());
+    assert_eq!(incomes[0].source_ref(), Some("P24A1001"));
+    assert_eq!(incomes[0].currency(), Currency::UAH);
+    assert_eq!(
+        incomes[0].comment(),
+        Some("Оплата за послуги згідно рахунку №5")
+    );
+}
+
+#[test]
+fn import_credits_from_xlsx() {
+    let incomes = PrivatBankXlsxReader
+        .read_incomes(&read_file("statement.xlsx"))
+        .unwrap();
+
+    assert_eq!(incomes, expected_incomes());
+    assert_eq!(incomes[1].source_ref(), Some("P24A1003"));
+    assert_eq!(incomes[1].comment(), Some("Оплата згідно договору 7"));
+}
+
+#[test]
+fn import_utf8_csv() {
+    let content = "Дата операції;Дебет;Кредит;Призначення платежу;Референс\n\
+                   01.03.2024 12:00:00;;1234.56;Оплата;R1\n";
+
+    let incomes = PrivatBankCsvReader
+        .read_incomes(content.as_bytes())
+        .unwrap();
+
+    assert_eq!(incomes, vec![income("01.03.2024 12:00", 1234.56)]);
+}
+
+#[test]
+fn read_coverage_from_title() {
+    let expected = StatementCoverage::new(
+        "UA583052990000026004011234567".to_string(),
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 4, 30).unwrap(),
+    );
+
+    let csv = statement::read_csv_coverage(&read_file("statement.csv")).unwrap();
+    let xlsx = statement::read_xlsx_coverage(&read_file("statement.xlsx")).unwrap();
+
+    assert_eq!(csv, Some(expected.clone()));
+    assert_eq!(xlsx, Some(expected));
+}
+
+#[test]
+fn cover_nothing_without_title() {
+    let content = "Дата операції;Дебет;Кредит\n01.03.2024;;100\n";
+
+    let coverage = statement::read_csv_coverage(content.as_bytes()).unwrap();
+
+    assert_eq!(coverage, None);
+}
+
+#[test]
+fn detect_privatbank_statements() {
+    let csv = read_file("statement.csv");
+    let xlsx = read_file("statement.xlsx");
+
+    assert!(PrivatBankCsvReader.detect(&csv));
+    assert!(!PrivatBankCsvReader.detect(&xlsx));
+    assert!(PrivatBankXlsxReader.detect(&xlsx));
+    assert!(!PrivatBankXlsxReader.detect(&csv));
+    assert!(!PrivatBankCsvReader.detect(b"date;amount\n01.01.2024;100\n"));
+}
+
+#[test]
+fn reject_invalid_amounts() {
+    let content = "Дата операції;Дебет;Кредит\n01.03.2024;;abc\n";
+
+    assert!(PrivatBankCsvReader
+        .read_incomes(content.as_bytes())
+        .is_err());
+}
@@ -0,0 +1,3 @@
+-- keep currency and bank reference of imported incomes
+ALTER TABLE income ADD COLUMN currency TEXT NOT NULL DEFAULT 'UAH';
+ALTER TABLE income ADD COLUMN source_ref TEXT;
@@ -0,0 +1,5 @@
+-- remember how far account statements were synchronized with banks
+CREATE TABLE statement_sync (
+    account TEXT PRIMARY KEY NOT NULL,
+    synced_until DATETIME NOT NULL
+);
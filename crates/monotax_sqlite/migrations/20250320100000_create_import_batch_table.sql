@@ -0,0 +1,15 @@
+-- keep track of statement files incomes are imported from
+CREATE TABLE import_batch (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    format TEXT NOT NULL,
+    imported_at DATETIME NOT NULL,
+    total_rows INTEGER NOT NULL,
+    imported_rows INTEGER NOT NULL,
+    reverted_at DATETIME
+);
+
+CREATE INDEX import_batch_sha256 ON import_batch (sha256);
+
+ALTER TABLE income ADD COLUMN batch_id INTEGER REFERENCES import_batch (id);
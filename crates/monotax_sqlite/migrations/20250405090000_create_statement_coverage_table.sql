@@ -0,0 +1,7 @@
+-- periods of bank accounts covered by imported statements
+CREATE TABLE statement_coverage (
+    account TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    batch_id INTEGER REFERENCES import_batch (id)
+);
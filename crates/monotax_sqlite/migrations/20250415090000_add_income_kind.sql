@@ -0,0 +1,2 @@
+-- refunds and corrections of the income book
+ALTER TABLE income ADD COLUMN kind TEXT NOT NULL DEFAULT 'income';
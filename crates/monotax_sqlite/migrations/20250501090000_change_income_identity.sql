@@ -0,0 +1,23 @@
+-- tell incomes apart by their currency and bank reference besides the date and the amount
+CREATE TABLE income_identity (
+    date DATETIME NOT NULL,
+    amount DOUBLE NOT NULL,
+    payment_no INTEGER NOT NULL UNIQUE,
+    description TEXT,
+    year SMALLINT NOT NULL,
+    quarter TINYINT NOT NULL,
+    tax_paid BOOLEAN NOT NULL DEFAULT false,
+    currency TEXT NOT NULL DEFAULT 'UAH',
+    source_ref TEXT,
+    batch_id INTEGER REFERENCES import_batch (id),
+    kind TEXT NOT NULL DEFAULT 'income'
+);
+
+INSERT INTO income_identity (date, amount, payment_no, description, year, quarter, tax_paid, currency, source_ref, batch_id, kind)
+SELECT date, amount, payment_no, description, year, quarter, tax_paid, currency, source_ref, batch_id, kind FROM income;
+
+DROP TABLE income;
+
+ALTER TABLE income_identity RENAME TO income;
+
+CREATE UNIQUE INDEX income_key ON income (date, amount, currency, COALESCE(source_ref, ''));
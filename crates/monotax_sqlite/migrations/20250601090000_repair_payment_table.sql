@@ -0,0 +1,6 @@
+-- payments copied from incomes got the date in the amount column and the amount in the date column
+UPDATE payment SET amount = payment_date, payment_date = amount WHERE typeof(amount) = 'text';
+
+UPDATE payment SET payment_date = date(payment_date);
+
+ALTER TABLE payment ADD COLUMN tax TEXT;
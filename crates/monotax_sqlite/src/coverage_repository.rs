@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::import_batch::BatchID;
+use monotax_core::domain::repository::coverage::CoverageRepository;
+use sqlx::SqlitePool;
+
+pub struct SqlxCoverageRepository {
+    db_pool: SqlitePool,
+}
+
+impl SqlxCoverageRepository {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl CoverageRepository for SqlxCoverageRepository {
+    async fn save_all(
+        &self,
+        coverages: &[StatementCoverage],
+        batch_id: Option<BatchID>,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.db_pool.begin().await?;
+        for coverage in coverages {
+            let (account, start, end) = (coverage.account(), coverage.start(), coverage.end());
+            sqlx::query!(
+                r#"
+                INSERT INTO statement_coverage (account, start_date, end_date, batch_id)
+                VALUES (?, ?, ?, ?)
+                "#,
+                account,
+                start,
+                end,
+                batch_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<StatementCoverage>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT account, start_date as "start_date: NaiveDate", end_date as "end_date: NaiveDate"
+            FROM statement_coverage
+            ORDER BY account, start_date
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        let coverages = records
+            .into_iter()
+            .map(|record| {
+                StatementCoverage::new(record.account, record.start_date, record.end_date)
+            })
+            .collect();
+        Ok(coverages)
+    }
+}
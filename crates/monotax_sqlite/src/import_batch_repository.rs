@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use monotax_core::domain::model::import_batch::{BatchID, ImportBatch, ImportSource};
+use monotax_core::domain::repository::import_batch::ImportBatchRepository;
+use monotax_core::domain::Income;
+use sqlx::SqlitePool;
+
+use crate::income_repository::insert_incomes;
+use crate::record::{ImportBatchRecord, IncomeRecord};
+
+pub struct SqlxImportBatchRepository {
+    db_pool: SqlitePool,
+}
+
+impl SqlxImportBatchRepository {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl ImportBatchRepository for SqlxImportBatchRepository {
+    async fn import(
+        &self,
+        source: ImportSource,
+        incomes: &[Income],
+    ) -> anyhow::Result<ImportBatch> {
+        let imported_at = Utc::now();
+        let total_rows = incomes.len() as i64;
+        let (name, sha256, format) = (source.name(), source.sha256(), source.format());
+        let mut tx = self.db_pool.begin().await?;
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO import_batch (source, sha256, format, imported_at, total_rows, imported_rows)
+            VALUES (?, ?, ?, ?, ?, 0)
+            "#,
+            name,
+            sha256,
+            format,
+            imported_at,
+            total_rows
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        let imported_rows = insert_incomes(&mut tx, incomes, Some(id)).await?;
+        let imported_count = imported_rows as i64;
+        sqlx::query!(
+            "UPDATE import_batch SET imported_rows = ? WHERE id = ?",
+            imported_count,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(ImportBatch::new(
+            id,
+            source,
+            imported_at,
+            incomes.len(),
+            imported_rows,
+        ))
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<ImportBatch>> {
+        let records = sqlx::query_as!(
+            ImportBatchRecord,
+            r#"
+            SELECT id as "id!", source, sha256, format, imported_at as "imported_at: DateTime<Utc>",
+                total_rows, imported_rows, reverted_at as "reverted_at: DateTime<Utc>"
+            FROM import_batch
+            ORDER BY id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        Ok(records.into_iter().map(ImportBatch::from).collect())
+    }
+
+    async fn find_by_id(&self, id: BatchID) -> anyhow::Result<Option<ImportBatch>> {
+        let record = sqlx::query_as!(
+            ImportBatchRecord,
+            r#"
+            SELECT id as "id!", source, sha256, format, imported_at as "imported_at: DateTime<Utc>",
+                total_rows, imported_rows, reverted_at as "reverted_at: DateTime<Utc>"
+            FROM import_batch
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(&self.db_pool)
+        .await?;
+        Ok(record.map(ImportBatch::from))
+    }
+
+    async fn find_by_sha256(&self, sha256: &str) -> anyhow::Result<Vec<ImportBatch>> {
+        let records = sqlx::query_as!(
+            ImportBatchRecord,
+            r#"
+            SELECT id as "id!", source, sha256, format, imported_at as "imported_at: DateTime<Utc>",
+                total_rows, imported_rows, reverted_at as "reverted_at: DateTime<Utc>"
+            FROM import_batch
+            WHERE sha256 = ?
+            ORDER BY id
+            "#,
+            sha256
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        Ok(records.into_iter().map(ImportBatch::from).collect())
+    }
+
+    async fn find_incomes(&self, id: BatchID) -> anyhow::Result<Vec<Income>> {
+        let records = sqlx::query_as!(
+            IncomeRecord,
+            r#"
+            SELECT date, amount, payment_no, description, year as "year: u16", quarter as "quarter: u8", tax_paid, currency, source_ref, kind
+            FROM income
+            WHERE batch_id = ?
+            ORDER BY date
+            "#,
+            id
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        records.into_iter().map(Income::try_from).collect()
+    }
+
+    async fn revert(&self, id: BatchID) -> anyhow::Result<usize> {
+        let reverted_at = Utc::now();
+        let mut tx = self.db_pool.begin().await?;
+        let removed = sqlx::query!("DELETE FROM income WHERE batch_id = ?", id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        sqlx::query!("DELETE FROM statement_coverage WHERE batch_id = ?", id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            "UPDATE import_batch SET reverted_at = ? WHERE id = ? AND reverted_at IS NULL",
+            reverted_at,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(removed as usize)
+    }
+}
@@ -2,10 +2,12 @@ use crate::criteria::SqlxCriterion;
 
 use super::record::IncomeRecord;
 use async_trait::async_trait;
+use log::warn;
 use monotax_core::domain::filter::income::{IncomeCriteria, IncomeCriterion};
+use monotax_core::domain::repository::income::IncomeRepositoryError;
 use monotax_core::domain::repository::IncomeRepository;
 use monotax_core::domain::Income;
-use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use sqlx::{QueryBuilder, Sqlite, SqliteConnection, SqlitePool};
 
 pub struct SqlxIncomeRepository {
     pool: SqlitePool,
@@ -21,38 +23,100 @@ pub fn income_repository(pool: SqlitePool) -> impl IncomeRepository {
     SqlxIncomeRepository::new(pool)
 }
 
+/// Inserts new incomes numbering them after the last known payment.
+/// Incomes with the date, amount, currency and bank reference of saved incomes are skipped
+/// with a warning.
+pub(crate) async fn insert_incomes(
+    conn: &mut SqliteConnection,
+    incomes: &[Income],
+    batch_id: Option<i64>,
+) -> anyhow::Result<usize> {
+    let mut updated = 0;
+    for income in incomes {
+        if insert_income(&mut *conn, income, batch_id).await? {
+            updated += 1;
+        } else {
+            warn!(
+                "skipped income of {} {} on {}: the same income is already saved",
+                income.amount(),
+                income.currency(),
+                income.datetime()
+            );
+        }
+    }
+    Ok(updated)
+}
+
+/// Inserts the income numbering it after the last known payment.
+/// Returns false if the same income is already saved.
+async fn insert_income(
+    conn: &mut SqliteConnection,
+    income: &Income,
+    batch_id: Option<i64>,
+) -> anyhow::Result<bool> {
+    let record = IncomeRecord::from(income);
+    let max_payment_no: i64 = sqlx::query_scalar!(r#"SELECT MAX(payment_no) FROM income"#)
+        .fetch_one(&mut *conn)
+        .await?
+        .unwrap_or_default();
+    let payment_no = max_payment_no + 1;
+    let result = sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO income (date, amount, payment_no, description, year, quarter, tax_paid, currency, source_ref, kind, batch_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        record.date,
+        record.amount,
+        payment_no,
+        record.description,
+        record.year,
+        record.quarter,
+        record.tax_paid,
+        record.currency,
+        record.source_ref,
+        record.kind,
+        batch_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
 #[async_trait]
 impl IncomeRepository for SqlxIncomeRepository {
     async fn save_all(&mut self, incomes: &[Income]) -> anyhow::Result<usize> {
-        let income_records = incomes.iter().map(IncomeRecord::from);
-        let mut updated = 0;
         let mut tx = self.pool.begin().await?;
-        let max_payment_no: i64 = sqlx::query_scalar!(r#"SELECT MAX(payment_no) FROM income"#)
-            .fetch_one(&mut *tx)
-            .await
-            .expect("failed to fetch max payment no")
-            .unwrap_or_default();
-        for record in income_records {
-            let payment_no = max_payment_no + updated as i64 + 1;
+        let updated = insert_incomes(&mut tx, incomes, None).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn restore_all(&mut self, incomes: &[Income]) -> anyhow::Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut restored = 0;
+        for record in incomes.iter().map(IncomeRecord::from) {
             let result = sqlx::query!(
                 r#"
-                INSERT OR IGNORE INTO income (date, amount, payment_no, description, year, quarter, tax_paid)
-                VALUES (?, ?, ?, ?, ?, ?, ?)
+                INSERT OR IGNORE INTO income (date, amount, payment_no, description, year, quarter, tax_paid, currency, source_ref, kind)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
                 record.date,
                 record.amount,
-                payment_no,
+                record.payment_no,
                 record.description,
                 record.year,
                 record.quarter,
-                record.tax_paid
+                record.tax_paid,
+                record.currency,
+                record.source_ref,
+                record.kind
             )
                 .execute(&mut *tx)
                 .await?;
-            updated += result.rows_affected() as usize;
+            restored += result.rows_affected() as usize;
         }
         tx.commit().await?;
-        Ok(updated)
+        Ok(restored)
     }
 
     async fn find_all(&mut self) -> anyhow::Result<Vec<Income>> {
@@ -60,14 +124,13 @@ impl IncomeRepository for SqlxIncomeRepository {
         let records = sqlx::query_as!(
             IncomeRecord,
             r#"
-            SELECT date, amount, payment_no, description, year as "year: u16", quarter as "quarter: u8", tax_paid
+            SELECT date, amount, payment_no, description, year as "year: u16", quarter as "quarter: u8", tax_paid, currency, source_ref, kind
             FROM income
             "#
         )
             .fetch_all(pool)
             .await?;
-        let incomes = records.into_iter().map(|record| record.into()).collect();
-        Ok(incomes)
+        records.into_iter().map(Income::try_from).collect()
     }
 
     async fn find_by(&mut self, criteria: IncomeCriteria) -> anyhow::Result<Vec<Income>> {
@@ -75,7 +138,7 @@ impl IncomeRepository for SqlxIncomeRepository {
 
         let mut query_builder: QueryBuilder<Sqlite> = QueryBuilder::new(
             r#"
-            SELECT date, amount, payment_no, description, year, quarter, tax_paid
+            SELECT date, amount, payment_no, description, year, quarter, tax_paid, currency, source_ref, kind
             FROM income
             "#,
         );
@@ -103,8 +166,7 @@ impl IncomeRepository for SqlxIncomeRepository {
 
         let query = query_builder.build_query_as();
         let records: Vec<IncomeRecord> = query.fetch_all(pool).await?;
-        let incomes = records.into_iter().map(|record| record.into()).collect();
-        Ok(incomes)
+        records.into_iter().map(Income::try_from).collect()
     }
 
     async fn find_by_payment_no(&mut self, payment_no: i64) -> anyhow::Result<Option<Income>> {
@@ -112,7 +174,7 @@ impl IncomeRepository for SqlxIncomeRepository {
         let record = sqlx::query_as!(
             IncomeRecord,
             r#"
-            SELECT date, amount, payment_no, description, year as "year: u16", quarter as "quarter: u8", tax_paid
+            SELECT date, amount, payment_no, description, year as "year: u16", quarter as "quarter: u8", tax_paid, currency, source_ref, kind
             FROM income
             where payment_no = ?
             "#,
@@ -120,9 +182,56 @@ impl IncomeRepository for SqlxIncomeRepository {
         )
             .fetch_optional(pool)
             .await?;
-        if record.is_none() {
-            return Ok(None);
+        record.map(Income::try_from).transpose()
+    }
+
+    async fn add(&mut self, income: &Income) -> anyhow::Result<Income> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = insert_income(&mut tx, income, None).await?;
+        if !inserted {
+            return Err(IncomeRepositoryError::duplicate(income).into());
         }
-        Ok(Some(Income::from(record.unwrap())))
+        let payment_no: i64 =
+            sqlx::query_scalar!(r#"SELECT MAX(payment_no) as "payment_no!" FROM income"#)
+                .fetch_one(&mut *tx)
+                .await?;
+        tx.commit().await?;
+        Ok(income.clone().with_no(payment_no))
+    }
+
+    async fn update(&mut self, income: &Income) -> anyhow::Result<bool> {
+        let record = IncomeRecord::from(income);
+        let result = sqlx::query!(
+            r#"
+            UPDATE income
+            SET date = ?, amount = ?, description = ?, year = ?, quarter = ?, currency = ?, source_ref = ?, kind = ?
+            WHERE payment_no = ?
+            "#,
+            record.date,
+            record.amount,
+            record.description,
+            record.year,
+            record.quarter,
+            record.currency,
+            record.source_ref,
+            record.kind,
+            record.payment_no
+        )
+            .execute(&self.pool)
+            .await;
+        match result {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(sqlx::Error::Database(error)) if error.is_unique_violation() => {
+                Err(IncomeRepositoryError::duplicate(income).into())
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn delete(&mut self, payment_no: i64) -> anyhow::Result<bool> {
+        let result = sqlx::query!("DELETE FROM income WHERE payment_no = ?", payment_no)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
     }
 }
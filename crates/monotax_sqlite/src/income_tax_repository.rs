@@ -83,10 +83,55 @@ impl IncomeTaxRepository for SqlxIncomeTaxRepository {
                 TaxRate::new(last_record.rate).unwrap(),
             );
             income_tax.add_rate(rate);
+            taxes.push(income_tax);
         }
 
         Ok(taxes)
     }
+
+    /// Rates are stored by their start dates, every rate lasts until the next one starts.
+    /// Gaps after closed rates are stored as zero rates.
+    async fn save(&self, tax: &IncomeTax) -> anyhow::Result<()> {
+        let id = tax.id().to_string();
+        let name = tax.name();
+        let mut tx = self.db_pool.begin().await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO income_tax (id, title) VALUES (?, ?)
+            ON CONFLICT (id) DO UPDATE SET title = excluded.title
+            "#,
+            id,
+            name
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM income_tax_rate WHERE income_tax_id = ?", id)
+            .execute(&mut *tx)
+            .await?;
+        let mut rates = tax.rates().to_vec();
+        rates.sort_by_key(|rate| rate.start_date());
+        let mut starts = vec![];
+        for (index, rate) in rates.iter().enumerate() {
+            starts.push((rate.start_date(), rate.rate().rate()));
+            let next_start = rates.get(index + 1).map(|next| next.start_date());
+            match rate.end_date() {
+                Some(end_date) if next_start != Some(end_date) => starts.push((end_date, 0.0)),
+                _ => {}
+            }
+        }
+        for (start_date, rate) in starts {
+            sqlx::query!(
+                "INSERT INTO income_tax_rate (income_tax_id, rate, start_date) VALUES (?, ?, ?)",
+                id,
+                rate,
+                start_date
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
 }
 
 impl From<IncomeTaxRecord> for IncomeTax {
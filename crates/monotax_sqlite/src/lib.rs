@@ -1,7 +1,11 @@
 pub mod configuration;
 pub mod connection;
+pub mod coverage_repository;
 pub mod criteria;
+pub mod import_batch_repository;
 pub mod income_repository;
 pub mod income_tax_repository;
 pub mod init;
+pub mod payment_repository;
 mod record;
+pub mod sync_state_repository;
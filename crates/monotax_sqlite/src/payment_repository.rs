@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::payment::{PaymentID, TaxPayment};
+use monotax_core::domain::repository::payment::PaymentRepository;
+use sqlx::SqlitePool;
+
+pub struct SqlxPaymentRepository {
+    db_pool: SqlitePool,
+}
+
+impl SqlxPaymentRepository {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl PaymentRepository for SqlxPaymentRepository {
+    async fn add(&self, payment: &TaxPayment) -> anyhow::Result<TaxPayment> {
+        let (date, amount, tax) = (payment.date(), payment.amount().amount(), payment.tax());
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO payment (payment_date, amount, tax)
+            VALUES (?, ?, ?)
+            "#,
+            date,
+            amount,
+            tax
+        )
+        .execute(&self.db_pool)
+        .await?
+        .last_insert_rowid();
+        Ok(payment.clone().with_id(id))
+    }
+
+    async fn restore_all(&self, payments: &[TaxPayment]) -> anyhow::Result<usize> {
+        let mut tx = self.db_pool.begin().await?;
+        let mut restored = 0;
+        for payment in payments {
+            let (id, date, amount, tax) = (
+                payment.id(),
+                payment.date(),
+                payment.amount().amount(),
+                payment.tax(),
+            );
+            let result = sqlx::query!(
+                r#"
+                INSERT OR IGNORE INTO payment (id, payment_date, amount, tax)
+                VALUES (?, ?, ?, ?)
+                "#,
+                id,
+                date,
+                amount,
+                tax
+            )
+            .execute(&mut *tx)
+            .await?;
+            restored += result.rows_affected() as usize;
+        }
+        tx.commit().await?;
+        Ok(restored)
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<TaxPayment>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT id, payment_date as "payment_date: NaiveDate", amount, tax
+            FROM payment
+            ORDER BY payment_date, id
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        records
+            .into_iter()
+            .map(|record| {
+                let payment = TaxPayment::new(record.payment_date, Amount::new(record.amount)?)
+                    .with_id(record.id);
+                Ok(match record.tax {
+                    Some(tax) => payment.with_tax(tax),
+                    None => payment,
+                })
+            })
+            .collect()
+    }
+
+    async fn delete(&self, id: PaymentID) -> anyhow::Result<bool> {
+        let result = sqlx::query!("DELETE FROM payment WHERE id = ?", id)
+            .execute(&self.db_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,7 +1,8 @@
-use chrono::{Datelike, NaiveDateTime};
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
 use sqlx::prelude::FromRow;
 
-use monotax_core::domain::{model::income::Amount, Income, Quarter};
+use monotax_core::domain::model::import_batch::{ImportBatch, ImportSource};
+use monotax_core::domain::{model::income::Amount, Currency, Income, Quarter};
 #[derive(Debug, Clone, FromRow)]
 pub struct IncomeRecord {
     pub date: NaiveDateTime,
@@ -11,12 +12,31 @@ pub struct IncomeRecord {
     pub year: u16,
     pub quarter: u8,
     pub tax_paid: bool,
+    pub currency: String,
+    pub source_ref: Option<String>,
+    pub kind: String,
 }
 
-impl From<IncomeRecord> for Income {
-    fn from(record: IncomeRecord) -> Self {
-        let amount = Amount::new(record.amount).unwrap();
-        Income::new(record.date, amount).with_no(record.payment_no)
+impl TryFrom<IncomeRecord> for Income {
+    type Error = anyhow::Error;
+
+    /// Fails on values that can't be saved, like unknown currencies and kinds.
+    fn try_from(record: IncomeRecord) -> Result<Self, Self::Error> {
+        let amount = Amount::new(record.amount)?;
+        let currency = Currency::new(&record.currency)?;
+        let kind = record.kind.parse()?;
+        let mut income = Income::new(record.date, amount)
+            .with_no(record.payment_no)
+            .with_currency(currency)
+            .with_tax_paid(record.tax_paid)
+            .with_kind(kind);
+        if let Some(description) = record.description {
+            income = income.with_comment(description);
+        }
+        if let Some(source_ref) = record.source_ref {
+            income = income.with_source_ref(source_ref);
+        }
+        Ok(income)
     }
 }
 
@@ -30,7 +50,39 @@ impl From<&Income> for IncomeRecord {
             description: value.comment().map(|s| s.to_string()),
             year: value.datetime().year() as u16,
             quarter: quarter as u8,
-            tax_paid: false,
+            tax_paid: value.tax_paid(),
+            currency: value.currency().to_string(),
+            source_ref: value.source_ref().map(|s| s.to_string()),
+            kind: value.kind().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ImportBatchRecord {
+    pub id: i64,
+    pub source: String,
+    pub sha256: String,
+    pub format: String,
+    pub imported_at: DateTime<Utc>,
+    pub total_rows: i64,
+    pub imported_rows: i64,
+    pub reverted_at: Option<DateTime<Utc>>,
+}
+
+impl From<ImportBatchRecord> for ImportBatch {
+    fn from(record: ImportBatchRecord) -> Self {
+        let source = ImportSource::new(record.source, record.sha256, record.format);
+        let batch = ImportBatch::new(
+            record.id,
+            source,
+            record.imported_at,
+            record.total_rows as usize,
+            record.imported_rows as usize,
+        );
+        match record.reverted_at {
+            Some(reverted_at) => batch.with_reverted_at(reverted_at),
+            None => batch,
         }
     }
 }
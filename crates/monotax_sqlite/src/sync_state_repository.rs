@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use monotax_core::domain::repository::sync::SyncStateRepository;
+use sqlx::SqlitePool;
+
+pub struct SqlxSyncStateRepository {
+    db_pool: SqlitePool,
+}
+
+impl SqlxSyncStateRepository {
+    pub fn new(db_pool: SqlitePool) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl SyncStateRepository for SqlxSyncStateRepository {
+    async fn last_synced(&self, account: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let synced_until = sqlx::query_scalar!(
+            r#"
+            SELECT synced_until as "synced_until: DateTime<Utc>"
+            FROM statement_sync
+            WHERE account = ?
+            "#,
+            account
+        )
+        .fetch_optional(&self.db_pool)
+        .await?;
+        Ok(synced_until)
+    }
+
+    async fn save_last_synced(
+        &self,
+        account: &str,
+        synced_at: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO statement_sync (account, synced_until)
+            VALUES (?, ?)
+            ON CONFLICT (account) DO UPDATE SET synced_until = excluded.synced_until
+            "#,
+            account,
+            synced_at
+        )
+        .execute(&self.db_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_all(&self) -> anyhow::Result<Vec<(String, DateTime<Utc>)>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT account, synced_until as "synced_until: DateTime<Utc>"
+            FROM statement_sync
+            ORDER BY account
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+        let synced = records
+            .into_iter()
+            .map(|record| (record.account, record.synced_until))
+            .collect();
+        Ok(synced)
+    }
+}
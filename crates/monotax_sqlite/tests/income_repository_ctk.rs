@@ -3,8 +3,10 @@ use monotax_core::domain::filter::income::IncomeCriteria;
 use monotax_core::domain::filter::income::IncomeCriterion;
 use monotax_core::domain::filter::income::QuarterFilter;
 use monotax_core::domain::filter::income::YearFilter;
-use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::income::{Amount, IncomeKind};
+use monotax_core::domain::repository::income::IncomeRepositoryError;
 use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Currency;
 use monotax_core::domain::Income;
 use monotax_core::domain::Quarter;
 
@@ -197,3 +199,162 @@ pub async fn test_filter_incomes_on_quarters_and_years(repo: &mut impl IncomeRep
         ]
     );
 }
+
+pub async fn test_keep_currency_and_source_ref(repo: &mut impl IncomeRepository) {
+    let foreign = income("2024-04-13 14:00:00", 225.0)
+        .with_currency(Currency::USD)
+        .with_source_ref("op-1".to_string());
+    let local = income("2024-07-13 14:00:00", 325.0);
+
+    let _ = repo.save_all(&[foreign, local]).await.unwrap();
+
+    let incomes = repo.find_all().await.unwrap();
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].source_ref(), Some("op-1"));
+    assert_eq!(incomes[1].currency(), Currency::UAH);
+    assert_eq!(incomes[1].source_ref(), None);
+}
+
+pub async fn test_keep_incomes_with_different_references(repo: &mut impl IncomeRepository) {
+    let invoice = |reference: &str| {
+        income("2024-04-13 00:00:00", 225.0).with_source_ref(reference.to_string())
+    };
+    let incomes = vec![
+        invoice("INV-1"),
+        invoice("INV-2"),
+        invoice("INV-1"),
+        income("2024-04-13 00:00:00", 225.0).with_currency(Currency::USD),
+        income("2024-04-13 00:00:00", 225.0),
+        income("2024-04-13 00:00:00", 225.0),
+    ];
+
+    let updated = repo.save_all(&incomes).await.unwrap();
+
+    assert_eq!(
+        updated, 4,
+        "only incomes without differences must be skipped"
+    );
+    let references = repo
+        .find_all()
+        .await
+        .unwrap()
+        .iter()
+        .map(|income| (income.source_ref().map(str::to_string), income.currency()))
+        .collect::<Vec<_>>();
+    assert!(references.contains(&(Some("INV-1".to_string()), Currency::UAH)));
+    assert!(references.contains(&(Some("INV-2".to_string()), Currency::UAH)));
+    assert!(references.contains(&(None, Currency::USD)));
+    assert!(references.contains(&(None, Currency::UAH)));
+}
+
+pub async fn test_add_income(repo: &mut impl IncomeRepository) {
+    let _ = repo
+        .save_all(&[income("2024-04-13 14:00:00", 225.0)])
+        .await
+        .unwrap();
+    let cash = income("2024-05-01 10:00:00", 100.0)
+        .with_comment("cash".to_string())
+        .with_kind(IncomeKind::Correction);
+
+    let added = repo.add(&cash).await.unwrap();
+
+    assert_eq!(added, cash);
+    assert_eq!(added.income_no(), 2, "payment numbers must continue");
+    let saved = repo.find_by_payment_no(2).await.unwrap().unwrap();
+    assert_eq!(saved, cash);
+    assert_eq!(saved.comment(), Some("cash"));
+    assert_eq!(saved.kind(), IncomeKind::Correction);
+
+    let duplicate = repo.add(&cash).await.unwrap_err();
+    assert!(matches!(
+        duplicate.downcast_ref::<IncomeRepositoryError>(),
+        Some(IncomeRepositoryError::Duplicate { .. })
+    ));
+}
+
+pub async fn test_update_income(repo: &mut impl IncomeRepository) {
+    let original = repo
+        .add(&income("2024-04-13 14:00:00", 225.0))
+        .await
+        .unwrap();
+    let other = repo
+        .add(&income("2024-05-13 14:00:00", 325.0))
+        .await
+        .unwrap();
+
+    let corrected = original
+        .clone()
+        .with_amount(Amount::new(250.0).unwrap())
+        .with_currency(Currency::USD)
+        .with_comment("corrected".to_string())
+        .with_kind(IncomeKind::Refund);
+    assert!(repo.update(&corrected).await.unwrap());
+
+    let saved = repo
+        .find_by_payment_no(original.income_no())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(saved, corrected);
+    assert_eq!(saved.currency(), Currency::USD);
+    assert_eq!(saved.comment(), Some("corrected"));
+    assert_eq!(saved.kind(), IncomeKind::Refund);
+
+    let clash = corrected
+        .with_date(other.datetime())
+        .with_amount(other.amount())
+        .with_currency(other.currency());
+    assert!(
+        repo.update(&clash).await.is_err(),
+        "incomes must stay unique"
+    );
+
+    let missing = income("2024-06-13 14:00:00", 425.0).with_no(100);
+    assert!(!repo.update(&missing).await.unwrap());
+}
+
+pub async fn test_delete_income(repo: &mut impl IncomeRepository) {
+    let first = repo
+        .add(&income("2024-04-13 14:00:00", 225.0))
+        .await
+        .unwrap();
+    let second = repo
+        .add(&income("2024-05-13 14:00:00", 325.0))
+        .await
+        .unwrap();
+
+    assert!(repo.delete(first.income_no()).await.unwrap());
+    assert!(!repo.delete(first.income_no()).await.unwrap());
+
+    assert_eq!(repo.find_all().await.unwrap(), vec![second]);
+}
+
+pub async fn test_restore_incomes(repo: &mut impl IncomeRepository) {
+    repo.add(&income("2024-04-13 14:00:00", 225.0))
+        .await
+        .unwrap();
+    let incomes = vec![
+        income("2024-04-13 14:00:00", 225.0).with_no(1),
+        income("2024-05-13 14:00:00", 325.0)
+            .with_comment("restored".to_string())
+            .with_no(7)
+            .with_tax_paid(true),
+    ];
+
+    let restored = repo.restore_all(&incomes).await.unwrap();
+
+    assert_eq!(restored, 1, "saved incomes must be skipped");
+    let saved = repo.find_by_payment_no(7).await.unwrap().unwrap();
+    assert_eq!(saved, incomes[1]);
+    assert_eq!(saved.comment(), Some("restored"));
+    assert!(saved.tax_paid());
+    let next = repo
+        .add(&income("2024-06-13 14:00:00", 425.0))
+        .await
+        .unwrap();
+    assert_eq!(
+        next.income_no(),
+        8,
+        "numbering must go on after restored incomes"
+    );
+}
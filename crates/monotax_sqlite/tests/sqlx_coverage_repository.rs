@@ -0,0 +1,55 @@
+use chrono::NaiveDate;
+use common::connect_to_test_db;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::import_batch::ImportSource;
+use monotax_core::domain::repository::coverage::CoverageRepository;
+use monotax_core::domain::repository::import_batch::ImportBatchRepository;
+use monotax_sqlite::coverage_repository::SqlxCoverageRepository;
+use monotax_sqlite::import_batch_repository::SqlxImportBatchRepository;
+
+mod common;
+
+fn coverage(account: &str, start: &str, end: &str) -> StatementCoverage {
+    let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+    StatementCoverage::new(account.to_string(), date(start), date(end))
+}
+
+#[tokio::test]
+async fn save_and_load_coverage() {
+    let repo = SqlxCoverageRepository::new(connect_to_test_db().await);
+    let march = coverage("uah", "2024-03-01", "2024-03-31");
+    let january = coverage("uah", "2024-01-01", "2024-01-31");
+    let usd = coverage("usd", "2024-01-01", "2024-03-31");
+
+    repo.save_all(&[march.clone(), usd.clone()], None)
+        .await
+        .unwrap();
+    repo.save_all(std::slice::from_ref(&january), None)
+        .await
+        .unwrap();
+
+    assert_eq!(repo.find_all().await.unwrap(), vec![january, march, usd]);
+}
+
+#[tokio::test]
+async fn remove_coverage_of_reverted_batch() {
+    let pool = connect_to_test_db().await;
+    let repo = SqlxCoverageRepository::new(pool.clone());
+    let batch_repo = SqlxImportBatchRepository::new(pool);
+    let source = ImportSource::from_content("a.sta".to_string(), "mt940".to_string(), b"a");
+    let batch = batch_repo.import(source, &[]).await.unwrap();
+    let kept = coverage("uah", "2024-01-01", "2024-01-31");
+    repo.save_all(std::slice::from_ref(&kept), None)
+        .await
+        .unwrap();
+    repo.save_all(
+        &[coverage("uah", "2024-02-01", "2024-02-29")],
+        Some(batch.id()),
+    )
+    .await
+    .unwrap();
+
+    batch_repo.revert(batch.id()).await.unwrap();
+
+    assert_eq!(repo.find_all().await.unwrap(), vec![kept]);
+}
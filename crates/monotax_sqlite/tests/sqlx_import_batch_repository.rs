@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use common::connect_to_test_db;
+use monotax_core::domain::model::import_batch::ImportSource;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::repository::import_batch::ImportBatchRepository;
+use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Income;
+use monotax_sqlite::import_batch_repository::SqlxImportBatchRepository;
+use monotax_sqlite::income_repository::income_repository;
+
+mod common;
+
+fn income(datetime: &str, amount: f64) -> Income {
+    let datetime = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%d %H:%M:%S").unwrap();
+    Income::new(datetime, Amount::new(amount).unwrap())
+}
+
+fn source(name: &str, content: &[u8]) -> ImportSource {
+    ImportSource::from_content(name.to_string(), "dbo".to_string(), content)
+}
+
+#[tokio::test]
+async fn tag_incomes_with_batch() {
+    let pool = connect_to_test_db().await;
+    let repo = SqlxImportBatchRepository::new(pool.clone());
+    let known = income("2024-04-13 14:00:00", 225.0);
+    let new = income("2024-07-13 14:00:00", 325.0);
+    income_repository(pool)
+        .save_all(std::slice::from_ref(&known))
+        .await
+        .unwrap();
+
+    let batch = repo
+        .import(source("april.csv", b"april"), &[known, new.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(batch.total_rows(), 2);
+    assert_eq!(batch.imported_rows(), 1);
+    assert_eq!(repo.find_incomes(batch.id()).await.unwrap(), vec![new]);
+    let saved = repo.find_by_id(batch.id()).await.unwrap().unwrap();
+    assert_eq!(saved.source().name(), "april.csv");
+    assert_eq!(saved.source().format(), "dbo");
+    assert_eq!(saved.imported_rows(), 1);
+    assert!(!saved.is_reverted());
+}
+
+#[tokio::test]
+async fn find_batches_by_content_hash() {
+    let repo = SqlxImportBatchRepository::new(connect_to_test_db().await);
+    let april = source("april.csv", b"april");
+
+    repo.import(april.clone(), &[]).await.unwrap();
+    repo.import(source("may.csv", b"may"), &[]).await.unwrap();
+    repo.import(source("copy.csv", b"april"), &[])
+        .await
+        .unwrap();
+
+    let batches = repo.find_by_sha256(april.sha256()).await.unwrap();
+    let names = batches
+        .iter()
+        .map(|batch| batch.source().name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["april.csv", "copy.csv"]);
+    assert_eq!(repo.find_all().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn revert_only_incomes_of_batch() {
+    let pool = connect_to_test_db().await;
+    let repo = SqlxImportBatchRepository::new(pool.clone());
+    let mut income_repo = income_repository(pool);
+    let first = income("2024-04-13 14:00:00", 225.0);
+    let second = income("2024-07-13 14:00:00", 325.0);
+    let first_batch = repo
+        .import(source("april.csv", b"april"), std::slice::from_ref(&first))
+        .await
+        .unwrap();
+    let second_batch = repo
+        .import(source("july.csv", b"july"), &[second])
+        .await
+        .unwrap();
+
+    let removed = repo.revert(second_batch.id()).await.unwrap();
+
+    assert_eq!(removed, 1);
+    assert_eq!(income_repo.find_all().await.unwrap(), vec![first]);
+    let reverted = repo.find_by_id(second_batch.id()).await.unwrap().unwrap();
+    assert!(reverted.is_reverted());
+    let kept = repo.find_by_id(first_batch.id()).await.unwrap().unwrap();
+    assert!(!kept.is_reverted());
+}
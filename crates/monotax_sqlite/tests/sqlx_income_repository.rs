@@ -1,5 +1,7 @@
 use common::connect_to_test_db;
+use monotax_core::domain::model::income::Amount;
 use monotax_core::domain::repository::IncomeRepository;
+use monotax_core::domain::Income;
 use monotax_sqlite::income_repository::income_repository;
 
 mod common;
@@ -44,3 +46,77 @@ async fn filter_incomes_on_quarters_and_years() {
 
     income_repository_ctk::test_filter_incomes_on_quarters_and_years(&mut repo).await;
 }
+
+#[tokio::test]
+async fn keep_currency_and_source_ref() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_keep_currency_and_source_ref(&mut repo).await;
+}
+
+#[tokio::test]
+async fn keep_incomes_with_different_references() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_keep_incomes_with_different_references(&mut repo).await;
+}
+
+#[tokio::test]
+async fn add_income() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_add_income(&mut repo).await;
+}
+
+#[tokio::test]
+async fn update_income() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_update_income(&mut repo).await;
+}
+
+#[tokio::test]
+async fn delete_income() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_delete_income(&mut repo).await;
+}
+
+#[tokio::test]
+async fn restore_incomes() {
+    let mut repo = create_repository().await;
+
+    income_repository_ctk::test_restore_incomes(&mut repo).await;
+}
+
+#[tokio::test]
+async fn reject_unknown_currencies() {
+    let pool = connect_to_test_db().await;
+    let mut repo = income_repository(pool.clone());
+    let income =
+        Income::from_date("2024-01-15".parse().unwrap(), Amount::new(100.0).unwrap()).with_no(1);
+    repo.add(&income).await.unwrap();
+    sqlx::query("UPDATE income SET currency = 'US$'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(repo.find_all().await.is_err());
+    assert!(repo.find_by_payment_no(1).await.is_err());
+}
+
+#[tokio::test]
+async fn reject_unknown_kinds() {
+    let pool = connect_to_test_db().await;
+    let mut repo = income_repository(pool.clone());
+    let income =
+        Income::from_date("2024-01-15".parse().unwrap(), Amount::new(100.0).unwrap()).with_no(1);
+    repo.add(&income).await.unwrap();
+    sqlx::query("UPDATE income SET kind = 'gift'")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(repo.find_all().await.is_err());
+    assert!(repo.find_by_payment_no(1).await.is_err());
+}
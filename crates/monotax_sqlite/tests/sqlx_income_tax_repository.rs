@@ -0,0 +1,92 @@
+use chrono::NaiveDate;
+use common::connect_to_test_db;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::income_tax::{IncomeTax, IncomeTaxRate, TaxRate};
+use monotax_core::domain::repository::income_tax::IncomeTaxRepository;
+use monotax_sqlite::income_tax_repository::SqlxIncomeTaxRepository;
+use uuid::Uuid;
+
+mod common;
+
+#[tokio::test]
+async fn load_taxes_with_rates() {
+    let pool = connect_to_test_db().await;
+    sqlx::query(
+        r#"
+        INSERT INTO income_tax (id, title) VALUES
+            ('4f1c7a52-3b7e-4d5a-9a0e-1c2b3d4e5f60', 'Single tax'),
+            ('7a2d8b63-4c8f-4e6b-8b1f-2d3c4e5f6a71', 'Military levy');
+        INSERT INTO income_tax_rate (income_tax_id, rate, start_date) VALUES
+            ('4f1c7a52-3b7e-4d5a-9a0e-1c2b3d4e5f60', 0.05, '2020-01-01'),
+            ('7a2d8b63-4c8f-4e6b-8b1f-2d3c4e5f6a71', 0.015, '2020-01-01'),
+            ('7a2d8b63-4c8f-4e6b-8b1f-2d3c4e5f6a71', 0.01, '2025-01-01');
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let repo = SqlxIncomeTaxRepository::new(pool);
+
+    let taxes = repo.find_all().await.unwrap();
+
+    let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+    let amount = Amount::new(1000.0).unwrap();
+    let names = taxes.iter().map(|tax| tax.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["Single tax", "Military levy"]);
+    assert_eq!(
+        taxes[0].calculate_obligation(amount, date("2024-06-01")),
+        Amount::new(50.0).unwrap()
+    );
+    assert_eq!(
+        taxes[1].calculate_obligation(amount, date("2024-06-01")),
+        Amount::new(15.0).unwrap()
+    );
+    assert_eq!(
+        taxes[1].calculate_obligation(amount, date("2025-06-01")),
+        Amount::new(10.0).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn save_taxes_with_rates() {
+    let pool = connect_to_test_db().await;
+    let repo = SqlxIncomeTaxRepository::new(pool);
+    let date = |raw: &str| raw.parse::<NaiveDate>().unwrap();
+    let rate = |raw| TaxRate::new(raw).unwrap();
+    let id = Uuid::new_v4();
+    let tax = IncomeTax::new(
+        id,
+        "Military levy".to_string(),
+        vec![IncomeTaxRate::open(date("2020-01-01"), rate(0.015))],
+    );
+    repo.save(&tax).await.unwrap();
+    let tax = IncomeTax::new(
+        id,
+        "Military levy (renamed)".to_string(),
+        vec![
+            IncomeTaxRate::open(date("2025-01-01"), rate(0.01)),
+            IncomeTaxRate::closed(date("2020-01-01"), date("2024-01-01"), rate(0.015)).unwrap(),
+        ],
+    );
+
+    repo.save(&tax).await.unwrap();
+
+    let taxes = repo.find_all().await.unwrap();
+    let amount = Amount::new(1000.0).unwrap();
+    assert_eq!(taxes.len(), 1);
+    assert_eq!(*taxes[0].id(), id);
+    assert_eq!(taxes[0].name(), "Military levy (renamed)");
+    assert_eq!(
+        taxes[0].calculate_obligation(amount, date("2023-06-01")),
+        Amount::new(15.0).unwrap()
+    );
+    assert_eq!(
+        taxes[0].calculate_obligation(amount, date("2024-06-01")),
+        Amount::new(0.0).unwrap(),
+        "gaps between rates must stay untaxed"
+    );
+    assert_eq!(
+        taxes[0].calculate_obligation(amount, date("2025-06-01")),
+        Amount::new(10.0).unwrap()
+    );
+}
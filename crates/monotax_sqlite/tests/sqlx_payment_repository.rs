@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use common::connect_to_test_db;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::model::payment::TaxPayment;
+use monotax_core::domain::repository::payment::PaymentRepository;
+use monotax_sqlite::payment_repository::SqlxPaymentRepository;
+
+mod common;
+
+fn payment(date: &str, amount: f64) -> TaxPayment {
+    TaxPayment::new(
+        date.parse::<NaiveDate>().unwrap(),
+        Amount::new(amount).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn save_and_load_payments() {
+    let repo = SqlxPaymentRepository::new(connect_to_test_db().await);
+
+    let april = repo
+        .add(&payment("2024-04-19", 1500.0).with_tax("ЄП".to_string()))
+        .await
+        .unwrap();
+    let january = repo.add(&payment("2024-01-19", 800.5)).await.unwrap();
+
+    assert_ne!(april.id(), january.id());
+    assert_eq!(repo.find_all().await.unwrap(), vec![january, april]);
+}
+
+#[tokio::test]
+async fn restore_payments_keeping_ids() {
+    let repo = SqlxPaymentRepository::new(connect_to_test_db().await);
+    let payments = vec![
+        payment("2024-01-19", 800.5).with_id(7),
+        payment("2024-04-19", 1500.0)
+            .with_id(9)
+            .with_tax("ЄП".to_string()),
+    ];
+
+    assert_eq!(repo.restore_all(&payments).await.unwrap(), 2);
+    assert_eq!(repo.restore_all(&payments).await.unwrap(), 0);
+    assert_eq!(repo.find_all().await.unwrap(), payments);
+}
+
+#[tokio::test]
+async fn delete_payment() {
+    let repo = SqlxPaymentRepository::new(connect_to_test_db().await);
+    let saved = repo.add(&payment("2024-04-19", 1500.0)).await.unwrap();
+
+    assert!(repo.delete(saved.id()).await.unwrap());
+    assert!(!repo.delete(saved.id()).await.unwrap());
+    assert!(repo.find_all().await.unwrap().is_empty());
+}
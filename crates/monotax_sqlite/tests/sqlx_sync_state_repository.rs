@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use common::connect_to_test_db;
+use monotax_core::domain::repository::sync::SyncStateRepository;
+use monotax_sqlite::sync_state_repository::SqlxSyncStateRepository;
+
+mod common;
+
+fn timestamp(raw: &str) -> DateTime<Utc> {
+    raw.parse().unwrap()
+}
+
+#[tokio::test]
+async fn remember_last_synced_per_account() {
+    let repo = SqlxSyncStateRepository::new(connect_to_test_db().await);
+
+    assert_eq!(repo.last_synced("uah").await.unwrap(), None);
+
+    repo.save_last_synced("uah", timestamp("2024-03-01T10:00:00Z"))
+        .await
+        .unwrap();
+    repo.save_last_synced("usd", timestamp("2024-02-01T10:00:00Z"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repo.last_synced("uah").await.unwrap(),
+        Some(timestamp("2024-03-01T10:00:00Z"))
+    );
+    assert_eq!(
+        repo.last_synced("usd").await.unwrap(),
+        Some(timestamp("2024-02-01T10:00:00Z"))
+    );
+    assert_eq!(
+        repo.find_all().await.unwrap(),
+        vec![
+            ("uah".to_string(), timestamp("2024-03-01T10:00:00Z")),
+            ("usd".to_string(), timestamp("2024-02-01T10:00:00Z")),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn move_last_synced_forward() {
+    let repo = SqlxSyncStateRepository::new(connect_to_test_db().await);
+
+    repo.save_last_synced("uah", timestamp("2024-03-01T10:00:00Z"))
+        .await
+        .unwrap();
+    repo.save_last_synced("uah", timestamp("2024-04-01T10:00:00Z"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repo.last_synced("uah").await.unwrap(),
+        Some(timestamp("2024-04-01T10:00:00Z"))
+    );
+}
@@ -0,0 +1,2 @@
+//! Integration with banks via SWIFT messages.
+pub mod mt940;
@@ -0,0 +1,464 @@
+//! Import format for SWIFT MT940 customer statements.
+//!
+//! A statement is a sequence of tagged fields. The important ones are:
+//!
+//! - `:60F:`/`:60M:` - the opening balance.
+//! - `:61:` - a statement line with a single transaction.
+//! - `:86:` - information to the account owner about the preceding transaction.
+//! - `:62F:`/`:62M:` - the closing balance.
+//!
+//! Field values can span multiple lines.
+//! Incomes are dated with the entry date of their statement lines, when it's present,
+//! as that is when the money was received. Otherwise the value date is used.
+//! Every statement is verified to have the closing balance matching the opening balance
+//! and all transactions, so a truncated file is not imported silently.
+use std::fmt::Display;
+
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use thiserror::Error;
+
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+
+const NO_REFERENCE: &str = "NONREF";
+/// Digits of the amount field including the fraction.
+const MAX_AMOUNT_DIGITS: usize = 15;
+
+/// Statement reader for SWIFT MT940 files.
+pub struct Mt940StatementReader;
+
+/// A transaction from the `:61:` statement line and its `:86:` information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transaction {
+    pub value_date: NaiveDate,
+    /// Date the transaction was booked on the account.
+    pub entry_date: Option<NaiveDate>,
+    pub mark: Mark,
+    /// Amount in minor units.
+    pub amount: i64,
+    pub customer_reference: String,
+    pub bank_reference: Option<String>,
+    pub information: Option<String>,
+    /// Line of the statement file the transaction starts on.
+    pub line: usize,
+}
+
+/// Debit/credit mark of transactions and balances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Credit,
+    Debit,
+    ReversalOfCredit,
+    ReversalOfDebit,
+}
+
+/// Opening or closing balance of the statement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Balance {
+    pub date: NaiveDate,
+    pub currency: Currency,
+    /// Signed amount in minor units.
+    pub amount: i64,
+}
+
+/// A single statement of the MT940 file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub reference: String,
+    pub account: String,
+    pub opening_balance: Balance,
+    pub closing_balance: Balance,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Error)]
+pub enum Mt940Error {
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error(
+        "statement {reference} does not balance: opening {opening} and transactions give {expected}, but closing is {closing}. some transactions may be missing"
+    )]
+    Unbalanced {
+        reference: String,
+        opening: MinorUnits,
+        expected: MinorUnits,
+        closing: MinorUnits,
+    },
+}
+
+/// Amount in minor units printed as a decimal number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinorUnits(pub i64);
+
+/// A tagged field with the line it starts on.
+struct Field {
+    tag: String,
+    value: String,
+    line: usize,
+}
+
+/// Parses all statements of the MT940 content and verifies their balances.
+pub fn read_statements(content: &[u8]) -> Result<Vec<Statement>, Mt940Error> {
+    let text = String::from_utf8_lossy(content);
+    let fields = fields(&text)?;
+    let mut statements = vec![];
+    let mut builder: Option<StatementBuilder> = None;
+    for field in fields {
+        match field.tag.as_str() {
+            "20" => {
+                if let Some(builder) = builder.take() {
+                    statements.push(builder.build(field.line)?);
+                }
+                builder = Some(StatementBuilder::new(field.value.clone()));
+            }
+            _ => {
+                let Some(builder) = builder.as_mut() else {
+                    return Err(syntax(field.line, "statement must start with :20: field"));
+                };
+                builder.add(&field)?;
+            }
+        }
+    }
+    if let Some(builder) = builder {
+        statements.push(builder.build(text.lines().count())?);
+    }
+    Ok(statements)
+}
+
+/// Reads incomes from credit transactions of all statements.
+pub fn read_incomes(content: &[u8]) -> Result<Vec<Income>, Mt940Error> {
+    let statements = read_statements(content)?;
+    let mut incomes = vec![];
+    for statement in statements {
+        let currency = statement.opening_balance.currency;
+        for transaction in statement.transactions {
+            if transaction.mark != Mark::Credit {
+                continue;
+            }
+            incomes.push(transaction.into_income(currency)?);
+        }
+    }
+    incomes.sort();
+    Ok(incomes)
+}
+
+/// Splits the content into fields joining continuation lines.
+/// SWIFT block headers like `{1:...}{2:...}{4:` and trailers `-}` are skipped.
+fn fields(text: &str) -> Result<Vec<Field>, Mt940Error> {
+    let mut fields: Vec<Field> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let mut line = line.trim_end();
+        if line.starts_with('{') {
+            match line.find("{4:") {
+                Some(start) => line = &line[start + 3..],
+                None => continue,
+            }
+        }
+        if line.is_empty() || line == "-" || line == "-}" || line.starts_with("-}") {
+            continue;
+        }
+        if let Some((tag, value)) = tagged(line) {
+            fields.push(Field {
+                tag: tag.to_string(),
+                value: value.to_string(),
+                line: line_no,
+            });
+        } else {
+            let Some(field) = fields.last_mut() else {
+                return Err(syntax(line_no, "content before the first field"));
+            };
+            field.value.push('\n');
+            field.value.push_str(line);
+        }
+    }
+    Ok(fields)
+}
+
+/// Splits `:tag:value` line. Tags are two digits with an optional letter.
+fn tagged(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix(':')?;
+    let end = rest.find(':')?;
+    let tag = &rest[..end];
+    let valid_tag = tag.is_ascii()
+        && (2..=3).contains(&tag.len())
+        && tag[..2].chars().all(|c| c.is_ascii_digit())
+        && tag[2..].chars().all(|c| c.is_ascii_uppercase());
+    valid_tag.then(|| (tag, &rest[end + 1..]))
+}
+
+struct StatementBuilder {
+    reference: String,
+    account: String,
+    opening_balance: Option<Balance>,
+    closing_balance: Option<Balance>,
+    transactions: Vec<Transaction>,
+}
+
+impl StatementBuilder {
+    fn new(reference: String) -> Self {
+        Self {
+            reference,
+            account: String::new(),
+            opening_balance: None,
+            closing_balance: None,
+            transactions: vec![],
+        }
+    }
+
+    fn add(&mut self, field: &Field) -> Result<(), Mt940Error> {
+        match field.tag.as_str() {
+            "25" => self.account = field.value.clone(),
+            "60F" | "60M" => self.opening_balance = Some(parse_balance(field)?),
+            "62F" | "62M" => self.closing_balance = Some(parse_balance(field)?),
+            "61" => self.transactions.push(parse_transaction(field)?),
+            // the information belongs to the preceding transaction,
+            // or to the whole statement when it follows the closing balance
+            "86" if self.closing_balance.is_none() => {
+                if let Some(transaction) = self.transactions.last_mut() {
+                    transaction.information = Some(field.value.replace('\n', " "));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn build(self, line: usize) -> Result<Statement, Mt940Error> {
+        let opening_balance = self.opening_balance.ok_or_else(|| {
+            syntax(
+                line,
+                format!("statement {} has no opening balance", self.reference),
+            )
+        })?;
+        let closing_balance = self.closing_balance.ok_or_else(|| {
+            syntax(
+                line,
+                format!("statement {} has no closing balance", self.reference),
+            )
+        })?;
+        let turnover: i64 = self
+            .transactions
+            .iter()
+            .map(|transaction| transaction.signed_amount())
+            .sum();
+        let expected = opening_balance.amount + turnover;
+        if expected != closing_balance.amount {
+            return Err(Mt940Error::Unbalanced {
+                reference: self.reference,
+                opening: MinorUnits(opening_balance.amount),
+                expected: MinorUnits(expected),
+                closing: MinorUnits(closing_balance.amount),
+            });
+        }
+        Ok(Statement {
+            reference: self.reference,
+            account: self.account,
+            opening_balance,
+            closing_balance,
+            transactions: self.transactions,
+        })
+    }
+}
+
+/// Parses `C240301EUR1000,00` balance.
+fn parse_balance(field: &Field) -> Result<Balance, Mt940Error> {
+    let value = field.value.trim();
+    let invalid = || syntax(field.line, format!("invalid balance {:?}", value));
+    let sign = match value.get(..1) {
+        Some("C") => 1,
+        Some("D") => -1,
+        _ => return Err(invalid()),
+    };
+    let date = value.get(1..7).and_then(parse_date).ok_or_else(invalid)?;
+    let currency = value
+        .get(7..10)
+        .and_then(|code| Currency::new(code).ok())
+        .ok_or_else(invalid)?;
+    let amount = value.get(10..).and_then(parse_amount).ok_or_else(invalid)?;
+    Ok(Balance {
+        date,
+        currency,
+        amount: sign * amount,
+    })
+}
+
+/// Parses `:61:` statement line.
+///
+/// The format is `YYMMDD[MMDD]<mark>[funds code]<amount>N<type><customer ref>[//<bank ref>]`
+/// with optional supplementary details on the next line.
+fn parse_transaction(field: &Field) -> Result<Transaction, Mt940Error> {
+    let mut lines = field.value.lines();
+    let value = lines.next().unwrap_or_default().trim();
+    let invalid = |what: &str| syntax(field.line, format!("invalid {} in {:?}", what, value));
+
+    let value_date = value
+        .get(..6)
+        .and_then(parse_date)
+        .ok_or_else(|| invalid("value date"))?;
+    let mut rest = &value[6..];
+    let mut entry_date = None;
+    if let Some(month_day) = rest
+        .get(..4)
+        .filter(|month_day| month_day.chars().all(|c| c.is_ascii_digit()))
+    {
+        entry_date =
+            Some(parse_entry_date(value_date, month_day).ok_or_else(|| invalid("entry date"))?);
+        rest = &rest[4..];
+    }
+    let (mark, mark_len) = if rest.starts_with("RC") {
+        (Mark::ReversalOfCredit, 2)
+    } else if rest.starts_with("RD") {
+        (Mark::ReversalOfDebit, 2)
+    } else if rest.starts_with('C') {
+        (Mark::Credit, 1)
+    } else if rest.starts_with('D') {
+        (Mark::Debit, 1)
+    } else {
+        return Err(invalid("debit/credit mark"));
+    };
+    rest = &rest[mark_len..];
+    // optional funds code is the third character of the currency code
+    if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        rest = &rest[1..];
+    }
+    let amount_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ','))
+        .ok_or_else(|| invalid("transaction type"))?;
+    let amount = parse_amount(&rest[..amount_len]).ok_or_else(|| invalid("amount"))?;
+    rest = &rest[amount_len..];
+    // transaction type is a letter and three characters like NTRF
+    let references = rest.get(4..).ok_or_else(|| invalid("transaction type"))?;
+    let (customer_reference, bank_reference) = match references.split_once("//") {
+        Some((customer, bank)) => (customer, Some(bank.to_string())),
+        None => (references, None),
+    };
+    Ok(Transaction {
+        value_date,
+        entry_date,
+        mark,
+        amount,
+        customer_reference: customer_reference.to_string(),
+        bank_reference,
+        information: None,
+        line: field.line,
+    })
+}
+
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(raw, "%y%m%d").ok()
+}
+
+/// Parses `MMDD` entry date. Its year is the one putting it closest to the value date,
+/// as entries are booked around the turn of the year before or after the value date.
+fn parse_entry_date(value_date: NaiveDate, month_day: &str) -> Option<NaiveDate> {
+    let month = month_day[..2].parse().ok()?;
+    let day = month_day[2..].parse().ok()?;
+    (value_date.year() - 1..=value_date.year() + 1)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .min_by_key(|date| (*date - value_date).num_days().abs())
+}
+
+/// Parses `1000,00` amount into minor units.
+/// Amounts have up to 15 digits and no sign, the mark tells debits from credits.
+fn parse_amount(raw: &str) -> Option<i64> {
+    let (units, fraction) = raw.split_once(',')?;
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if units.is_empty()
+        || fraction.len() > 2
+        || units.len() + fraction.len() > MAX_AMOUNT_DIGITS
+        || !is_digits(units)
+        || !is_digits(fraction)
+    {
+        return None;
+    }
+    let units: i64 = units.parse().ok()?;
+    let fraction: i64 = format!("{:0<2}", fraction).parse().ok()?;
+    units.checked_mul(100)?.checked_add(fraction)
+}
+
+fn syntax(line: usize, message: impl Display) -> Mt940Error {
+    Mt940Error::Syntax {
+        line,
+        message: message.to_string(),
+    }
+}
+
+impl Transaction {
+    fn signed_amount(&self) -> i64 {
+        match self.mark {
+            Mark::Credit | Mark::ReversalOfDebit => self.amount,
+            Mark::Debit | Mark::ReversalOfCredit => -self.amount,
+        }
+    }
+
+    /// Reference of the transaction. The bank reference is preferred as it's unique.
+    pub fn reference(&self) -> Option<&str> {
+        self.bank_reference
+            .as_deref()
+            .or(Some(self.customer_reference.as_str()))
+            .filter(|reference| !reference.is_empty() && *reference != NO_REFERENCE)
+    }
+
+    fn into_income(self, currency: Currency) -> Result<Income, Mt940Error> {
+        let amount = Amount::new(self.amount as f64 / 100.0).map_err(|e| syntax(self.line, e))?;
+        let date = self
+            .entry_date
+            .unwrap_or(self.value_date)
+            .and_time(NaiveTime::MIN);
+        let mut income = Income::new(date, amount).with_currency(currency);
+        if let Some(information) = &self.information {
+            income = income.with_comment(information.clone());
+        }
+        if let Some(reference) = self.reference() {
+            income = income.with_source_ref(reference.to_string());
+        }
+        Ok(income)
+    }
+}
+
+impl Display for MinorUnits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(
+            f,
+            "{}{}.{:02}",
+            sign,
+            self.0.abs() / 100,
+            self.0.abs() % 100
+        )
+    }
+}
+
+impl StatementReader for Mt940StatementReader {
+    fn format(&self) -> &str {
+        "mt940"
+    }
+
+    fn detect(&self, content: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(content);
+        let has_field = |tag: &str| text.lines().any(|line| line.trim_start().starts_with(tag));
+        has_field(":20:") && (has_field(":60F:") || has_field(":60M:"))
+    }
+
+    fn read_incomes(&self, content: &[u8]) -> anyhow::Result<Vec<Income>> {
+        Ok(read_incomes(content)?)
+    }
+
+    /// Every statement covers its account from the opening to the closing balance date.
+    fn read_coverage(&self, content: &[u8]) -> anyhow::Result<Vec<StatementCoverage>> {
+        let coverages = read_statements(content)?
+            .into_iter()
+            .map(|statement| {
+                StatementCoverage::new(
+                    statement.account,
+                    statement.opening_balance.date,
+                    statement.closing_balance.date,
+                )
+            })
+            .collect();
+        Ok(coverages)
+    }
+}
@@ -0,0 +1,163 @@
+// Test import incomes from SWIFT MT940 statements.
+
+use chrono::NaiveDate;
+use monotax_core::domain::model::coverage::StatementCoverage;
+use monotax_core::domain::model::income::Amount;
+use monotax_core::domain::{Currency, Income};
+use monotax_core::integration::statement::StatementReader;
+use monotax_swift::mt940::{self, Mark, Mt940Error, Mt940StatementReader};
+
+fn income(year: i32, month: u32, day: u32, amount: f64) -> Income {
+    let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
+    Income::from_date(date, Amount::new(amount).unwrap())
+}
+
+fn statement() -> Vec<u8> {
+    std::fs::read("tests/test_files/statement.sta").unwrap()
+}
+
+#[test]
+fn parse_statements() {
+    let statements = mt940::read_statements(&statement()).unwrap();
+
+    assert_eq!(statements.len(), 2);
+    let march = &statements[0];
+    assert_eq!(march.reference, "STMT240301");
+    assert_eq!(march.account, "UA213223130000026007233566001");
+    assert_eq!(march.opening_balance.amount, 100000);
+    assert_eq!(march.closing_balance.amount, 466550);
+    assert_eq!(march.transactions.len(), 3);
+    assert_eq!(march.transactions[1].mark, Mark::Debit);
+    assert_eq!(
+        march.transactions[0].information.as_deref(),
+        Some("Payment for software development services, invoice 17")
+    );
+}
+
+#[test]
+fn import_credits() {
+    let incomes = Mt940StatementReader.read_incomes(&statement()).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![
+            income(2024, 3, 5, 2500.0),
+            income(2024, 3, 20, 1200.5),
+            income(2024, 4, 10, 800.0),
+        ]
+    );
+    assert_eq!(incomes[0].currency(), Currency::USD);
+    assert_eq!(incomes[0].source_ref(), Some("BR24030500001"));
+    assert_eq!(
+        incomes[0].comment(),
+        Some("Payment for software development services, invoice 17")
+    );
+    // no bank reference and the customer reference is not provided
+    assert_eq!(incomes[2].source_ref(), None);
+}
+
+#[test]
+fn detect_mt940_statement() {
+    assert!(Mt940StatementReader.detect(&statement()));
+    assert!(!Mt940StatementReader.detect(b"date;amount\n"));
+}
+
+#[test]
+fn reject_statement_with_missing_transactions() {
+    let content = String::from_utf8(statement())
+        .unwrap()
+        .replace(":61:2403060306D35,00NCHGNONREF//BR24030600002\n", "");
+
+    let error = mt940::read_statements(content.as_bytes()).unwrap_err();
+
+    assert!(
+        matches!(&error, Mt940Error::Unbalanced { reference, .. } if reference == "STMT240301"),
+        "unexpected error {}",
+        error
+    );
+    assert!(error.to_string().contains("4700.50"), "{}", error);
+}
+
+#[test]
+fn report_invalid_statement_line() {
+    let content =
+        b":20:REF\n:60F:C240301EUR0,00\n:61:24XX01C10,00NTRFNONREF\n:62F:C240331EUR10,00\n";
+
+    let error = mt940::read_statements(content).unwrap_err();
+
+    assert!(
+        matches!(error, Mt940Error::Syntax { line: 3, .. }),
+        "unexpected error {}",
+        error
+    );
+}
+
+#[test]
+fn reject_signed_and_oversized_amounts() {
+    for (line, content) in [
+        (2, ":20:REF\n:60F:C240301EUR-5,00\n:62F:C240331EUR0,00\n"),
+        (
+            3,
+            ":20:REF\n:60F:C240301EUR0,00\n:61:240301C92233720368547758,07NTRFNONREF\n:62F:C240331EUR0,00\n",
+        ),
+        (
+            3,
+            ":20:REF\n:60F:C240301EUR0,00\n:61:240301C12345678901234,56NTRFNONREF\n:62F:C240331EUR0,00\n",
+        ),
+    ] {
+        let error = mt940::read_statements(content.as_bytes()).unwrap_err();
+
+        assert!(
+            matches!(error, Mt940Error::Syntax { line: l, .. } if l == line),
+            "unexpected error {}",
+            error
+        );
+    }
+}
+
+#[test]
+fn read_statement_periods() {
+    let coverages = Mt940StatementReader.read_coverage(&statement()).unwrap();
+
+    let account = "UA213223130000026007233566001".to_string();
+    let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+    assert_eq!(
+        coverages,
+        vec![
+            StatementCoverage::new(account.clone(), date(3, 1), date(3, 31)),
+            StatementCoverage::new(account, date(4, 1), date(4, 30)),
+        ]
+    );
+}
+
+#[test]
+fn date_incomes_with_entry_date() {
+    let content = b":20:REF\n:25:ACC\n:60F:C231229EUR0,00\n\
+:61:2312290102C10,00NTRFNONREF\n\
+:61:240329C5,00NTRFNONREF\n\
+:62F:C240331EUR15,00\n";
+
+    let incomes = Mt940StatementReader.read_incomes(content).unwrap();
+
+    assert_eq!(
+        incomes,
+        vec![income(2024, 1, 2, 10.0), income(2024, 3, 29, 5.0)]
+    );
+}
+
+#[test]
+fn reject_malformed_multibyte_content() {
+    let cases = [
+        ":20:REF\n:1Ж:value\n",
+        ":20:REF\n:60F:C240301EUR0,00\n:61:240115aaaЖC10,00NTRFNONREF\n:62F:C240331EUR10,00\n",
+        ":20:REF\n:60F:C240301EUR0,00\n:61:240115ЖЖC10,00NTRFNONREF\n:62F:C240331EUR10,00\n",
+    ];
+
+    for content in cases {
+        assert!(
+            mt940::read_statements(content.as_bytes()).is_err(),
+            "{} must be rejected",
+            content
+        );
+    }
+}